@@ -281,6 +281,11 @@ class Main {
             assert eventHandler.x == i;
             assert eventHandler.s.equals(Integer.toString(i));
         }
+        // many callback invocations during one native call
+        // must not overflow JNI local references table
+        events.change_many_times(100_000, "many");
+        assert eventHandler.x == 100_000 - 1;
+        assert eventHandler.s.equals("many");
     }
 
     private static void testCallbacksMultiThread() throws InterruptedException {
@@ -509,6 +514,21 @@ class Main {
 	assert owned2.length == 1;
 	assert owned2[0].calcF(0, 0) == 17;
 	assert owned2[0].getName().equals("");
+
+	// conversion of big containers must not overflow JNI local references table
+	final int n = 100_000;
+	Foo[] big = TestContainers.makeBigStructVec(n);
+	assert big.length == n;
+	assert big[n - 1].calcF(0, 0) == n - 1;
+	assert TestContainers.sumStructVec(big) == (long) n * (n - 1) / 2;
+	String[] bigStrv = TestContainers.makeBigStringVec(n);
+	assert bigStrv.length == n;
+	assert bigStrv[n - 1].equals(Integer.toString(n - 1));
+	long totalLen = 0;
+	for (String s : bigStrv) {
+	    totalLen += s.length();
+	}
+	assert TestContainers.stringVecTotalLen(bigStrv) == totalLen;
     }
 
     private static void testNullString() {
@@ -36,6 +36,8 @@ fn main() {
 
     for include_path in &[
         Path::new("src/java_jni/jni-include.rs"),
+        Path::new("src/java_jni/jni-java-time-include.rs"),
+        Path::new("src/java_jni/jni-java-array-include.rs"),
         Path::new("src/cpp/cpp-include.rs"),
     ] {
         let src_cnt_tail = std::fs::read_to_string(include_path)
@@ -1,19 +1,43 @@
 use log::debug;
 use std::rc::Rc;
-use syn::{Type};
+use syn::Type;
 
 use crate::{
     error::*,
+    source_registry::SourceId,
     typemap::{
         ast::{self, TypeName},
         ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS, RustType},
         TypeConvCode, FROM_VAR_TEMPLATE,
     },
     types::{ForeignClassInfo, SelfTypeDesc},
-    TypeMap, SMART_PTR_COPY_TRAIT, source_registry::SourceId,
+    TypeMap, SMART_PTR_COPY_TRAIT,
 };
 
-pub(crate) fn register_class(conv_map: &mut TypeMap, class: &ForeignClassInfo) -> Result<()> {
+/// Register types for class, pointer to Rust object is passed through C ABI
+/// as opaque foreign type `handle_type`, like `IntPtr` for .NET
+pub(crate) fn register_class(
+    conv_map: &mut TypeMap,
+    class: &ForeignClassInfo,
+    handle_type: &str,
+) -> Result<()> {
+    register_class_with(conv_map, class, |conv_map, types_info| {
+        register_intermediate_pointer_types(conv_map, class, handle_type, types_info)?;
+        register_rust_ty_conversation_rules(conv_map, types_info)?;
+        register_main_foreign_types(conv_map, class, types_info)
+    })
+}
+
+/// Register self type of class with derived traits, then call
+/// `register_typemap` to add foreign types and conversation rules of backend
+pub(crate) fn register_class_with<F>(
+    conv_map: &mut TypeMap,
+    class: &ForeignClassInfo,
+    register_typemap: F,
+) -> Result<()>
+where
+    F: FnOnce(&mut TypeMap, &ClassTypesInfo) -> Result<()>,
+{
     class
         .validate_class()
         .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
@@ -34,18 +58,25 @@ pub(crate) fn register_class(conv_map: &mut TypeMap, class: &ForeignClassInfo) -
             traits.push(SMART_PTR_COPY_TRAIT);
         }
 
-        let self_type = conv_map.find_or_alloc_rust_type_that_implements(
-            self_ty,
-            &traits,
-            class.src_id,
-        );
+        let self_type =
+            conv_map.find_or_alloc_rust_type_that_implements(self_ty, &traits, class.src_id);
 
-        register_typemap_for_self_type(conv_map, class, self_type, self_desc)?;
+        let types_info = ClassTypesInfo::new(
+            conv_map,
+            class,
+            self_type,
+            self_desc,
+            (class.src_id, class.span()),
+        );
+        register_typemap(conv_map, &types_info)?;
     }
     conv_map.find_or_alloc_rust_type(&class.self_type_as_ty(), class.src_id);
     Ok(())
 }
 
+/// Check of pointer passed from foreign code for backends without way to report error
+pub(crate) const ASSERT_NOT_NULL: &str = "assert!(!{from_var}.is_null());";
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(crate) enum SmartPointerType {
     None,
@@ -64,8 +95,7 @@ impl SmartPointerType {
     ) -> SmartPointerType {
         if let Some(inner_ty) = ast::check_if_smart_pointer_return_inner_type(storage_type, "Arc") {
             let rust_inner_ty = conv_map.find_or_alloc_rust_type(&inner_ty, src_id);
-            if ast::check_if_smart_pointer_return_inner_type(&rust_inner_ty, "Mutex").is_some()
-            {
+            if ast::check_if_smart_pointer_return_inner_type(&rust_inner_ty, "Mutex").is_some() {
                 SmartPointerType::ArcMutex
             } else {
                 SmartPointerType::Arc
@@ -86,56 +116,72 @@ impl SmartPointerType {
         parse_type_spanned_checked!(span, *mut #storage_ty)
     }
 
-
     pub(crate) fn pointer_can_be_mutable(&self) -> bool {
         match self {
             SmartPointerType::None | SmartPointerType::Box => true,
-            SmartPointerType::Rc | SmartPointerType::Arc | SmartPointerType::ArcMutex | SmartPointerType::Mutex  => false,
+            SmartPointerType::Rc
+            | SmartPointerType::Arc
+            | SmartPointerType::ArcMutex
+            | SmartPointerType::Mutex => false,
         }
     }
 
     pub(crate) fn self_can_be_mutable(&self) -> bool {
         match self {
-            SmartPointerType::None | SmartPointerType::Box | SmartPointerType::ArcMutex | SmartPointerType::Mutex  => true,
+            SmartPointerType::None
+            | SmartPointerType::Box
+            | SmartPointerType::ArcMutex
+            | SmartPointerType::Mutex => true,
             SmartPointerType::Rc | SmartPointerType::Arc => false,
         }
     }
 
-    pub(crate) fn conversion_code_from_intermediate_to_self_ref(&self) -> String {
+    /// `null_check` is code that checks `{from_var}`, like `ASSERT_NOT_NULL`
+    pub(crate) fn conversion_code_from_intermediate_to_self_ref(&self, null_check: &str) -> String {
         let convert_code = match self {
-            SmartPointerType::None | SmartPointerType::Box  => "let {to_var} = unsafe { (&*{from_var}) };",
+            SmartPointerType::None | SmartPointerType::Box  => "let {to_var} = unsafe { &*{from_var} };",
             SmartPointerType::Rc | SmartPointerType::Arc => "let {to_var} = unsafe { (*{from_var}).as_ref() };",
             SmartPointerType::Mutex => "let {to_var}_lock = unsafe { (*{from_var}).lock().unwrap() }; let {to_var} = &*{to_var}_lock;",
             SmartPointerType::ArcMutex => "let {to_var}_lock = unsafe { (*{from_var}).lock().unwrap() }; let {to_var} = &*{to_var}_lock;",
         };
-        "assert!(!{from_var}.is_null());".to_owned() + convert_code
+        null_check.to_owned() + convert_code
     }
 
-    pub(crate) fn conversion_code_from_intermediate_to_self_mut_ref(&self) -> String {
+    pub(crate) fn conversion_code_from_intermediate_to_self_mut_ref(
+        &self,
+        null_check: &str,
+    ) -> String {
         let convert_code = match self {
-            SmartPointerType::None | SmartPointerType::Box  => "let {to_var} = unsafe { (&mut *{from_var}) };",
+            SmartPointerType::None | SmartPointerType::Box  => "let {to_var} = unsafe { &mut *{from_var} };",
             SmartPointerType::Rc | SmartPointerType::Arc => panic!("You can't deref_mut on `Arc` or `Rc` types."),
             SmartPointerType::Mutex => "let mut {to_var}_lock = unsafe { (*{from_var}).lock().unwrap() }; let {to_var} = &mut *{to_var}_lock;",
             SmartPointerType::ArcMutex => "let mut {to_var}_lock = unsafe { (*{from_var}).lock().unwrap() }; let {to_var} = & mut *{to_var}_lock;",
         };
-        "assert!(!{from_var}.is_null());".to_owned() + convert_code
+        null_check.to_owned() + convert_code
     }
 }
 
-struct ClassTypesInfo {
-    smart_pointer_type: SmartPointerType,
-    self_has_clone: bool,
-    self_type: RustType,
-    self_type_ref: RustType,
-    self_type_mut_ref: RustType,
-    storage_type: RustType,
-    storage_type_ref: RustType,
-    storage_type_mut_ref: RustType,
-    intermediate_ptr_type: RustType,
+pub(crate) struct ClassTypesInfo {
+    pub(crate) smart_pointer_type: SmartPointerType,
+    pub(crate) self_has_clone: bool,
+    pub(crate) self_type: RustType,
+    pub(crate) self_type_ref: RustType,
+    pub(crate) self_type_mut_ref: RustType,
+    pub(crate) storage_type: RustType,
+    pub(crate) storage_type_ref: RustType,
+    pub(crate) storage_type_mut_ref: RustType,
+    /// `*mut storage_type`
+    pub(crate) intermediate_ptr_type: RustType,
 }
 
 impl ClassTypesInfo {
-    fn new(conv_map: &mut TypeMap, class: &ForeignClassInfo, self_type: RustType, self_desc: &SelfTypeDesc, source_span: SourceIdSpan) -> Self {
+    fn new(
+        conv_map: &mut TypeMap,
+        class: &ForeignClassInfo,
+        self_type: RustType,
+        self_desc: &SelfTypeDesc,
+        source_span: SourceIdSpan,
+    ) -> Self {
         let src_id = source_span.0;
         let span = source_span.1;
         let self_ty = &self_type.ty;
@@ -149,13 +195,13 @@ impl ClassTypesInfo {
 
         let self_ty_ref = parse_type_spanned_checked!(span, & #self_ty);
         let self_type_ref = conv_map.find_or_alloc_rust_type(&self_ty_ref, src_id);
-    
+
         let self_ty_mut_ref = parse_type_spanned_checked!(span, &mut #self_ty);
         let self_type_mut_ref = conv_map.find_or_alloc_rust_type(&self_ty_mut_ref, src_id);
 
         let storage_ty_ref = parse_type_spanned_checked!(span, & #storage_ty);
         let storage_type_ref = conv_map.find_or_alloc_rust_type(&storage_ty_ref, src_id);
-    
+
         let storage_ty_mut_ref = parse_type_spanned_checked!(span, &mut #storage_ty);
         let storage_type_mut_ref = conv_map.find_or_alloc_rust_type(&storage_ty_mut_ref, src_id);
 
@@ -172,50 +218,24 @@ impl ClassTypesInfo {
         }
     }
 
-    fn storage_has_clone(&self) -> bool {
+    pub(crate) fn storage_has_clone(&self) -> bool {
         match self.smart_pointer_type {
             SmartPointerType::None | SmartPointerType::Box => self.self_has_clone,
             SmartPointerType::Rc | SmartPointerType::Arc | SmartPointerType::ArcMutex => true,
-            SmartPointerType::Mutex => false
+            SmartPointerType::Mutex => false,
         }
     }
 }
 
-
-fn register_typemap_for_self_type(
-    conv_map: &mut TypeMap,
-    class: &ForeignClassInfo,
-    self_type: RustType,
-    self_desc: &SelfTypeDesc,
-) -> Result<()> {
-    let types_info = ClassTypesInfo::new(conv_map, class, self_type, self_desc, (class.src_id, class.span()));
-
-    register_intermediate_pointer_types(
-        conv_map,
-        class,
-        &types_info,
-    )?;
-    register_rust_ty_conversation_rules(
-        conv_map,
-        &types_info,
-    )?;
-
-    register_main_foreign_types(
-        conv_map,
-        class,
-        &types_info,
-    )?;
-    Ok(())
-}
-
 fn register_intermediate_pointer_types(
     conv_map: &mut TypeMap,
     class: &ForeignClassInfo,
+    handle_type: &str,
     types_info: &ClassTypesInfo,
 ) -> Result<()> {
     let c_ftype = ForeignTypeS {
         name: TypeName::new(
-            format!("/* {} */ IntPtr", class.name),
+            format!("/* {} */ {}", class.name, handle_type),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![],
@@ -243,7 +263,9 @@ fn register_rust_ty_conversation_rules(
         types_info.intermediate_ptr_type.to_idx(),
         types_info.self_type_ref.to_idx(),
         TypeConvCode::new2(
-            types_info.smart_pointer_type.conversion_code_from_intermediate_to_self_ref(),
+            types_info
+                .smart_pointer_type
+                .conversion_code_from_intermediate_to_self_ref(ASSERT_NOT_NULL),
             invalid_src_id_span(),
         )
         .into(),
@@ -255,7 +277,9 @@ fn register_rust_ty_conversation_rules(
             types_info.intermediate_ptr_type.to_idx(),
             types_info.self_type_mut_ref.to_idx(),
             TypeConvCode::new2(
-                types_info.smart_pointer_type.conversion_code_from_intermediate_to_self_mut_ref(),
+                types_info
+                    .smart_pointer_type
+                    .conversion_code_from_intermediate_to_self_mut_ref(ASSERT_NOT_NULL),
                 invalid_src_id_span(),
             )
             .into(),
@@ -279,7 +303,7 @@ fn register_rust_ty_conversation_rules(
             types_info.intermediate_ptr_type.to_idx(),
             types_info.storage_type_ref.to_idx(),
             TypeConvCode::new2(
-                    r#"
+                r#"
         assert!(!{from_var}.is_null());
         let {to_var} = unsafe { &*{from_var} };
     "#,
@@ -289,13 +313,15 @@ fn register_rust_ty_conversation_rules(
         );
     }
 
-    if types_info.smart_pointer_type.pointer_can_be_mutable() && types_info.smart_pointer_type != SmartPointerType::None {
+    if types_info.smart_pointer_type.pointer_can_be_mutable()
+        && types_info.smart_pointer_type != SmartPointerType::None
+    {
         // intermediate_type -> &mut storage_type
         conv_map.add_conversation_rule(
             types_info.intermediate_ptr_type.to_idx(),
             types_info.storage_type_mut_ref.to_idx(),
             TypeConvCode::new2(
-                    r#"
+                r#"
         assert!(!{from_var}.is_null());
         let {to_var} = unsafe { &mut *{from_var} };
     "#,
@@ -335,11 +361,13 @@ fn register_rust_ty_conversation_rules(
     if types_info.self_has_clone && types_info.smart_pointer_type != SmartPointerType::None {
         // intermediate_ptr_type -> self_type
         conv_map.add_conversation_rule(
-            types_info.storage_type_ref.to_idx(),
-            types_info.storage_type.to_idx(),
+            types_info.intermediate_ptr_type.to_idx(),
+            types_info.self_type.to_idx(),
             TypeConvCode::new2(
-                types_info.smart_pointer_type.conversion_code_from_intermediate_to_self_ref().to_owned() + 
-                    "let {to_var} = {to_var}.clone()",
+                types_info
+                    .smart_pointer_type
+                    .conversion_code_from_intermediate_to_self_ref(ASSERT_NOT_NULL)
+                    + "let {to_var} = {to_var}.clone();",
                 invalid_src_id_span(),
             )
             .into(),
@@ -391,7 +419,10 @@ fn register_main_foreign_types(
     conv_map.alloc_foreign_type(class_ftype)?;
 
     let class_ftype_ref = ForeignTypeS {
-        name: TypeName::new(format!("/* ref */ {}", class.name.to_string()), (class.src_id, class.name.span())),
+        name: TypeName::new(
+            format!("/* ref */ {}", class.name),
+            (class.src_id, class.name.span()),
+        ),
         provides_by_module: vec![],
         into_from_rust: Some(ForeignConversationRule {
             rust_ty: types_info.storage_type_ref.to_idx(),
@@ -0,0 +1,528 @@
+//! Mapping of Rust types of method arguments to foreign types via `TypeMap`,
+//! including instantiation of generic `foreign_typemap!` rules
+
+use log::{debug, info};
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use rustc_hash::FxHashSet;
+use smol_str::SmolStr;
+use std::rc::Rc;
+use syn::{spanned::Spanned, Type};
+
+use super::CAbiContext;
+use crate::{
+    error::*,
+    typemap::{
+        ast::{DisplayToTokens, TyParamsSubstList},
+        ty::{ForeignTypeS, RustType, TraitNamesSet},
+        utils::{self, ForeignMethodSignature, ForeignTypeInfoT},
+        CItem, ExpandedFType, MapToForeignFlag, TypeMap, TypeMapConvRuleInfo,
+        TypeMapConvRuleInfoExpanderHelper, FROM_VAR_TEMPLATE,
+    },
+    types::{FnArg, ForeignClassInfo, ForeignMethod, MethodVariant, SelfTypeVariant},
+};
+
+pub(crate) struct CAbiForeignMethodSignature {
+    pub(crate) output: CAbiArgInfo,
+    pub(crate) input: Vec<CAbiArgInfo>,
+    pub(crate) name: String,
+    pub(crate) variant: MethodVariant,
+    pub(crate) rust_function_call: String,
+}
+
+impl ForeignMethodSignature for CAbiForeignMethodSignature {
+    type FI = CAbiArgInfo;
+    fn output(&self) -> &dyn ForeignTypeInfoT {
+        &self.output
+    }
+    fn input(&self) -> &[CAbiArgInfo] {
+        &self.input[..]
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum ArgName {
+    SelfArg,
+    Named(SmolStr),
+    Return,
+}
+
+impl ArgName {
+    pub(crate) fn rust_variable_name(&self) -> &str {
+        match self {
+            ArgName::SelfArg => "this",
+            ArgName::Return => "ret",
+            ArgName::Named(name) => name,
+        }
+    }
+}
+
+pub(crate) struct CAbiArgInfo {
+    pub(crate) type_info: CAbiTypeInfo,
+    pub(crate) arg_name: ArgName,
+    pub(crate) span: SourceIdSpan,
+}
+
+impl CAbiArgInfo {
+    pub(crate) fn new(type_info: CAbiTypeInfo, arg_name: ArgName, span: SourceIdSpan) -> Self {
+        Self {
+            type_info,
+            arg_name,
+            span,
+        }
+    }
+
+    pub(crate) fn rust_conversion_code(
+        &self,
+        conv_map: &mut TypeMap,
+    ) -> Result<(Vec<TokenStream>, String)> {
+        let (from, to) = match self.direction() {
+            Direction::Incoming => (
+                self.type_info.rust_intermediate_type.to_idx(),
+                self.type_info.rust_type.to_idx(),
+            ),
+            Direction::Outgoing => (
+                self.type_info.rust_type.to_idx(),
+                self.type_info.rust_intermediate_type.to_idx(),
+            ),
+        };
+        conv_map.convert_rust_types(
+            from,
+            to,
+            self.arg_name.rust_variable_name(),
+            self.arg_name.rust_variable_name(),
+            "()",
+            self.span,
+        )
+    }
+
+    pub(crate) fn direction(&self) -> Direction {
+        if self.arg_name == ArgName::Return {
+            Direction::Outgoing
+        } else {
+            Direction::Incoming
+        }
+    }
+}
+
+pub(crate) struct CAbiTypeInfo {
+    pub(crate) foreign_type: SmolStr,
+    pub(crate) rust_type: RustType,
+    pub(crate) foreign_intermediate_type: SmolStr,
+    pub(crate) rust_intermediate_type: RustType,
+    pub(crate) foreign_conversion_code: String,
+}
+
+impl ForeignTypeInfoT for CAbiArgInfo {
+    fn name(&self) -> &str {
+        &self.type_info.foreign_type
+    }
+    fn correspoding_rust_type(&self) -> &RustType {
+        &self.type_info.rust_type
+    }
+}
+
+impl CAbiTypeInfo {
+    pub(crate) fn new_primitive(foreign_type: SmolStr, rust_type: RustType) -> Self {
+        Self {
+            foreign_intermediate_type: foreign_type.clone(),
+            rust_intermediate_type: rust_type.clone(),
+            foreign_type,
+            rust_type,
+            foreign_conversion_code: String::new(),
+        }
+    }
+}
+
+fn calc_this_type_for_method(_: &TypeMap, class: &ForeignClassInfo) -> Option<Type> {
+    class
+        .self_desc
+        .as_ref()
+        .map(|x| x.constructor_ret_type.clone())
+}
+
+pub(crate) fn make_foreign_method_signature(
+    ctx: &mut CAbiContext,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+) -> Result<CAbiForeignMethodSignature> {
+    let dummy_ty = parse_type! { () };
+    let dummy_rust_ty = ctx.conv_map.find_or_alloc_rust_type_no_src_id(&dummy_ty);
+
+    let input = method
+        .fn_decl
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Default(named_arg) => Ok(CAbiArgInfo::new(
+                map_type(
+                    ctx,
+                    &named_arg.ty,
+                    Direction::Incoming,
+                    (class.src_id, named_arg.span),
+                )?,
+                ArgName::Named(named_arg.name.clone()),
+                (class.src_id, named_arg.span),
+            )),
+            FnArg::SelfArg(span_ref, self_variant) => {
+                let span = *span_ref;
+                let self_ty = class
+                    .self_desc
+                    .as_ref()
+                    .ok_or_else(|| {
+                        DiagnosticError::new(
+                            class.src_id,
+                            span,
+                            "Non-static methods can only be defined for a class \
+                             that have a constructor (at least private empty one)",
+                        )
+                    })?
+                    .self_type
+                    .clone();
+                let self_ty_full = match self_variant {
+                    SelfTypeVariant::Rptr => parse_type_spanned_checked!(span, & #self_ty),
+                    SelfTypeVariant::RptrMut => parse_type_spanned_checked!(span, &mut #self_ty),
+                    SelfTypeVariant::Default | SelfTypeVariant::Mut => {
+                        parse_type_spanned_checked!(span, #self_ty)
+                    }
+                };
+                Ok(CAbiArgInfo::new(
+                    map_type(
+                        ctx,
+                        &self_ty_full,
+                        Direction::Incoming,
+                        (class.src_id, span),
+                    )?,
+                    ArgName::SelfArg,
+                    (class.src_id, span),
+                ))
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output_span = (class.src_id, method.fn_decl.output.span());
+    let output = match method.fn_decl.output {
+        syn::ReturnType::Default => CAbiArgInfo::new(
+            CAbiTypeInfo::new_primitive("void".into(), dummy_rust_ty),
+            ArgName::Return,
+            output_span,
+        ),
+        syn::ReturnType::Type(_, ref ty) => CAbiArgInfo::new(
+            map_type(ctx, ty, Direction::Outgoing, output_span)?,
+            ArgName::Return,
+            output_span,
+        ),
+    };
+    Ok(CAbiForeignMethodSignature {
+        input,
+        output,
+        name: method.short_name(),
+        variant: method.variant,
+        rust_function_call: method.generate_code_to_call_rust_func(),
+    })
+}
+
+fn map_type(
+    ctx: &mut CAbiContext,
+    ty: &Type,
+    direction: Direction,
+    span: SourceIdSpan,
+) -> Result<CAbiTypeInfo> {
+    let rust_ty = ctx.conv_map.find_or_alloc_rust_type(ty, span.0);
+
+    let foreign_type = find_foreign_type(ctx, &rust_ty, direction, span)?;
+    let rule = match direction {
+        Direction::Outgoing => foreign_type.into_from_rust.as_ref(),
+        Direction::Incoming => foreign_type.from_into_rust.as_ref(),
+    }
+    .ok_or_else(|| {
+        DiagnosticError::new2(
+            span,
+            format!(
+                "No rule to convert foreign type {} as input/output type",
+                foreign_type.name
+            ),
+        )
+    })?;
+
+    if let Some(intermediate) = rule.intermediate.as_ref() {
+        let intermediate_rust_type = ctx.conv_map[intermediate.intermediate_ty].clone();
+        let intermediate_foreign_type =
+            find_foreign_type(ctx, &intermediate_rust_type, direction, span)?;
+        Ok(CAbiTypeInfo {
+            foreign_type: foreign_type.typename(),
+            rust_type: rust_ty,
+            foreign_intermediate_type: intermediate_foreign_type.typename(),
+            rust_intermediate_type: intermediate_rust_type,
+            foreign_conversion_code: intermediate.conv_code.to_string(),
+        })
+    } else {
+        let correspoding_rust_type = ctx.conv_map[rule.rust_ty].clone();
+        Ok(CAbiTypeInfo {
+            foreign_type: foreign_type.typename(),
+            rust_type: rust_ty,
+            foreign_intermediate_type: foreign_type.typename(),
+            rust_intermediate_type: correspoding_rust_type,
+            foreign_conversion_code: FROM_VAR_TEMPLATE.to_owned(),
+        })
+    }
+}
+
+fn find_foreign_type(
+    ctx: &mut CAbiContext,
+    rust_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<ForeignTypeS> {
+    if let Some(foreign_type_idx) = ctx.conv_map.map_through_conversation_to_foreign(
+        rust_ty.to_idx(),
+        direction,
+        MapToForeignFlag::FastSearch,
+        arg_ty_span,
+        calc_this_type_for_method,
+    ) {
+        return Ok(ctx.conv_map[foreign_type_idx].clone());
+    }
+
+    let idx_subst_map: Option<(Rc<_>, TyParamsSubstList)> =
+        ctx.conv_map.generic_rules().iter().find_map(|grule| {
+            grule
+                .is_ty_subst_of_my_generic_rtype(&rust_ty.ty, direction, |ty, traits| -> bool {
+                    is_ty_implement_traits(ctx.conv_map, ty, traits)
+                })
+                .map(|sm| (grule.clone(), sm.into()))
+        });
+    if let Some((grule, subst_list)) = idx_subst_map {
+        info!(
+            "find_foreign_type: we found generic rule for {}: {:?}",
+            rust_ty, subst_list
+        );
+        let subst_map = subst_list.as_slice().into();
+        let c_types = grule
+            .subst_generic_params_to_c_items(
+                &subst_map,
+                &mut CAbiGenericParamExpander {
+                    ctx,
+                    arg_ty_span,
+                    direction,
+                },
+            )
+            .map_err(|err| {
+                err.add_span_note(
+                    (grule.src_id, grule.span),
+                    "subst. of generic params in define_c_type failed",
+                )
+            })?;
+        if let Some(c_items) = c_types {
+            if ctx
+                .known_c_items_modules
+                .insert(c_items.header_name.clone())
+            {
+                for c_item in c_items.items {
+                    if let CItem::Fn(fn_item) = c_item {
+                        ctx.rust_code.push(fn_item.into_token_stream())
+                    }
+                }
+            }
+        }
+        let new_rule = grule
+            .subst_generic_params(
+                subst_map,
+                direction,
+                &mut CAbiGenericParamExpander {
+                    ctx,
+                    arg_ty_span,
+                    direction,
+                },
+            )
+            .map_err(|err| {
+                err.add_span_note(
+                    (grule.src_id, grule.span),
+                    "subst. of generic params into rule failed",
+                )
+            })?;
+        debug_assert!(!new_rule.is_empty());
+        merge_rule(ctx, new_rule)?;
+    }
+
+    if let Some(foreign_type_idx) = ctx.conv_map.map_through_conversation_to_foreign(
+        rust_ty.to_idx(),
+        direction,
+        MapToForeignFlag::FullSearch,
+        arg_ty_span,
+        calc_this_type_for_method,
+    ) {
+        return Ok(ctx.conv_map[foreign_type_idx].clone());
+    }
+
+    Err(DiagnosticError::new2(
+        arg_ty_span,
+        format!(
+            "Do not know conversation from foreign language to such rust type '{}'",
+            rust_ty
+        ),
+    ))
+}
+
+fn is_ty_implement_traits(tmap: &TypeMap, ty: &syn::Type, traits: &TraitNamesSet) -> bool {
+    if let Some(rty) = tmap.ty_to_rust_type_checked(ty) {
+        for tname in traits.iter() {
+            if tname.is_ident("SwigTypeIsReprC") {
+                if tmap
+                    .find_foreign_type_related_to_rust_ty(rty.to_idx())
+                    .is_none()
+                {
+                    return false;
+                }
+            } else if !rty.implements.contains_path(tname) {
+                return false;
+            }
+        }
+        true
+    } else {
+        println!(
+            "cargo:warning=mapping types: type {} unknown",
+            DisplayToTokens(ty)
+        );
+        false
+    }
+}
+
+fn merge_rule(ctx: &mut CAbiContext, mut rule: TypeMapConvRuleInfo) -> Result<()> {
+    debug!("merge_rule begin {:?}", rule);
+    if rule.is_empty() {
+        return Err(DiagnosticError::new(
+            rule.src_id,
+            rule.span,
+            format!("rule {:?} is empty", rule),
+        ));
+    }
+
+    for f_code in rule.f_code.drain(..) {
+        ctx.additional_foreign_code
+            .entry(f_code.module_name.clone())
+            .or_insert(f_code.code);
+    }
+
+    let options = FxHashSet::default();
+    utils::configure_ftype_rule(&mut rule.ftype_left_to_right, "=>", rule.src_id, &options)?;
+    utils::configure_ftype_rule(&mut rule.ftype_right_to_left, "<=", rule.src_id, &options)?;
+
+    ctx.conv_map.merge_conv_rule(rule.src_id, rule)?;
+    Ok(())
+}
+
+struct CAbiGenericParamExpander<'a, 'b> {
+    ctx: &'a mut CAbiContext<'b>,
+    arg_ty_span: SourceIdSpan,
+    direction: Direction,
+}
+
+impl<'a, 'b> CAbiGenericParamExpander<'a, 'b> {
+    fn arg_direction(&self, param1: Option<&str>) -> Result<Direction> {
+        match param1 {
+            Some("output") => Ok(Direction::Outgoing),
+            Some("input") => Ok(Direction::Incoming),
+            None => Ok(self.direction),
+            Some(param) => Err(DiagnosticError::new2(
+                self.arg_ty_span,
+                format!("Invalid argument '{}' for swig_f_type", param),
+            )),
+        }
+    }
+
+    fn convert_rust_types(
+        &mut self,
+        ty: &syn::Type,
+        direction: Direction,
+        in_var_name: &str,
+        out_var_name: &str,
+    ) -> Result<String> {
+        let rust_ty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(ty, self.arg_ty_span.0);
+        let type_info = map_type(self.ctx, &rust_ty.ty, direction, self.arg_ty_span)?;
+        let (from, to) = match direction {
+            Direction::Outgoing => (
+                type_info.rust_type.to_idx(),
+                type_info.rust_intermediate_type.to_idx(),
+            ),
+            Direction::Incoming => (
+                type_info.rust_intermediate_type.to_idx(),
+                type_info.rust_type.to_idx(),
+            ),
+        };
+        let (mut conv_deps, conv_code) = self.ctx.conv_map.convert_rust_types(
+            from,
+            to,
+            in_var_name,
+            out_var_name,
+            "#error",
+            self.arg_ty_span,
+        )?;
+        self.ctx.rust_code.append(&mut conv_deps);
+        Ok(conv_code)
+    }
+}
+
+impl<'a, 'b> TypeMapConvRuleInfoExpanderHelper for CAbiGenericParamExpander<'a, 'b> {
+    fn swig_i_type(&mut self, ty: &syn::Type, opt_arg: Option<&str>) -> Result<syn::Type> {
+        let rust_ty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(ty, self.arg_ty_span.0);
+        let direction = self.arg_direction(opt_arg)?;
+        let type_info = map_type(self.ctx, &rust_ty.ty, direction, self.arg_ty_span)?;
+        Ok(type_info.rust_intermediate_type.ty.clone())
+    }
+    fn swig_from_rust_to_i_type(
+        &mut self,
+        ty: &syn::Type,
+        in_var_name: &str,
+        out_var_name: &str,
+    ) -> Result<String> {
+        self.convert_rust_types(ty, Direction::Outgoing, in_var_name, out_var_name)
+    }
+    fn swig_from_i_type_to_rust(
+        &mut self,
+        ty: &syn::Type,
+        in_var_name: &str,
+        out_var_name: &str,
+    ) -> Result<String> {
+        self.convert_rust_types(ty, Direction::Incoming, in_var_name, out_var_name)
+    }
+    fn swig_f_type(&mut self, ty: &syn::Type, param1: Option<&str>) -> Result<ExpandedFType> {
+        let rust_ty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(ty, self.arg_ty_span.0);
+        let direction = self.arg_direction(param1)?;
+        let type_info = map_type(self.ctx, &rust_ty.ty, direction, self.arg_ty_span)?;
+        Ok(ExpandedFType {
+            name: type_info.foreign_type,
+            provides_by_module: vec![],
+        })
+    }
+    fn swig_foreign_to_i_type(&mut self, ty: &syn::Type, var_name: &str) -> Result<String> {
+        let rust_ty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(ty, self.arg_ty_span.0);
+        let type_info = map_type(self.ctx, &rust_ty.ty, Direction::Incoming, self.arg_ty_span)?;
+        Ok(type_info
+            .foreign_conversion_code
+            .replace(FROM_VAR_TEMPLATE, var_name))
+    }
+    fn swig_foreign_from_i_type(&mut self, ty: &syn::Type, var_name: &str) -> Result<String> {
+        let rust_ty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(ty, self.arg_ty_span.0);
+        let type_info = map_type(self.ctx, &rust_ty.ty, Direction::Outgoing, self.arg_ty_span)?;
+        Ok(type_info
+            .foreign_conversion_code
+            .replace(FROM_VAR_TEMPLATE, var_name))
+    }
+}
@@ -0,0 +1,33 @@
+//! Common code for backends that export plain C ABI functions from Rust
+//! and call them from foreign language without C/C++ glue code (.NET, Java Panama).
+//! Objects of classes are passed as opaque pointers, other types
+//! are mapped via rules from `TypeMap`, foreign code of rules
+//! is collected in `CAbiContext::additional_foreign_code`.
+
+pub(crate) mod classes;
+pub(crate) mod map_type;
+
+use proc_macro2::TokenStream;
+use rustc_hash::{FxHashMap, FxHashSet};
+use smol_str::SmolStr;
+
+use crate::TypeMap;
+
+pub(crate) struct CAbiContext<'a> {
+    pub(crate) conv_map: &'a mut TypeMap,
+    pub(crate) rust_code: Vec<TokenStream>,
+    /// Foreign code of typemap rules, module name -> code
+    pub(crate) additional_foreign_code: FxHashMap<SmolStr, String>,
+    pub(crate) known_c_items_modules: FxHashSet<SmolStr>,
+}
+
+impl<'a> CAbiContext<'a> {
+    pub(crate) fn new(conv_map: &'a mut TypeMap) -> Self {
+        CAbiContext {
+            conv_map,
+            rust_code: Vec::new(),
+            additional_foreign_code: FxHashMap::default(),
+            known_c_items_modules: FxHashSet::default(),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+thread_local! {
+    static C_API_LAST_ERROR: ::std::cell::RefCell<Option<::std::ffi::CString>> =
+        ::std::cell::RefCell::new(None);
+}
+
+/// Remember error of current call for current thread,
+/// caller gets it via `<prefix>take_last_error`
+#[allow(dead_code)]
+fn c_api_set_last_error(msg: &str) {
+    let msg = ::std::ffi::CString::new(msg.replace('\0', "\\0")).unwrap_or_default();
+    C_API_LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(msg));
+}
+
+#[allow(dead_code)]
+fn c_api_clear_last_error() {
+    C_API_LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+#[allow(dead_code)]
+fn c_api_take_last_error() -> *mut ::std::ffi::c_char {
+    match C_API_LAST_ERROR.with(|last_error| last_error.borrow_mut().take()) {
+        Some(msg) => msg.into_raw(),
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+/// Value to return from function in case of error
+#[allow(dead_code)]
+trait CApiInvalidValue {
+    fn c_api_invalid_value() -> Self;
+}
+
+impl<T> CApiInvalidValue for *const T {
+    fn c_api_invalid_value() -> Self {
+        ::std::ptr::null()
+    }
+}
+
+impl<T> CApiInvalidValue for *mut T {
+    fn c_api_invalid_value() -> Self {
+        ::std::ptr::null_mut()
+    }
+}
+
+impl CApiInvalidValue for () {
+    fn c_api_invalid_value() -> Self {}
+}
+
+macro_rules! impl_c_api_invalid_value {
+    ($($t:ty)*) => ($(
+        impl CApiInvalidValue for $t {
+            fn c_api_invalid_value() -> Self {
+                <$t>::default()
+            }
+        }
+    )*)
+}
+
+impl_c_api_invalid_value! { bool i8 u8 i16 u16 i32 u32 i64 u64 isize usize f32 f64 }
+
+foreign_typemap!(
+    (r_type) ();
+    (f_type) "void";
+);
+
+foreign_typemap!(
+    (r_type) ::std::ffi::c_char;
+    (f_type) "char";
+);
+
+foreign_typemap!(
+    (r_type) bool;
+    (f_type) "bool";
+);
+
+foreign_typemap!(
+    (r_type) f32;
+    (f_type) "float";
+);
+
+foreign_typemap!(
+    (r_type) f64;
+    (f_type) "double";
+);
+
+foreign_typemap!(
+    (r_type) i8;
+    (f_type) "int8_t";
+);
+
+foreign_typemap!(
+    (r_type) u8;
+    (f_type) "uint8_t";
+);
+
+foreign_typemap!(
+    (r_type) i16;
+    (f_type) "int16_t";
+);
+
+foreign_typemap!(
+    (r_type) u16;
+    (f_type) "uint16_t";
+);
+
+foreign_typemap!(
+    (r_type) i32;
+    (f_type) "int32_t";
+);
+
+foreign_typemap!(
+    (r_type) u32;
+    (f_type) "uint32_t";
+);
+
+foreign_typemap!(
+    (r_type) i64;
+    (f_type) "int64_t";
+);
+
+foreign_typemap!(
+    (r_type) u64;
+    (f_type) "uint64_t";
+);
+
+foreign_typemap!(
+    (r_type) usize;
+    (f_type) "uintptr_t";
+);
+
+foreign_typemap!(
+    (r_type) isize;
+    (f_type) "intptr_t";
+);
+
+foreign_typemap!(
+    (r_type) *const ::std::ffi::c_char;
+    (f_type) "const char *";
+);
+
+foreign_typemap!(
+    (r_type) *mut ::std::ffi::c_char;
+    (f_type) "char *";
+);
+
+foreign_typemap!(
+    ($p:r_type) String => *mut ::std::ffi::c_char {
+        $out = match ::std::ffi::CString::new($p) {
+            Ok(x) => x.into_raw(),
+            Err(_) => {
+                c_api_set_last_error("String with nul byte can not be passed to C");
+                return CApiInvalidValue::c_api_invalid_value();
+            }
+        };
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) &str <= *const ::std::ffi::c_char {
+        if $p.is_null() {
+            c_api_set_last_error("NULL passed as string");
+            return CApiInvalidValue::c_api_invalid_value();
+        }
+        $out = match unsafe { ::std::ffi::CStr::from_ptr($p) }.to_str() {
+            Ok(x) => x,
+            Err(_) => {
+                c_api_set_last_error("invalid utf-8 in string from C");
+                return CApiInvalidValue::c_api_invalid_value();
+            }
+        };
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) &str => String {
+        $out = $p.to_owned();
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) String => &str {
+        $out = & $p;
+    };
+);
+
+#[allow(dead_code)]
+pub trait SwigForeignEnum: Sized {
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
+
+/// Value of flags created by `bitflags!` is passed as its bits
+#[allow(dead_code)]
+pub trait SwigForeignBitflags: Sized {
+    /// `None` if there are unknown bits
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
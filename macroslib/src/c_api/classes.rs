@@ -0,0 +1,172 @@
+use syn::Type;
+
+use crate::{
+    c_abi::classes::{self as c_abi_classes, ClassTypesInfo, SmartPointerType},
+    error::*,
+    typemap::{
+        ast::TypeName,
+        ty::{ForeignConversationRule, ForeignTypeS, RustType},
+        TypeConvCode,
+    },
+    types::ForeignClassInfo,
+    TypeMap,
+};
+
+/// Register types for class, C code gets pointers to Rust objects:
+/// `const {Class} *` for borrowing and `{Class} *` for ownership or mutable borrowing
+pub(in crate::c_api) fn register_class(
+    conv_map: &mut TypeMap,
+    class: &ForeignClassInfo,
+    symbol_prefix: &str,
+) -> Result<()> {
+    c_abi_classes::register_class_with(conv_map, class, |conv_map, types_info| {
+        let storage_ty = &types_info.storage_type.ty;
+        let const_ptr_type =
+            conv_map.find_or_alloc_rust_type(&const_ptr_ty(storage_ty), class.src_id);
+        register_pointer_foreign_types(
+            conv_map,
+            class,
+            types_info,
+            &const_ptr_type,
+            symbol_prefix,
+        )?;
+        register_rust_ty_conversation_rules(conv_map, types_info, &const_ptr_type);
+        Ok(())
+    })
+}
+
+/// Report error instead of dereference of NULL passed from C code
+const NULL_CHECK: &str = r#"if {from_var}.is_null() { c_api_set_last_error("NULL passed as object"); return CApiInvalidValue::c_api_invalid_value(); }"#;
+
+/// Type of pointer that owns object or used for mutable borrowing
+pub(in crate::c_api) fn owned_ptr_ty(storage_ty: &Type) -> Type {
+    let span = invalid_src_id_span().1;
+    parse_type_spanned_checked!(span, *mut #storage_ty)
+}
+
+/// Type of pointer that used for shared borrowing
+fn const_ptr_ty(storage_ty: &Type) -> Type {
+    let span = invalid_src_id_span().1;
+    parse_type_spanned_checked!(span, *const #storage_ty)
+}
+
+/// Name of opaque C type of class
+pub(in crate::c_api) fn c_class_name(class: &ForeignClassInfo, symbol_prefix: &str) -> String {
+    format!("{}{}", symbol_prefix, class.name)
+}
+
+fn register_pointer_foreign_types(
+    conv_map: &mut TypeMap,
+    class: &ForeignClassInfo,
+    types_info: &ClassTypesInfo,
+    const_ptr_type: &RustType,
+    symbol_prefix: &str,
+) -> Result<()> {
+    let c_class_name = c_class_name(class, symbol_prefix);
+    for (name, ptr_type) in [
+        (
+            format!("{} *", c_class_name),
+            &types_info.intermediate_ptr_type,
+        ),
+        (format!("const {} *", c_class_name), const_ptr_type),
+    ] {
+        conv_map.alloc_foreign_type(ForeignTypeS {
+            name: TypeName::new(name, (class.src_id, class.name.span())),
+            provides_by_module: vec![],
+            into_from_rust: Some(ForeignConversationRule {
+                rust_ty: ptr_type.to_idx(),
+                intermediate: None,
+            }),
+            from_into_rust: Some(ForeignConversationRule {
+                rust_ty: ptr_type.to_idx(),
+                intermediate: None,
+            }),
+            name_prefix: None,
+        })?;
+    }
+    Ok(())
+}
+
+/// `types_info.intermediate_ptr_type` is owned pointer: `*mut storage_type`
+fn register_rust_ty_conversation_rules(
+    conv_map: &mut TypeMap,
+    types_info: &ClassTypesInfo,
+    const_ptr_type: &RustType,
+) {
+    let owned_ptr_type = &types_info.intermediate_ptr_type;
+    let smart_pointer_type = types_info.smart_pointer_type;
+    let mut add_rule = |from: &RustType, to: &RustType, code: String| {
+        conv_map.add_conversation_rule(
+            from.to_idx(),
+            to.to_idx(),
+            TypeConvCode::new2(code, invalid_src_id_span()).into(),
+        );
+    };
+
+    // const_ptr_type -> &self_type
+    add_rule(
+        const_ptr_type,
+        &types_info.self_type_ref,
+        smart_pointer_type.conversion_code_from_intermediate_to_self_ref(NULL_CHECK),
+    );
+
+    if smart_pointer_type.self_can_be_mutable() {
+        // owned_ptr_type -> &mut self_type
+        add_rule(
+            owned_ptr_type,
+            &types_info.self_type_mut_ref,
+            smart_pointer_type.conversion_code_from_intermediate_to_self_mut_ref(NULL_CHECK),
+        );
+    }
+
+    // storage_type -> owned_ptr_type
+    add_rule(
+        &types_info.storage_type,
+        owned_ptr_type,
+        "let {to_var} = Box::into_raw(Box::new({from_var}));".into(),
+    );
+
+    // owned_ptr_type -> storage_type, caller passes ownership
+    add_rule(
+        owned_ptr_type,
+        &types_info.storage_type,
+        NULL_CHECK.to_owned() + "let {to_var} = unsafe { *Box::from_raw({from_var}) };",
+    );
+
+    if smart_pointer_type != SmartPointerType::None {
+        // const_ptr_type -> &storage_type
+        add_rule(
+            const_ptr_type,
+            &types_info.storage_type_ref,
+            NULL_CHECK.to_owned() + "let {to_var} = unsafe { &*{from_var} };",
+        );
+    }
+
+    if smart_pointer_type.pointer_can_be_mutable() && smart_pointer_type != SmartPointerType::None {
+        // owned_ptr_type -> &mut storage_type
+        add_rule(
+            owned_ptr_type,
+            &types_info.storage_type_mut_ref,
+            NULL_CHECK.to_owned() + "let {to_var} = unsafe { &mut *{from_var} };",
+        );
+    }
+
+    if types_info.storage_has_clone() {
+        // &storage_type -> owned_ptr_type
+        add_rule(
+            &types_info.storage_type_ref,
+            owned_ptr_type,
+            "let {to_var} = Box::into_raw(Box::new({from_var}.clone()));".into(),
+        );
+    }
+
+    if types_info.self_has_clone && smart_pointer_type != SmartPointerType::None {
+        // const_ptr_type -> self_type
+        add_rule(
+            const_ptr_type,
+            &types_info.self_type,
+            smart_pointer_type.conversion_code_from_intermediate_to_self_ref(NULL_CHECK)
+                + "let {to_var} = {to_var}.clone();",
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use petgraph::Direction;
+use smol_str::SmolStr;
+use syn::{spanned::Spanned, Type};
+
+use crate::{
+    error::*,
+    typemap::{
+        ty::{ForeignTypeS, RustType},
+        MapToForeignFlag, TypeMap,
+    },
+    types::{FnArg, ForeignClassInfo, ForeignMethod, SelfTypeVariant},
+};
+
+pub(in crate::c_api) struct CForeignMethodSignature {
+    pub(in crate::c_api) output: CArgInfo,
+    pub(in crate::c_api) input: Vec<CArgInfo>,
+    pub(in crate::c_api) name: String,
+    pub(in crate::c_api) rust_function_call: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(in crate::c_api) enum ArgName {
+    SelfArg,
+    Named(SmolStr),
+    Return,
+}
+
+impl ArgName {
+    pub(in crate::c_api) fn rust_variable_name(&self) -> &str {
+        match self {
+            ArgName::SelfArg => "this",
+            ArgName::Return => "ret",
+            ArgName::Named(name) => name,
+        }
+    }
+
+    /// `this` is reserved word in C++, so it can not be used in headers
+    pub(in crate::c_api) fn c_variable_name(&self) -> &str {
+        match self {
+            ArgName::SelfArg => "self",
+            ArgName::Return => "ret",
+            ArgName::Named(name) => name,
+        }
+    }
+}
+
+pub(in crate::c_api) struct CArgInfo {
+    pub(in crate::c_api) type_info: CTypeInfo,
+    pub(in crate::c_api) arg_name: ArgName,
+    pub(in crate::c_api) span: SourceIdSpan,
+}
+
+impl CArgInfo {
+    pub(in crate::c_api) fn direction(&self) -> Direction {
+        if self.arg_name == ArgName::Return {
+            Direction::Outgoing
+        } else {
+            Direction::Incoming
+        }
+    }
+
+    pub(in crate::c_api) fn rust_conversion_code(
+        &self,
+        conv_map: &mut TypeMap,
+    ) -> Result<(Vec<proc_macro2::TokenStream>, String)> {
+        let (from, to) = match self.direction() {
+            Direction::Incoming => (
+                self.type_info.rust_intermediate_type.to_idx(),
+                self.type_info.rust_type.to_idx(),
+            ),
+            Direction::Outgoing => (
+                self.type_info.rust_type.to_idx(),
+                self.type_info.rust_intermediate_type.to_idx(),
+            ),
+        };
+        conv_map.convert_rust_types(
+            from,
+            to,
+            self.arg_name.rust_variable_name(),
+            self.arg_name.rust_variable_name(),
+            "()",
+            self.span,
+        )
+    }
+}
+
+pub(in crate::c_api) struct CTypeInfo {
+    pub(in crate::c_api) c_type: SmolStr,
+    pub(in crate::c_api) rust_type: RustType,
+    pub(in crate::c_api) rust_intermediate_type: RustType,
+}
+
+fn calc_this_type_for_method(_: &TypeMap, class: &ForeignClassInfo) -> Option<Type> {
+    class
+        .self_desc
+        .as_ref()
+        .map(|x| x.constructor_ret_type.clone())
+}
+
+pub(in crate::c_api) fn make_foreign_method_signature(
+    conv_map: &mut TypeMap,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+) -> Result<CForeignMethodSignature> {
+    let input = method
+        .fn_decl
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Default(named_arg) => Ok(CArgInfo {
+                type_info: map_type(
+                    conv_map,
+                    &named_arg.ty,
+                    Direction::Incoming,
+                    (class.src_id, named_arg.span),
+                )?,
+                arg_name: ArgName::Named(named_arg.name.clone()),
+                span: (class.src_id, named_arg.span),
+            }),
+            FnArg::SelfArg(span_ref, self_variant) => {
+                let span = *span_ref;
+                let self_ty = class
+                    .self_desc
+                    .as_ref()
+                    .ok_or_else(|| {
+                        DiagnosticError::new(
+                            class.src_id,
+                            span,
+                            "Non-static methods can only be defined for a class \
+                             that have a constructor (at least private empty one)",
+                        )
+                    })?
+                    .self_type
+                    .clone();
+                let self_ty_full = match self_variant {
+                    SelfTypeVariant::Rptr => parse_type_spanned_checked!(span, & #self_ty),
+                    SelfTypeVariant::RptrMut => parse_type_spanned_checked!(span, &mut #self_ty),
+                    SelfTypeVariant::Default | SelfTypeVariant::Mut => {
+                        parse_type_spanned_checked!(span, #self_ty)
+                    }
+                };
+                Ok(CArgInfo {
+                    type_info: map_type(
+                        conv_map,
+                        &self_ty_full,
+                        Direction::Incoming,
+                        (class.src_id, span),
+                    )?,
+                    arg_name: ArgName::SelfArg,
+                    span: (class.src_id, span),
+                })
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output_span = (class.src_id, method.fn_decl.output.span());
+    let output_ty = match method.fn_decl.output {
+        syn::ReturnType::Default => parse_type! { () },
+        syn::ReturnType::Type(_, ref ty) => (**ty).clone(),
+    };
+    let output = CArgInfo {
+        type_info: map_type(conv_map, &output_ty, Direction::Outgoing, output_span)?,
+        arg_name: ArgName::Return,
+        span: output_span,
+    };
+    Ok(CForeignMethodSignature {
+        input,
+        output,
+        name: method.short_name(),
+        rust_function_call: method.generate_code_to_call_rust_func(),
+    })
+}
+
+fn map_type(
+    conv_map: &mut TypeMap,
+    ty: &Type,
+    direction: Direction,
+    span: SourceIdSpan,
+) -> Result<CTypeInfo> {
+    let rust_ty = conv_map.find_or_alloc_rust_type(ty, span.0);
+    let foreign_type = find_foreign_type(conv_map, &rust_ty, direction, span)?;
+    let rule = match direction {
+        Direction::Outgoing => foreign_type.into_from_rust.as_ref(),
+        Direction::Incoming => foreign_type.from_into_rust.as_ref(),
+    }
+    .ok_or_else(|| {
+        DiagnosticError::new2(
+            span,
+            format!(
+                "No rule to convert foreign type {} as input/output type",
+                foreign_type.name
+            ),
+        )
+    })?;
+    let rust_intermediate_type = match rule.intermediate.as_ref() {
+        Some(intermediate) => conv_map[intermediate.intermediate_ty].clone(),
+        None => conv_map[rule.rust_ty].clone(),
+    };
+    Ok(CTypeInfo {
+        c_type: foreign_type.typename(),
+        rust_type: rust_ty,
+        rust_intermediate_type,
+    })
+}
+
+fn find_foreign_type(
+    conv_map: &mut TypeMap,
+    rust_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<ForeignTypeS> {
+    for flag in [MapToForeignFlag::FastSearch, MapToForeignFlag::FullSearch] {
+        if let Some(foreign_type_idx) = conv_map.map_through_conversation_to_foreign(
+            rust_ty.to_idx(),
+            direction,
+            flag,
+            arg_ty_span,
+            calc_this_type_for_method,
+        ) {
+            return Ok(conv_map[foreign_type_idx].clone());
+        }
+    }
+    Err(DiagnosticError::new2(
+        arg_ty_span,
+        format!(
+            "Do not know conversation from C to such rust type '{}'",
+            rust_ty
+        ),
+    ))
+}
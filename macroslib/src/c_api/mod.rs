@@ -0,0 +1,693 @@
+//! Plain C99 API on top of the same `extern "C"` functions approach as the C++ backend,
+//! but without C++ wrappers: every class is an opaque pointer with explicit
+//! `<prefix><Class>_free` function, strings are `char *` freed by `<prefix>string_free`,
+//! and ownership of every pointer is documented in generated headers.
+//! Errors of conversion of arguments or return values are reported
+//! via `<prefix>take_last_error`.
+
+mod classes;
+mod map_type;
+
+use log::debug;
+use proc_macro2::TokenStream;
+use quote::quote;
+use rustc_hash::{FxHashMap, FxHashSet};
+use smol_str::SmolStr;
+use std::{fmt::Write as _, io::Write, path::PathBuf, rc::Rc};
+use syn::{parse_str, Ident, Type};
+
+use crate::{
+    doc_comments::{exported_items, DocComment, ExportedItems},
+    error::{invalid_src_id_span, DiagnosticError, Result, ResultSynDiagnostic},
+    file_cache::FileWriteCache,
+    typemap::{
+        ast::TypeName,
+        ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS},
+        utils::{remove_files_if, rust_code_for_bitflags},
+        TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    types::{ForeignClassInfo, ForeignEnumInfo, ForeignLifecycle, ItemToExpand, MethodAccess},
+    CConfig, ExtHandlers, LanguageGenerator, SourceCode, TypeMap, WRITE_TO_MEM_FAILED_MSG,
+};
+use map_type::{CArgInfo, CForeignMethodSignature};
+
+struct CGenerator<'a> {
+    cfg: &'a CConfig,
+    conv_map: &'a mut TypeMap,
+    rust_code: Vec<TokenStream>,
+    generated_foreign_files: FxHashSet<PathBuf>,
+    exported_items: ExportedItems,
+    /// Rust intermediate types of values that caller owns, and names of functions to free them
+    free_functions: FxHashMap<SmolStr, String>,
+    /// Opaque types, enums and lifecycle functions, shared by all headers
+    common_decls: String,
+    class_headers: Vec<String>,
+}
+
+impl<'a> CGenerator<'a> {
+    fn generate(
+        mut self,
+        items: Vec<ItemToExpand>,
+    ) -> Result<(Vec<TokenStream>, FxHashSet<PathBuf>)> {
+        self.generate_string_free()?;
+        self.generate_take_last_error()?;
+        for item in &items {
+            match item {
+                ItemToExpand::Class(fclass) => self.register_class(fclass)?,
+                ItemToExpand::Enum(fenum) => self.generate_enum(fenum)?,
+                ItemToExpand::Lifecycle(lifecycle) => self.generate_lifecycle(lifecycle)?,
+                ItemToExpand::Interface(finterface) => {
+                    return Err(DiagnosticError::new2(
+                        finterface.src_id_span(),
+                        "foreign_callback is not supported yet for C API",
+                    ));
+                }
+            }
+        }
+        for item in &items {
+            if let ItemToExpand::Class(fclass) = item {
+                self.generate_class(fclass)?;
+            }
+        }
+        self.write_common_header()?;
+        self.write_umbrella_header()?;
+        Ok((self.rust_code, self.generated_foreign_files))
+    }
+
+    fn prefix(&self) -> &str {
+        &self.cfg.symbol_prefix
+    }
+
+    fn generate_string_free(&mut self) -> Result<()> {
+        let func_name = format!("{}string_free", self.prefix());
+        self.rust_code.push(
+            parse_str(&format!(
+                r#"
+    #[no_mangle]
+    pub unsafe extern "C" fn {func_name}(s: *mut ::std::ffi::c_char) {{
+        if !s.is_null() {{
+            ::std::mem::drop(::std::ffi::CString::from_raw(s));
+        }}
+    }}
+"#,
+                func_name = func_name
+            ))
+            .map_err(|err| DiagnosticError::from_syn_err(invalid_src_id_span().0, err))?,
+        );
+        let c_string = self
+            .conv_map
+            .find_or_alloc_rust_type_no_src_id(&parse_type! { *mut ::std::ffi::c_char });
+        self.free_functions
+            .insert(c_string.normalized_name.clone(), func_name.clone());
+        writeln!(
+            &mut self.common_decls,
+            r#"
+/// Free string returned by library, `NULL` is ignored
+void {func_name}(char *s);"#,
+            func_name = func_name
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        Ok(())
+    }
+
+    fn generate_take_last_error(&mut self) -> Result<()> {
+        let func_name = format!("{}take_last_error", self.prefix());
+        self.rust_code.push(
+            parse_str(&format!(
+                r#"
+    #[no_mangle]
+    pub extern "C" fn {func_name}() -> *mut ::std::ffi::c_char {{
+        c_api_take_last_error()
+    }}
+"#,
+                func_name = func_name
+            ))
+            .map_err(|err| DiagnosticError::from_syn_err(invalid_src_id_span().0, err))?,
+        );
+        writeln!(
+            &mut self.common_decls,
+            r#"
+/// Error of the last call of library function in current thread, or `NULL`
+/// if it was successful. In case of error function returns `NULL`, zero or `false`.
+/// Ownership: caller owns returned string, it should be freed by `{prefix}string_free`
+char *{func_name}(void);"#,
+            prefix = self.cfg.symbol_prefix,
+            func_name = func_name
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        Ok(())
+    }
+
+    fn register_class(&mut self, class: &ForeignClassInfo) -> Result<()> {
+        classes::register_class(self.conv_map, class, &self.cfg.symbol_prefix)?;
+        if let Some(self_desc) = class.self_desc.as_ref() {
+            let owned_ptr = self.conv_map.find_or_alloc_rust_type(
+                &classes::owned_ptr_ty(&self_desc.constructor_ret_type),
+                class.src_id,
+            );
+            self.free_functions.insert(
+                owned_ptr.normalized_name.clone(),
+                free_func_name(class, self.prefix()),
+            );
+            let c_class_name = classes::c_class_name(class, self.prefix());
+            write!(
+                &mut self.common_decls,
+                "\n{doc_comments}typedef struct {name} {name};\n",
+                doc_comments =
+                    doc_comments_to_c_comments(&class.doc_comments, "", &self.exported_items),
+                name = c_class_name,
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        Ok(())
+    }
+
+    fn generate_enum(&mut self, fenum: &ForeignEnumInfo) -> Result<()> {
+        if (fenum.items.len() as u64) >= (i32::MAX as u64) {
+            return Err(DiagnosticError::new(
+                fenum.src_id,
+                fenum.span(),
+                "Too many items in enum",
+            ));
+        }
+        let enum_name = &fenum.name;
+        let c_enum_name = format!("{}{}", self.prefix(), enum_name);
+        // size of C enum is implementation defined, so use fixed size type instead
+        write!(
+            &mut self.common_decls,
+            "\n{doc_comments}typedef int32_t {c_enum_name};\n",
+            doc_comments =
+                doc_comments_to_c_comments(&fenum.doc_comments, "", &self.exported_items),
+            c_enum_name = c_enum_name,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        if fenum.is_bitflags {
+            self.generate_bitflags_items(fenum, &c_enum_name)?;
+        } else {
+            self.common_decls.push_str("enum {\n");
+            for (i, item) in fenum.items.iter().enumerate() {
+                writeln!(
+                    &mut self.common_decls,
+                    "{doc_comments}    {c_enum_name}_{item_name} = {value}{separator}",
+                    doc_comments = doc_comments_to_c_comments(
+                        &item.doc_comments,
+                        "    ",
+                        &self.exported_items
+                    ),
+                    c_enum_name = c_enum_name,
+                    item_name = item.name,
+                    value = item.value,
+                    separator = if i == fenum.items.len() - 1 { "" } else { "," },
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+            self.common_decls.push_str("};\n");
+        }
+
+        let span = fenum.span();
+        let enum_rty = self
+            .conv_map
+            .find_or_alloc_rust_type(&parse_type_spanned_checked!(span, #enum_name), fenum.src_id);
+        let i32_rty = self
+            .conv_map
+            .find_or_alloc_rust_type_no_src_id(&parse_type! { i32 });
+        let enum_conv = Some(ForeignConversationRule {
+            rust_ty: enum_rty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: i32_rty.to_idx(),
+                conv_code: Rc::new(TypeConvCode::new(FROM_VAR_TEMPLATE, invalid_src_id_span())),
+            }),
+        });
+        self.conv_map.alloc_foreign_type(ForeignTypeS {
+            name: TypeName::new(c_enum_name, (fenum.src_id, fenum.name.span())),
+            provides_by_module: vec![],
+            into_from_rust: enum_conv.clone(),
+            from_into_rust: enum_conv,
+            name_prefix: None,
+        })?;
+        self.conv_map.add_conversation_rule(
+            i32_rty.to_idx(),
+            enum_rty.to_idx(),
+            TypeConvCode::new2(
+                format!(
+                    r#"let {to_var} = match <{enum_name} as {trait_name}>::from_i32({from_var}) {{
+            Some(x) => x,
+            None => {{
+                c_api_set_last_error(&format!("{{}} not expected for {enum_name}", {from_var}));
+                return CApiInvalidValue::c_api_invalid_value();
+            }}
+        }};"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    enum_name = enum_name,
+                    trait_name = if fenum.is_bitflags {
+                        "SwigForeignBitflags"
+                    } else {
+                        "SwigForeignEnum"
+                    },
+                    from_var = FROM_VAR_TEMPLATE
+                ),
+                invalid_src_id_span(),
+            )
+            .into(),
+        );
+        self.conv_map.add_conversation_rule(
+            enum_rty.to_idx(),
+            i32_rty.to_idx(),
+            TypeConvCode::new2(
+                format!("let {} = {}.as_i32();", TO_VAR_TEMPLATE, FROM_VAR_TEMPLATE),
+                invalid_src_id_span(),
+            )
+            .into(),
+        );
+
+        if fenum.is_bitflags {
+            self.rust_code.push(rust_code_for_bitflags(fenum));
+            return Ok(());
+        }
+        let (arms_to_i32, arms_from_i32): (Vec<_>, Vec<_>) = fenum
+            .items
+            .iter()
+            .map(|item| {
+                let item_name = &item.rust_name;
+                let idx = item.value as i32;
+                (
+                    quote! { #item_name => #idx },
+                    quote! { #idx => Some(#item_name) },
+                )
+            })
+            .unzip();
+        self.rust_code.push(quote! {
+            impl SwigForeignEnum for #enum_name {
+                fn as_i32(&self) -> i32 {
+                    match *self {
+                        #(#arms_to_i32),*
+                    }
+                }
+                fn from_i32(x: i32) -> Option<Self> {
+                    match x {
+                        #(#arms_from_i32),*
+                        ,
+                        _ => None,
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Values of Rust constants are unknown during code generation,
+    /// so every flag is a function that returns its bits
+    fn generate_bitflags_items(
+        &mut self,
+        fenum: &ForeignEnumInfo,
+        c_enum_name: &str,
+    ) -> Result<()> {
+        let flags_name = &fenum.name;
+        for item in &fenum.items {
+            let func_name = format!("{}_{}", c_enum_name, item.name);
+            writeln!(
+                &mut self.common_decls,
+                "{doc_comments}{c_enum_name} {func_name}(void);",
+                doc_comments =
+                    doc_comments_to_c_comments(&item.doc_comments, "", &self.exported_items),
+                c_enum_name = c_enum_name,
+                func_name = func_name,
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+            let func_name = Ident::new(&func_name, item.name.span());
+            let rust_name = &item.rust_name;
+            self.rust_code.push(quote! {
+                #[no_mangle]
+                pub extern "C" fn #func_name() -> i32 {
+                    c_api_clear_last_error();
+                    <#flags_name as SwigForeignBitflags>::as_i32(&#rust_name)
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn generate_lifecycle(&mut self, lifecycle: &ForeignLifecycle) -> Result<()> {
+        let hooks = [
+            (
+                lifecycle.on_load.as_ref(),
+                "init",
+                "Should be called once before usage of any other function of library",
+            ),
+            (
+                lifecycle.on_unload.as_ref(),
+                "deinit",
+                "Should be called once after usage of library",
+            ),
+        ];
+        for (hook, suffix, doc) in hooks {
+            if let Some(hook) = hook {
+                let func_name = format!("{}{}", self.prefix(), suffix);
+                let func_ident = Ident::new(&func_name, proc_macro2::Span::call_site());
+                self.rust_code.push(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #func_ident() {
+                        #hook();
+                    }
+                });
+                writeln!(
+                    &mut self.common_decls,
+                    "\n/// {doc}\nvoid {func_name}(void);",
+                    doc = doc,
+                    func_name = func_name
+                )
+                .expect(WRITE_TO_MEM_FAILED_MSG);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_class(&mut self, class: &ForeignClassInfo) -> Result<()> {
+        let mut c_code = String::new();
+        for method in &class.methods {
+            if method.is_dummy_constructor() || method.access != MethodAccess::Public {
+                continue;
+            }
+            let f_method = map_type::make_foreign_method_signature(self.conv_map, class, method)?;
+            self.write_rust_glue_code(class, &f_method)?;
+            write!(
+                &mut c_code,
+                "\n{doc_comments}{ownership}{ret_type}{sep}{func_name}({args});\n",
+                doc_comments =
+                    doc_comments_to_c_comments(&method.doc_comments, "", &self.exported_items),
+                ownership = self.output_ownership_comment(&f_method.output),
+                ret_type = f_method.output.type_info.c_type,
+                sep = type_name_separator(&f_method.output.type_info.c_type),
+                func_name = c_func_name(class, &f_method, self.prefix()),
+                args = self.c_args(&f_method),
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        if let Some(self_desc) = class.self_desc.as_ref() {
+            let free_func = free_func_name(class, self.prefix());
+            let owned_ptr_ty = classes::owned_ptr_ty(&self_desc.constructor_ret_type);
+            self.write_free_function(class, &free_func, &owned_ptr_ty)?;
+            write!(
+                &mut c_code,
+                r#"
+/// Free object owned by caller, `NULL` is ignored.
+/// Usage of object after this call is undefined behavior.
+void {free_func}({c_class_name} *self);
+"#,
+                free_func = free_func,
+                c_class_name = classes::c_class_name(class, self.prefix()),
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        if !class.foreign_code.is_empty() {
+            c_code.push('\n');
+            c_code.push_str(&class.foreign_code);
+            if !class.foreign_code.ends_with('\n') {
+                c_code.push('\n');
+            }
+        }
+        let header_name = format!("{}{}.h", self.prefix(), class.name);
+        self.write_header(
+            &header_name,
+            &format!("#include \"{}common.h\"\n", self.prefix()),
+            &c_code,
+        )?;
+        self.class_headers.push(header_name);
+        Ok(())
+    }
+
+    fn write_free_function(
+        &mut self,
+        class: &ForeignClassInfo,
+        free_func: &str,
+        owned_ptr_ty: &Type,
+    ) -> Result<()> {
+        let free_ident = Ident::new(free_func, class.name.span());
+        self.rust_code.push(quote! {
+            #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+            #[no_mangle]
+            pub unsafe extern "C" fn #free_ident(this: #owned_ptr_ty) {
+                if !this.is_null() {
+                    ::std::mem::drop(Box::from_raw(this));
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn write_rust_glue_code(
+        &mut self,
+        class: &ForeignClassInfo,
+        f_method: &CForeignMethodSignature,
+    ) -> Result<()> {
+        let mut convert_input_code = String::new();
+        for arg in &f_method.input {
+            let (mut deps, conv_code) = arg.rust_conversion_code(self.conv_map)?;
+            self.rust_code.append(&mut deps);
+            convert_input_code.push_str(&conv_code);
+        }
+        let func_args = f_method
+            .input
+            .iter()
+            .map(|arg| {
+                format!(
+                    "{}: {}",
+                    arg.arg_name.rust_variable_name(),
+                    arg.type_info.rust_intermediate_type
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (mut deps, convert_output_code) =
+            f_method.output.rust_conversion_code(self.conv_map)?;
+        self.rust_code.append(&mut deps);
+
+        let rust_code = format!(
+            r#"
+    #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+    #[no_mangle]
+    pub unsafe extern "C" fn {func_name}({func_args}) -> {ret_type} {{
+        c_api_clear_last_error();
+        {convert_input_code}
+        let mut {ret_name}: {rust_ret_type} = {call};
+        {convert_output_code}
+        {ret_name}
+    }}
+"#,
+            func_name = c_func_name(class, f_method, self.prefix()),
+            func_args = func_args,
+            ret_type = f_method.output.type_info.rust_intermediate_type,
+            rust_ret_type = f_method.output.type_info.rust_type,
+            convert_input_code = convert_input_code,
+            ret_name = f_method.output.arg_name.rust_variable_name(),
+            call = f_method.rust_function_call,
+            convert_output_code = convert_output_code,
+        );
+        debug!("write_rust_glue_code: {}", rust_code);
+        self.rust_code
+            .push(parse_str(&rust_code).with_syn_src_id(class.src_id)?);
+        Ok(())
+    }
+
+    fn c_args(&self, f_method: &CForeignMethodSignature) -> String {
+        if f_method.input.is_empty() {
+            return "void".into();
+        }
+        f_method
+            .input
+            .iter()
+            .map(|arg| {
+                let c_type = &arg.type_info.c_type;
+                let mut c_arg = format!(
+                    "{}{}{}",
+                    c_type,
+                    type_name_separator(c_type),
+                    arg.arg_name.c_variable_name()
+                );
+                if let Some(ownership) = self.input_ownership(arg) {
+                    write!(&mut c_arg, " /* {} */", ownership).expect(WRITE_TO_MEM_FAILED_MSG);
+                }
+                c_arg
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn input_ownership(&self, arg: &CArgInfo) -> Option<&'static str> {
+        if !matches!(arg.type_info.rust_intermediate_type.ty, Type::Ptr(_)) {
+            return None;
+        }
+        let owned = !matches!(arg.type_info.rust_type.ty, Type::Reference(_))
+            && self
+                .free_functions
+                .contains_key(&arg.type_info.rust_intermediate_type.normalized_name);
+        Some(if owned {
+            "owned, consumed by the call"
+        } else {
+            "borrowed"
+        })
+    }
+
+    fn output_ownership_comment(&self, output: &CArgInfo) -> String {
+        let intermediate_ty = &output.type_info.rust_intermediate_type;
+        if let Some(free_func) = self.free_functions.get(&intermediate_ty.normalized_name) {
+            format!(
+                "/// Ownership: caller owns returned value, it should be freed by `{}`\n",
+                free_func
+            )
+        } else if matches!(intermediate_ty.ty, Type::Ptr(_)) {
+            "/// Ownership: returned value is borrowed, it should not be freed\n".into()
+        } else {
+            String::new()
+        }
+    }
+
+    fn write_common_header(&mut self) -> Result<()> {
+        let common_decls = std::mem::take(&mut self.common_decls);
+        self.write_header(
+            &format!("{}common.h", self.prefix()),
+            "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n",
+            &common_decls,
+        )
+    }
+
+    fn write_umbrella_header(&mut self) -> Result<()> {
+        let mut includes = format!("#include \"{}common.h\"\n", self.prefix());
+        for header in &self.class_headers {
+            writeln!(&mut includes, "#include \"{}\"", header).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        let path = self
+            .cfg
+            .output_dir
+            .join(format!("{}.h", self.cfg.library_name));
+        let guard = include_guard(&format!("{}.h", self.cfg.library_name));
+        let mut file = FileWriteCache::new(&path, &mut self.generated_foreign_files);
+        write!(
+            file,
+            "// Automatically generated by flapigen\n#ifndef {guard}\n#define {guard}\n\n{includes}\n#endif // {guard}\n",
+            guard = guard,
+            includes = includes,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        file.update_file_if_necessary()
+            .map_err(DiagnosticError::map_any_err_to_our_err)
+    }
+
+    fn write_header(&mut self, file_name: &str, includes: &str, c_code: &str) -> Result<()> {
+        let path = self.cfg.output_dir.join(file_name);
+        let guard = include_guard(file_name);
+        let mut file = FileWriteCache::new(&path, &mut self.generated_foreign_files);
+        write!(
+            file,
+            r#"// Automatically generated by flapigen
+#ifndef {guard}
+#define {guard}
+
+{includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+{c_code}
+#ifdef __cplusplus
+}}
+#endif
+
+#endif // {guard}
+"#,
+            guard = guard,
+            includes = includes,
+            c_code = c_code,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        file.update_file_if_necessary()
+            .map_err(DiagnosticError::map_any_err_to_our_err)
+    }
+}
+
+fn c_func_name(
+    class: &ForeignClassInfo,
+    f_method: &CForeignMethodSignature,
+    prefix: &str,
+) -> String {
+    format!("{}{}_{}", prefix, class.name, f_method.name)
+}
+
+fn free_func_name(class: &ForeignClassInfo, prefix: &str) -> String {
+    format!("{}{}_free", prefix, class.name)
+}
+
+/// `char *s`, but `int32_t x`
+fn type_name_separator(c_type: &str) -> &'static str {
+    if c_type.ends_with('*') {
+        ""
+    } else {
+        " "
+    }
+}
+
+fn include_guard(file_name: &str) -> String {
+    file_name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn doc_comments_to_c_comments(
+    doc_comments: &[String],
+    indent: &str,
+    exported_items: &ExportedItems,
+) -> String {
+    let mut comments = String::new();
+    for line in DocComment::parse(doc_comments).to_doxygen(exported_items) {
+        if line.is_empty() {
+            writeln!(&mut comments, "{}///", indent)
+        } else {
+            writeln!(&mut comments, "{}/// {}", indent, line)
+        }
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    comments
+}
+
+impl LanguageGenerator for CConfig {
+    fn expand_items(
+        &self,
+        conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        _code: &[SourceCode],
+        items: Vec<ItemToExpand>,
+        remove_not_generated_files: bool,
+        _ext_handlers: ExtHandlers,
+    ) -> Result<Vec<TokenStream>> {
+        let generator = CGenerator {
+            cfg: self,
+            conv_map,
+            rust_code: Vec::new(),
+            generated_foreign_files: FxHashSet::default(),
+            exported_items: exported_items(&items),
+            free_functions: FxHashMap::default(),
+            common_decls: String::new(),
+            class_headers: Vec::new(),
+        };
+        let (ret, generated_foreign_files) = generator.generate(items)?;
+        if remove_not_generated_files {
+            remove_files_if(&self.output_dir, |path| {
+                if let Some(ext) = path.extension() {
+                    if ext == "h" && !generated_foreign_files.contains(path) {
+                        return true;
+                    }
+                }
+                false
+            })
+            .map_err(DiagnosticError::map_any_err_to_our_err)?;
+        }
+        Ok(ret)
+    }
+}
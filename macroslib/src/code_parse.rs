@@ -3,7 +3,7 @@ use heck::MixedCase;
 use log::debug;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::ToTokens;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use smol_str::SmolStr;
 use std::convert::{TryFrom, TryInto};
 use syn::{
@@ -22,8 +22,8 @@ use crate::{
     typemap::ast::{normalize_type, DisplayToTokens},
     types::{
         FnArg, ForeignClassInfo, ForeignEnumInfo, ForeignEnumItem, ForeignInterface,
-        ForeignInterfaceMethod, ForeignMethod, MethodAccess, MethodVariant, NamedArg, SelfTypeDesc,
-        SelfTypeVariant,
+        ForeignInterfaceMethod, ForeignLifecycle, ForeignMethod, MethodAccess, MethodVariant,
+        NamedArg, SelfTypeDesc, SelfTypeVariant,
     },
     LanguageConfig, CAMEL_CASE_ALIASES, COPY_TRAIT, FOREIGNER_CODE_DEPRECATED, FOREIGN_CODE,
 };
@@ -34,13 +34,13 @@ pub(crate) fn parse_foreigner_class(
     tokens: TokenStream,
 ) -> Result<ForeignClassInfo> {
     match config {
-        LanguageConfig::CppConfig(_) => {
+        LanguageConfig::CppConfig(_) | LanguageConfig::CConfig(_) => {
             let mut class: CppClass =
                 syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
             class.0.src_id = src_id;
             Ok(class.0)
         }
-        LanguageConfig::JavaConfig(_) => {
+        LanguageConfig::JavaConfig(_) | LanguageConfig::JavaPanamaConfig(_) => {
             let mut class: JavaClass =
                 syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
             class.0.src_id = src_id;
@@ -68,6 +68,16 @@ pub(crate) fn parse_foreign_enum(src_id: SourceId, tokens: TokenStream) -> Resul
     Ok(f_enum.0)
 }
 
+pub(crate) fn parse_foreign_bitflags(
+    src_id: SourceId,
+    tokens: TokenStream,
+) -> Result<ForeignEnumInfo> {
+    let mut f_bitflags: ForeignBitflagsInfoParser =
+        syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
+    f_bitflags.0.src_id = src_id;
+    Ok(f_bitflags.0)
+}
+
 pub(crate) fn parse_foreign_interface(
     src_id: SourceId,
     tokens: TokenStream,
@@ -78,6 +88,18 @@ pub(crate) fn parse_foreign_interface(
     Ok(f_interface.0)
 }
 
+pub(crate) fn parse_foreign_lifecycle(
+    src_id: SourceId,
+    tokens: TokenStream,
+) -> Result<ForeignLifecycle> {
+    let span = tokens.span();
+    let mut f_lifecycle: ForeignLifecycleParser =
+        syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
+    f_lifecycle.0.src_id = src_id;
+    f_lifecycle.0.span = span;
+    Ok(f_lifecycle.0)
+}
+
 struct CppClass(ForeignClassInfo);
 
 impl Parse for CppClass {
@@ -134,6 +156,8 @@ mod kw {
     custom_keyword!(empty);
     custom_keyword!(interface);
     custom_keyword!(callback);
+    custom_keyword!(on_load);
+    custom_keyword!(on_unload);
 }
 
 struct Attrs {
@@ -690,38 +714,139 @@ struct ForeignEnumInfoParser(ForeignEnumInfo);
 
 impl Parse for ForeignEnumInfoParser {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let Attrs {
-            doc_comments: enum_doc_comments,
-            derive_list,
-            unknown_attrs,
-        } = parse_attrs(&input, ParseAttrsFlags::DERIVE)?;
-        assert!(unknown_attrs.is_empty());
+        Ok(ForeignEnumInfoParser(do_parse_foreign_enum(input, false)?))
+    }
+}
+
+struct ForeignBitflagsInfoParser(ForeignEnumInfo);
+
+impl Parse for ForeignBitflagsInfoParser {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(ForeignBitflagsInfoParser(do_parse_foreign_enum(
+            input, true,
+        )?))
+    }
+}
+
+/// `foreign_enum!(enum Name { Item = Name::Item [= value], ... })`
+/// or `foreign_bitflags!(struct Name { ... })`
+fn do_parse_foreign_enum(input: ParseStream, is_bitflags: bool) -> syn::Result<ForeignEnumInfo> {
+    let Attrs {
+        doc_comments: enum_doc_comments,
+        derive_list,
+        unknown_attrs,
+    } = parse_attrs(&input, ParseAttrsFlags::DERIVE)?;
+    assert!(unknown_attrs.is_empty());
+    if is_bitflags {
+        input.parse::<Token![struct]>()?;
+    } else {
         input.parse::<Token![enum]>()?;
-        let enum_name = input.parse::<Ident>()?;
-        debug!("ENUM NAME {:?}", enum_name);
-        let item_parser;
-        braced!(item_parser in input);
-        let mut items = vec![];
-        while !item_parser.is_empty() {
-            let doc_comments = parse_doc_comments(&item_parser)?;
-            let f_item_name = item_parser.parse::<Ident>()?;
+    }
+    let enum_name = input.parse::<Ident>()?;
+    debug!("ENUM NAME {:?}", enum_name);
+    let item_parser;
+    braced!(item_parser in input);
+    let mut items = vec![];
+    let mut used_values = FxHashMap::<u32, Ident>::default();
+    let mut next_value = Some(0u32);
+    while !item_parser.is_empty() {
+        let doc_comments = parse_doc_comments(&item_parser)?;
+        let f_item_name = item_parser.parse::<Ident>()?;
+        item_parser.parse::<Token![=]>()?;
+        let item_name = item_parser.call(syn::Path::parse_mod_style)?;
+        let value = if item_parser.peek(Token![=]) {
             item_parser.parse::<Token![=]>()?;
-            let item_name = item_parser.call(syn::Path::parse_mod_style)?;
-            item_parser.parse::<Token![,]>()?;
-
-            items.push(ForeignEnumItem {
-                name: f_item_name,
-                rust_name: item_name,
-                doc_comments,
-            });
+            let lit = item_parser.parse::<syn::LitInt>()?;
+            if is_bitflags {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "explicit values are not supported for flags",
+                ));
+            }
+            match lit.base10_parse::<u32>() {
+                Ok(x) if x <= i32::MAX as u32 => x,
+                _ => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!("enum value should be in range 0..={}", i32::MAX),
+                    ))
+                }
+            }
+        } else {
+            match next_value {
+                Some(x) if x <= i32::MAX as u32 => x,
+                _ => {
+                    return Err(syn::Error::new(
+                        f_item_name.span(),
+                        format!("enum value should be in range 0..={}", i32::MAX),
+                    ))
+                }
+            }
+        };
+        item_parser.parse::<Token![,]>()?;
+
+        if let Some(prev_item) = used_values.get(&value) {
+            let mut err = syn::Error::new(
+                f_item_name.span(),
+                format!("duplicate enum value {}", value),
+            );
+            err.combine(syn::Error::new(
+                prev_item.span(),
+                format!("value {} is already used here", value),
+            ));
+            return Err(err);
         }
+        used_values.insert(value, f_item_name.clone());
+        next_value = value.checked_add(1);
+
+        items.push(ForeignEnumItem {
+            name: f_item_name,
+            rust_name: item_name,
+            doc_comments,
+            value,
+        });
+    }
 
-        Ok(ForeignEnumInfoParser(ForeignEnumInfo {
+    Ok(ForeignEnumInfo {
+        src_id: SourceId::none(),
+        name: enum_name,
+        items,
+        doc_comments: enum_doc_comments,
+        derive_list,
+        is_bitflags,
+    })
+}
+
+struct ForeignLifecycleParser(ForeignLifecycle);
+
+impl Parse for ForeignLifecycleParser {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut on_load = None;
+        let mut on_unload = None;
+        while !input.is_empty() {
+            let kw_la = input.lookahead1();
+            let (hook, kw_span) = if kw_la.peek(kw::on_load) {
+                (&mut on_load, input.parse::<kw::on_load>()?.span)
+            } else if kw_la.peek(kw::on_unload) {
+                (&mut on_unload, input.parse::<kw::on_unload>()?.span)
+            } else {
+                return Err(kw_la.error());
+            };
+            if hook.is_some() {
+                return Err(syn::Error::new(kw_span, "duplicate of lifecycle hook"));
+            }
+            input.parse::<Token![=]>()?;
+            *hook = Some(input.call(syn::Path::parse_mod_style)?);
+            input.parse::<Token![;]>()?;
+        }
+        if on_load.is_none() && on_unload.is_none() {
+            return Err(input.error("expect on_load or on_unload"));
+        }
+        Ok(ForeignLifecycleParser(ForeignLifecycle {
             src_id: SourceId::none(),
-            name: enum_name,
-            items,
-            doc_comments: enum_doc_comments,
-            derive_list,
+            span: Span::call_site(),
+            on_load,
+            on_unload,
         }))
     }
 }
@@ -891,6 +1016,91 @@ mod tests {
         assert_eq!("MyEnum", enum_.name.to_string());
     }
 
+    #[test]
+    fn test_parse_foreign_enum_values() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(enum MyEnum {
+                ITEM1 = MyEnum::Item1,
+                ITEM2 = MyEnum::Item2 = 10,
+                ITEM3 = MyEnum::Item3,
+                ITEM4 = MyEnum::Item4 = 5,
+            })
+        };
+        let enum_ = parse_foreign_enum(SourceId::none(), mac.tokens).unwrap();
+        assert_eq!(
+            vec![0, 10, 11, 5],
+            enum_.items.iter().map(|x| x.value).collect::<Vec<_>>()
+        );
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(enum MyEnum {
+                ITEM1 = MyEnum::Item1 = 1,
+                ITEM2 = MyEnum::Item2 = 0,
+                ITEM3 = MyEnum::Item3,
+            })
+        };
+        assert!(parse_foreign_enum(SourceId::none(), mac.tokens).is_err());
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(enum MyEnum {
+                ITEM1 = MyEnum::Item1 = 4294967295,
+            })
+        };
+        assert!(parse_foreign_enum(SourceId::none(), mac.tokens).is_err());
+    }
+
+    #[test]
+    fn test_parse_foreign_bitflags() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreign_bitflags!(struct MyFlags {
+                A = MyFlags::A,
+                B = MyFlags::B,
+            })
+        };
+        let flags = parse_foreign_bitflags(SourceId::none(), mac.tokens).unwrap();
+        assert_eq!("MyFlags", flags.name.to_string());
+        assert!(flags.is_bitflags);
+        assert_eq!(2, flags.items.len());
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_bitflags!(enum MyFlags {
+                A = MyFlags::A,
+            })
+        };
+        assert!(parse_foreign_bitflags(SourceId::none(), mac.tokens).is_err());
+    }
+
+    #[test]
+    fn test_parse_foreign_lifecycle() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreign_lifecycle!(
+                on_load = crate::init_logger;
+                on_unload = shutdown;
+            )
+        };
+        let lifecycle = parse_foreign_lifecycle(SourceId::none(), mac.tokens).unwrap();
+        assert_eq!(
+            "crate :: init_logger",
+            lifecycle.on_load.unwrap().into_token_stream().to_string()
+        );
+        assert_eq!(
+            "shutdown",
+            lifecycle.on_unload.unwrap().into_token_stream().to_string()
+        );
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_lifecycle!(on_load = init; on_load = init2;)
+        };
+        assert!(parse_foreign_lifecycle(SourceId::none(), mac.tokens).is_err());
+        let mac: syn::Macro = parse_quote! {
+            foreign_lifecycle!(on_start = init;)
+        };
+        assert!(parse_foreign_lifecycle(SourceId::none(), mac.tokens).is_err());
+    }
+
     #[test]
     fn test_parse_foreign_class_with_copy_derive() {
         let _ = env_logger::try_init();
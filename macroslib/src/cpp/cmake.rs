@@ -0,0 +1,136 @@
+use std::{io::Write, path::PathBuf};
+
+use rustc_hash::FxHashSet;
+
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    CppConfig, CppOptional, CppSlice, CppStrView, CppVariant, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Generate `<package_name>Config.cmake` with imported target `<package_name>::<package_name>`,
+/// so `find_package(<package_name> CONFIG)` can be used to consume generated code.
+/// Generated `.cpp` files are added to sources of targets that link with it.
+pub(in crate::cpp) fn generate_package_config(
+    cfg: &CppConfig,
+    package_name: &str,
+    generated_foreign_files: &mut FxHashSet<PathBuf>,
+) -> Result<()> {
+    let path = cfg.output_dir.join(format!("{}Config.cmake", package_name));
+    let mut out = FileWriteCache::new(&path, generated_foreign_files);
+
+    let uses_boost = matches!(cfg.cpp_optional, CppOptional::Boost)
+        || matches!(cfg.cpp_variant, CppVariant::Boost)
+        || matches!(cfg.cpp_str_view, CppStrView::Boost);
+    let target = format!("{0}::{0}", package_name);
+
+    // `cmake_minimum_required` would reset policies of the project that calls `find_package`,
+    // so only check the version
+    let min_version = min_cmake_version(cxx_std(cfg));
+    writeln!(
+        out,
+        r#"# Automatically generated by flapigen
+if (CMAKE_VERSION VERSION_LESS {min_version})
+  message(FATAL_ERROR "{package_name} requires at least CMake {min_version}")
+endif ()
+"#,
+        min_version = min_version,
+        package_name = package_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    if uses_boost {
+        out.write_all(b"include(CMakeFindDependencyMacro)\nfind_dependency(Boost 1.66.0)\n\n")
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    write!(
+        out,
+        r#"if (NOT TARGET {target})
+  add_library({target} INTERFACE IMPORTED)
+  set_target_properties({target} PROPERTIES
+    INTERFACE_INCLUDE_DIRECTORIES "{include_dir}"
+    INTERFACE_COMPILE_FEATURES "cxx_std_{cxx_std}")
+"#,
+        target = target,
+        include_dir = cmake_path(&cfg.output_dir),
+        cxx_std = cxx_std(cfg),
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    let mut sources = generated_foreign_files
+        .iter()
+        .filter(|path| matches!(path.extension(), Some(ext) if ext == "cpp"))
+        .map(|path| cmake_path(path))
+        .collect::<Vec<_>>();
+    if !sources.is_empty() {
+        sources.sort();
+        writeln!(
+            out,
+            "  set_property(TARGET {} APPEND PROPERTY INTERFACE_SOURCES\n    \"{}\")",
+            target,
+            sources.join("\"\n    \"")
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    if uses_boost {
+        writeln!(
+            out,
+            "  set_property(TARGET {} APPEND PROPERTY INTERFACE_LINK_LIBRARIES Boost::boost)",
+            target
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    write!(
+        out,
+        r#"  # path to Rust library, it is not known during code generation
+  if ({package_name}_RUST_LIBRARY)
+    set_property(TARGET {target} APPEND PROPERTY INTERFACE_LINK_LIBRARIES "${{{package_name}_RUST_LIBRARY}}")
+  endif ()
+endif ()
+"#,
+        package_name = package_name,
+        target = target,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    out.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::map_any_err_to_our_err(format!(
+            "write to {} failed: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    Ok(())
+}
+
+/// Minimal C++ standard required by the types chosen in config
+fn cxx_std(cfg: &CppConfig) -> u32 {
+    let mut cxx_std = 11;
+    if matches!(cfg.cpp_optional, CppOptional::Std17)
+        || matches!(cfg.cpp_variant, CppVariant::Std17)
+        || matches!(cfg.cpp_str_view, CppStrView::Std17)
+    {
+        cxx_std = 17;
+    }
+    if matches!(cfg.cpp_slice, CppSlice::Std20) || matches!(cfg.cpp_str_view, CppStrView::Std20U8) {
+        cxx_std = 20;
+    }
+    if matches!(cfg.cpp_variant, CppVariant::Std23Expected) {
+        cxx_std = 23;
+    }
+    cxx_std
+}
+
+/// `cxx_std_*` compile features were added in different versions of CMake
+fn min_cmake_version(cxx_std: u32) -> &'static str {
+    match cxx_std {
+        23 => "3.20",
+        20 => "3.12",
+        _ => "3.8",
+    }
+}
+
+fn cmake_path(path: &std::path::Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let path = path.display().to_string();
+    // CMake expects '/' as separator, and does not understand Windows verbatim paths
+    path.trim_start_matches(r"\\?\").replace('\\', "/")
+}
@@ -0,0 +1,100 @@
+// Rules to map `Result<T, E>` to `T` and C++ exception.
+// They should be merged before rules from cpp-include.rs,
+// to be selected instead of rules that map `Result` to variant.
+
+// order is important!!!
+// we map () to void, but C++ can not handle RustError<void>
+foreign_typemap!(
+   generic_alias!(CRustRes = swig_concat_idents!(CRustVoidOkResultWithMsg, swig_i_type!(T)));
+   generic_alias!(CRustResUnion = swig_concat_idents!(CRustVoidOkResultWithMsgUnion, swig_i_type!(T)));
+   generic_alias!(CRustResModule = swig_concat_idents!(rust_void_ok_result_with_msg, swig_i_type!(T)));
+   define_c_type!(
+       module = "CRustResModule!().h";
+       #[repr(C)]
+       #[derive(Clone, Copy)]
+       pub union CRustResUnion!() {
+           ok: u8,
+           err: swig_i_type!(T),
+       }
+
+       #[repr(C)]
+       #[derive(Clone, Copy)]
+       pub struct CRustRes!() {
+           data: CRustResUnion!(),
+           msg: CRustString,
+           is_ok: u8,
+       }
+   );
+   ($p:r_type) <T> Result<(), T> => CRustRes!() {
+       $out = match $p {
+           Ok(()) => {
+               CRustRes!() {
+                   data: CRustResUnion!() { ok: 0 },
+                   msg: CRustString::from_string(String::new()),
+                   is_ok: 1,
+               }
+           }
+           Err(err) => {
+               let msg = CRustString::from_string(err.to_string());
+               swig_from_rust_to_i_type!(T, err, err)
+               CRustRes!() {
+                   data: CRustResUnion!() { err },
+                   msg,
+                   is_ok: 0,
+               }
+           }
+       };
+   };
+
+   ($p:f_type, req_modules = ["\"CRustResModule!().h\"", "\"rust_str.h\"", "\"rust_error.hpp\""]) => "/*throws RustError<swig_f_type!(T)>*/ void"
+       r#"($p.is_ok != 0) ? (void)0 :
+            throw RustError<swig_f_type!(T)>(swig_foreign_from_i_type!(T, $p.data.err), RustString{$p.msg}.to_std_string())"#;
+);
+
+foreign_typemap!(
+   generic_alias!(CRustRes = swig_concat_idents!(CRustResultWithMsg, swig_i_type!(T1), swig_i_type!(T2)));
+   generic_alias!(CRustResUnion = swig_concat_idents!(CRustResultWithMsgUnion, swig_i_type!(T1), swig_i_type!(T2)));
+   generic_alias!(CRustResModule = swig_concat_idents!(rust_result_with_msg, swig_i_type!(T1), swig_i_type!(T2)));
+   define_c_type!(
+       module = "CRustResModule!().h";
+       #[repr(C)]
+       #[derive(Clone, Copy)]
+       pub union CRustResUnion!() {
+           ok: swig_i_type!(T1),
+           err: swig_i_type!(T2),
+       }
+
+       #[repr(C)]
+       #[derive(Clone, Copy)]
+       pub struct CRustRes!() {
+           data: CRustResUnion!(),
+           msg: CRustString,
+           is_ok: u8,
+       }
+   );
+   ($p:r_type) <T1, T2> Result<T1, T2> => CRustRes!() {
+       $out = match $p {
+           Ok(mut x) => {
+               swig_from_rust_to_i_type!(T1, x, ok)
+               CRustRes!() {
+                   data: CRustResUnion!() { ok },
+                   msg: CRustString::from_string(String::new()),
+                   is_ok: 1,
+               }
+           }
+           Err(err) => {
+               let msg = CRustString::from_string(err.to_string());
+               swig_from_rust_to_i_type!(T2, err, err)
+               CRustRes!() {
+                   data: CRustResUnion!() { err },
+                   msg,
+                   is_ok: 0,
+               }
+           }
+       };
+   };
+
+   ($p:f_type, req_modules = ["\"CRustResModule!().h\"", "\"rust_str.h\"", "\"rust_error.hpp\""]) => "/*throws RustError<swig_f_type!(T2)>*/ swig_f_type!(T1)"
+       r#"($p.is_ok != 0) ? swig_foreign_from_i_type!(T1, $p.data.ok) :
+              throw RustError<swig_f_type!(T2)>(swig_foreign_from_i_type!(T2, $p.data.err), RustString{$p.msg}.to_std_string())"#;
+);
@@ -95,6 +95,14 @@ pub trait SwigForeignEnum {
     fn from_u32(_: u32) -> Self;
 }
 
+/// Value of flags created by `bitflags!` is passed as its bits
+#[allow(dead_code)]
+pub trait SwigForeignBitflags: Sized {
+    /// `None` if there are unknown bits
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
+
 #[allow(dead_code)]
 #[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_into();"]
 trait SwigInto<T> {
@@ -389,6 +397,11 @@ foreign_typemap!(
         "std::string_view{ $p.data, $p.len }";
     ($p:f_type, option = "CppStrView::Std17", req_modules = ["\"rust_str.h\"", "<string_view>"]) <= "std::string_view"
         "CRustStrView{ $p.data(), $p.size() }";
+
+    ($p:f_type, option = "CppStrView::Std20U8", req_modules = ["\"rust_str.h\"", "<string_view>"]) => "std::u8string_view"
+        "std::u8string_view{ reinterpret_cast<const char8_t *>($p.data), $p.len }";
+    ($p:f_type, option = "CppStrView::Std20U8", req_modules = ["\"rust_str.h\"", "<string_view>"]) <= "std::u8string_view"
+        "CRustStrView{ reinterpret_cast<const char *>($p.data()), $p.size() }";
 );
 
 foreign_typemap!(
@@ -419,6 +432,8 @@ foreign_typemap!(
                     r##"
 #ifdef __cplusplus
 
+#include <cstring>
+#include <functional>
 #include <string>
 "##
     );
@@ -426,6 +441,11 @@ foreign_typemap!(
                     option = "CppStrView::Std17";
                     r##"
 #include <string_view>
+"##);
+    foreign_code!(module = "rust_str.h";
+                    option = "CppStrView::Std20U8";
+                    r##"
+#include <string_view>
 "##);
     foreign_code!(module = "rust_str.h";
                     option = "CppStrView::Boost";
@@ -481,11 +501,40 @@ public:
     std::string to_std_string() const { return std::string(data, len); }
     size_t size() const noexcept { return this->len; }
     bool empty() const noexcept { return this->len == 0; }
+    bool operator==(const RustString &o) const noexcept
+    {
+        return len == o.len && (len == 0 || std::memcmp(data, o.data, len) == 0);
+    }
+    bool operator!=(const RustString &o) const noexcept { return !(*this == o); }
+    bool operator<(const RustString &o) const noexcept
+    {
+        const size_t common_len = len < o.len ? len : o.len;
+        const int res = common_len == 0 ? 0 : std::memcmp(data, o.data, common_len);
+        return res < 0 || (res == 0 && len < o.len);
+    }
+    size_t hash() const noexcept
+    {
+        // FNV-1a
+        size_t h = static_cast<size_t>(14695981039346656037ULL);
+        for (size_t i = 0; i < len; ++i) {
+            h ^= static_cast<unsigned char>(data[i]);
+            h *= static_cast<size_t>(1099511628211ULL);
+        }
+        return h;
+    }
 "##);
     foreign_code!(module = "rust_str.h";
                     option = "CppStrView::Std17";
                     r##"
     std::string_view to_string_view() const { return std::string_view(data, len); }
+"##);
+    foreign_code!(module = "rust_str.h";
+                    option = "CppStrView::Std20U8";
+                    r##"
+    std::u8string_view to_u8string_view() const
+    {
+        return std::u8string_view(reinterpret_cast<const char8_t *>(data), len);
+    }
 "##);
     foreign_code!(module = "rust_str.h";
                     option = "CppStrView::Boost";
@@ -510,6 +559,12 @@ private:
     }
 };
 } // namespace $RUST_SWIG_USER_NAMESPACE
+
+namespace std {
+template <> struct hash<$RUST_SWIG_USER_NAMESPACE::RustString> {
+    size_t operator()(const $RUST_SWIG_USER_NAMESPACE::RustString &s) const noexcept { return s.hash(); }
+};
+} // namespace std
 #endif // __cplusplus
 "##
                     );
@@ -538,7 +593,7 @@ foreign_typemap!(
         };
     };
     ($p:f_type, unique_prefix = "/*opt ref*/", req_modules = ["\"rust_option.h\""]) <= "/*opt ref*/const swig_f_type!(T) *" r#"
-        $out = CRustClassOpt!() { ($p != nullptr) ? static_cast<swig_f_type!(T)Opaque *>(* $p) : nullptr };
+        $out = CRustClassOpt!() { ($p != nullptr) ? static_cast<swig_f_type!(T)::CForeignType *>(* $p) : nullptr };
 "#;
 );
 
@@ -561,7 +616,7 @@ foreign_typemap!(
         };
     };
     ($p:f_type, unique_prefix = "/*opt mut ref*/", req_modules = ["\"rust_option.h\""]) <= "/*opt mut ref*/swig_f_type!(T) *" r#"
-        $out = CRustClassOptMut!() { ($p != nullptr) ? static_cast<swig_f_type!(T)Opaque *>(* $p) : nullptr };
+        $out = CRustClassOptMut!() { ($p != nullptr) ? static_cast<swig_f_type!(T)::CForeignType *>(* $p) : nullptr };
 "#;
 );
 
@@ -758,10 +813,15 @@ foreign_typemap!(
         assert!($p.len == 0 || !$p.data.is_null());
         $out = unsafe { ::std::slice::from_raw_parts($p.data, $p.len) };
     };
-    ($p:f_type, req_modules = ["\"CRustSlice!().h\""]) => "RustSlice<const swig_f_type!(T)>"
+    ($p:f_type, option = "CppSlice::RustSlice", req_modules = ["\"CRustSlice!().h\""]) => "RustSlice<const swig_f_type!(T)>"
         "RustSlice<const swig_f_type!(T)>{$p.data, $p.len}";
-    ($p:f_type, req_modules = ["\"CRustSlice!().h\""]) <= "RustSlice<const swig_f_type!(T)>"
+    ($p:f_type, option = "CppSlice::RustSlice", req_modules = ["\"CRustSlice!().h\""]) <= "RustSlice<const swig_f_type!(T)>"
         "$p.as_c<swig_f_type!(CRustSlice!())>()";
+
+    ($p:f_type, option = "CppSlice::Std20", req_modules = ["\"CRustSlice!().h\"", "<span>"]) => "std::span<const swig_f_type!(T)>"
+        "std::span<const swig_f_type!(T)>{$p.data, $p.len}";
+    ($p:f_type, option = "CppSlice::Std20", req_modules = ["\"CRustSlice!().h\"", "<span>"]) <= "std::span<const swig_f_type!(T)>"
+        "swig_f_type!(CRustSlice!()){$p.data(), $p.size()}";
 );
 
 foreign_typemap!(
@@ -783,7 +843,7 @@ foreign_typemap!(
 "##);
     ($p:r_type) <T: SwigTypeIsReprC> &mut [T] => CRustSliceMut!() {
         $out =  CRustSliceMut!() {
-            data: $p.as_ptr(),
+            data: $p.as_mut_ptr(),
             len: $p.len(),
         };
     };
@@ -791,10 +851,15 @@ foreign_typemap!(
         assert!($p.len == 0 || !$p.data.is_null());
         $out = unsafe { ::std::slice::from_raw_parts_mut($p.data, $p.len) };
     };
-    ($p:f_type, req_modules = ["\"CRustSliceMut!().h\""]) => "RustSlice<swig_f_type!(T)>"
+    ($p:f_type, option = "CppSlice::RustSlice", req_modules = ["\"CRustSliceMut!().h\""]) => "RustSlice<swig_f_type!(T)>"
         "RustSlice<swig_f_type!(T)>{$p.data, $p.len}";
-    ($p:f_type, req_modules = ["\"CRustSliceMut!().h\""]) <= "RustSlice<swig_f_type!(T)>"
+    ($p:f_type, option = "CppSlice::RustSlice", req_modules = ["\"CRustSliceMut!().h\""]) <= "RustSlice<swig_f_type!(T)>"
         "$p.as_c<swig_f_type!(CRustSliceMut!())>()";
+
+    ($p:f_type, option = "CppSlice::Std20", req_modules = ["\"CRustSliceMut!().h\"", "<span>"]) => "std::span<swig_f_type!(T)>"
+        "std::span<swig_f_type!(T)>{$p.data, $p.len}";
+    ($p:f_type, option = "CppSlice::Std20", req_modules = ["\"CRustSliceMut!().h\"", "<span>"]) <= "std::span<swig_f_type!(T)>"
+        "swig_f_type!(CRustSliceMut!()){$p.data(), $p.size()}";
 );
 
 foreign_typemap!(
@@ -845,6 +910,377 @@ using CppRustVec!() = RustVec<CRustVec!(), CRustVecFree!()>;
         "CppRustVec!(){$p}";
 );
 
+foreign_typemap!(
+    generic_alias!(CRustMapEntry = swig_concat_idents!(CRustMapEntry, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustMap = swig_concat_idents!(CRustMap, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustMapFree = swig_concat_idents!(CRustMap, swig_i_type!(K), swig_i_type!(V), _free));
+    generic_alias!(CRustMapModule = swig_concat_idents!(rust_map_, swig_i_type!(K), swig_i_type!(V)));
+    define_c_type!(
+        module = "CRustMapModule!().h";
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustMapEntry!() {
+            key: swig_i_type!(K),
+            value: swig_i_type!(V),
+        }
+
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustMap!() {
+            data: *const CRustMapEntry!(),
+            len: usize,
+            capacity: usize,
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustMapFree!()(m: CRustMap!()) {
+            let v = unsafe { Vec::from_raw_parts(m.data as *mut CRustMapEntry!(), m.len, m.capacity) };
+            drop(v);
+        }
+    );
+    ($p:r_type) <K, V> HashMap<K, V> => CRustMap!() {
+        let mut entries: Vec<CRustMapEntry!()> = $p
+            .into_iter()
+            .map(|(k, v)| {
+                swig_from_rust_to_i_type!(K, k, key)
+                swig_from_rust_to_i_type!(V, v, value)
+                CRustMapEntry!() { key, value }
+            })
+            .collect();
+        let p = entries.as_mut_ptr();
+        let len = entries.len();
+        let cap = entries.capacity();
+        ::std::mem::forget(entries);
+        $out = CRustMap!() {
+            data: p,
+            len: len,
+            capacity: cap,
+        };
+    };
+    ($p:r_type) <K, V> HashMap<K, V> <= CRustMap!() {
+        let entries: &[CRustMapEntry!()] = if $p.len == 0 {
+            &[]
+        } else {
+            unsafe { ::std::slice::from_raw_parts($p.data, $p.len) }
+        };
+        $out = entries
+            .iter()
+            .map(|e| {
+                swig_from_i_type_to_rust!(K, e.key, key)
+                swig_from_i_type_to_rust!(V, e.value, value)
+                (key, value)
+            })
+            .collect();
+    };
+    ($p:f_type, $tmp:temporary, $idx:temporary,
+     req_modules = ["\"CRustMapModule!().h\"", "<unordered_map>"])
+        => "std::unordered_map<swig_f_type!(K), swig_f_type!(V)>"
+        r#"[&]() {
+            std::unordered_map<swig_f_type!(K), swig_f_type!(V)> $tmp;
+            $tmp.reserve($p.len);
+            for (size_t $idx = 0; $idx < $p.len; ++$idx) {
+                $tmp.emplace(swig_foreign_from_i_type!(K, $p.data[$idx].key), swig_foreign_from_i_type!(V, $p.data[$idx].value));
+            }
+            CRustMapFree!()($p);
+            return $tmp;
+        }()"#;
+    ($p:f_type, $tmp:temporary, $entry:temporary,
+     req_modules = ["\"CRustMapModule!().h\"", "<unordered_map>", "<vector>"])
+        <= "std::unordered_map<swig_f_type!(K), swig_f_type!(V)>"
+        r#"
+        std::vector<swig_f_type!(CRustMapEntry!())> $tmp;
+        $tmp.reserve($p.size());
+        for (const auto &$entry : $p) {
+            $tmp.push_back(swig_f_type!(CRustMapEntry!()){ swig_foreign_to_i_type!(K, $entry.first), swig_foreign_to_i_type!(V, $entry.second) });
+        }
+        $out = swig_f_type!(CRustMap!()){ $tmp.data(), $tmp.size(), $tmp.capacity() };"#;
+);
+
+foreign_typemap!(
+    generic_alias!(CRustMapEntry = swig_concat_idents!(CRustMapEntry, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustMap = swig_concat_idents!(CRustMap, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustMapFree = swig_concat_idents!(CRustMap, swig_i_type!(K), swig_i_type!(V), _free));
+    generic_alias!(CRustMapModule = swig_concat_idents!(rust_map_, swig_i_type!(K), swig_i_type!(V)));
+    define_c_type!(
+        module = "CRustMapModule!().h";
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustMapEntry!() {
+            key: swig_i_type!(K),
+            value: swig_i_type!(V),
+        }
+
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustMap!() {
+            data: *const CRustMapEntry!(),
+            len: usize,
+            capacity: usize,
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustMapFree!()(m: CRustMap!()) {
+            let v = unsafe { Vec::from_raw_parts(m.data as *mut CRustMapEntry!(), m.len, m.capacity) };
+            drop(v);
+        }
+    );
+    ($p:r_type) <K, V> BTreeMap<K, V> => CRustMap!() {
+        let mut entries: Vec<CRustMapEntry!()> = $p
+            .into_iter()
+            .map(|(k, v)| {
+                swig_from_rust_to_i_type!(K, k, key)
+                swig_from_rust_to_i_type!(V, v, value)
+                CRustMapEntry!() { key, value }
+            })
+            .collect();
+        let p = entries.as_mut_ptr();
+        let len = entries.len();
+        let cap = entries.capacity();
+        ::std::mem::forget(entries);
+        $out = CRustMap!() {
+            data: p,
+            len: len,
+            capacity: cap,
+        };
+    };
+    ($p:r_type) <K, V> BTreeMap<K, V> <= CRustMap!() {
+        let entries: &[CRustMapEntry!()] = if $p.len == 0 {
+            &[]
+        } else {
+            unsafe { ::std::slice::from_raw_parts($p.data, $p.len) }
+        };
+        $out = entries
+            .iter()
+            .map(|e| {
+                swig_from_i_type_to_rust!(K, e.key, key)
+                swig_from_i_type_to_rust!(V, e.value, value)
+                (key, value)
+            })
+            .collect();
+    };
+    ($p:f_type, $tmp:temporary, $idx:temporary,
+     req_modules = ["\"CRustMapModule!().h\"", "<map>"])
+        => "std::map<swig_f_type!(K), swig_f_type!(V)>"
+        r#"[&]() {
+            std::map<swig_f_type!(K), swig_f_type!(V)> $tmp;
+            for (size_t $idx = 0; $idx < $p.len; ++$idx) {
+                $tmp.emplace(swig_foreign_from_i_type!(K, $p.data[$idx].key), swig_foreign_from_i_type!(V, $p.data[$idx].value));
+            }
+            CRustMapFree!()($p);
+            return $tmp;
+        }()"#;
+    ($p:f_type, $tmp:temporary, $entry:temporary,
+     req_modules = ["\"CRustMapModule!().h\"", "<map>", "<vector>"])
+        <= "std::map<swig_f_type!(K), swig_f_type!(V)>"
+        r#"
+        std::vector<swig_f_type!(CRustMapEntry!())> $tmp;
+        $tmp.reserve($p.size());
+        for (const auto &$entry : $p) {
+            $tmp.push_back(swig_f_type!(CRustMapEntry!()){ swig_foreign_to_i_type!(K, $entry.first), swig_foreign_to_i_type!(V, $entry.second) });
+        }
+        $out = swig_f_type!(CRustMap!()){ $tmp.data(), $tmp.size(), $tmp.capacity() };"#;
+);
+
+foreign_typemap!(
+    generic_alias!(CRustHashMapView = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustHashMapViewEntry = swig_concat_idents!(CRustHashMapViewEntry, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustHashMapViewSize = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V), _size));
+    generic_alias!(CRustHashMapViewFind = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V), _find));
+    generic_alias!(CRustHashMapViewIterNew = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V), _iter_new));
+    generic_alias!(CRustHashMapViewIterNext = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V), _iter_next));
+    generic_alias!(CRustHashMapViewIterFree = swig_concat_idents!(CRustHashMapView, swig_i_type!(K), swig_i_type!(V), _iter_free));
+    generic_alias!(CRustHashMapViewModule = swig_concat_idents!(rust_hash_map_view_, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CppRustHashMap = swig_concat_idents!(RustHashMap, swig_i_type!(K), swig_i_type!(V)));
+    define_c_type!(
+        module = "CRustHashMapViewModule!().h";
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustHashMapView!() {
+            map: *const ::std::ffi::c_void,
+        }
+
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustHashMapViewEntry!() {
+            key: *const swig_subst_type!(K),
+            value: *const swig_subst_type!(V),
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustHashMapViewSize!()(m: CRustHashMapView!()) -> usize {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::HashMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            m.len()
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustHashMapViewFind!()(
+            m: CRustHashMapView!(),
+            key: swig_subst_type!(K),
+        ) -> *const swig_subst_type!(V) {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::HashMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            match m.get(&key) {
+                Some(v) => v,
+                None => ::std::ptr::null(),
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustHashMapViewIterNew!()(m: CRustHashMapView!()) -> *mut ::std::ffi::c_void {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::HashMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            Box::into_raw(Box::new(m.iter())) as *mut ::std::ffi::c_void
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustHashMapViewIterNext!()(it: *mut ::std::ffi::c_void) -> CRustHashMapViewEntry!() {
+            let it = unsafe {
+                &mut *(it as *mut ::std::collections::hash_map::Iter<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            match it.next() {
+                Some((key, value)) => CRustHashMapViewEntry!() { key, value },
+                None => CRustHashMapViewEntry!() {
+                    key: ::std::ptr::null(),
+                    value: ::std::ptr::null(),
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustHashMapViewIterFree!()(it: *mut ::std::ffi::c_void) {
+            let it = unsafe {
+                Box::from_raw(it as *mut ::std::collections::hash_map::Iter<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            drop(it);
+        }
+    );
+    foreign_code!(module = "CRustHashMapViewModule!().h";
+                    r##"
+#ifdef __cplusplus
+
+#include "rust_map_impl.hpp"
+
+namespace $RUST_SWIG_USER_NAMESPACE {
+using CppRustHashMap!() = RustMap<swig_f_type!(K), swig_f_type!(V), CRustHashMapView!(), CRustHashMapViewEntry!(),
+    CRustHashMapViewSize!(), CRustHashMapViewFind!(), CRustHashMapViewIterNew!(), CRustHashMapViewIterNext!(),
+    CRustHashMapViewIterFree!()>;
+}
+
+#endif
+"##);
+    ($p:r_type) <K: SwigTypeIsReprC, V: SwigTypeIsReprC> &HashMap<K, V> => CRustHashMapView!() {
+        $out = CRustHashMapView!() {
+            map: $p as *const _ as *const ::std::ffi::c_void,
+        };
+    };
+    ($p:f_type, req_modules = ["\"CRustHashMapViewModule!().h\""]) => "CppRustHashMap!()"
+        "CppRustHashMap!(){$p}";
+);
+
+foreign_typemap!(
+    generic_alias!(CRustBTreeMapView = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustBTreeMapViewEntry = swig_concat_idents!(CRustBTreeMapViewEntry, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CRustBTreeMapViewSize = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V), _size));
+    generic_alias!(CRustBTreeMapViewFind = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V), _find));
+    generic_alias!(CRustBTreeMapViewIterNew = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V), _iter_new));
+    generic_alias!(CRustBTreeMapViewIterNext = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V), _iter_next));
+    generic_alias!(CRustBTreeMapViewIterFree = swig_concat_idents!(CRustBTreeMapView, swig_i_type!(K), swig_i_type!(V), _iter_free));
+    generic_alias!(CRustBTreeMapViewModule = swig_concat_idents!(rust_btree_map_view_, swig_i_type!(K), swig_i_type!(V)));
+    generic_alias!(CppRustBTreeMap = swig_concat_idents!(RustBTreeMap, swig_i_type!(K), swig_i_type!(V)));
+    define_c_type!(
+        module = "CRustBTreeMapViewModule!().h";
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustBTreeMapView!() {
+            map: *const ::std::ffi::c_void,
+        }
+
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct CRustBTreeMapViewEntry!() {
+            key: *const swig_subst_type!(K),
+            value: *const swig_subst_type!(V),
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustBTreeMapViewSize!()(m: CRustBTreeMapView!()) -> usize {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::BTreeMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            m.len()
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustBTreeMapViewFind!()(
+            m: CRustBTreeMapView!(),
+            key: swig_subst_type!(K),
+        ) -> *const swig_subst_type!(V) {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::BTreeMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            match m.get(&key) {
+                Some(v) => v,
+                None => ::std::ptr::null(),
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustBTreeMapViewIterNew!()(m: CRustBTreeMapView!()) -> *mut ::std::ffi::c_void {
+            let m = unsafe {
+                &*(m.map as *const ::std::collections::BTreeMap<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            Box::into_raw(Box::new(m.iter())) as *mut ::std::ffi::c_void
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustBTreeMapViewIterNext!()(it: *mut ::std::ffi::c_void) -> CRustBTreeMapViewEntry!() {
+            let it = unsafe {
+                &mut *(it as *mut ::std::collections::btree_map::Iter<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            match it.next() {
+                Some((key, value)) => CRustBTreeMapViewEntry!() { key, value },
+                None => CRustBTreeMapViewEntry!() {
+                    key: ::std::ptr::null(),
+                    value: ::std::ptr::null(),
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn CRustBTreeMapViewIterFree!()(it: *mut ::std::ffi::c_void) {
+            let it = unsafe {
+                Box::from_raw(it as *mut ::std::collections::btree_map::Iter<swig_subst_type!(K), swig_subst_type!(V)>)
+            };
+            drop(it);
+        }
+    );
+    foreign_code!(module = "CRustBTreeMapViewModule!().h";
+                    r##"
+#ifdef __cplusplus
+
+#include "rust_map_impl.hpp"
+
+namespace $RUST_SWIG_USER_NAMESPACE {
+using CppRustBTreeMap!() = RustMap<swig_f_type!(K), swig_f_type!(V), CRustBTreeMapView!(), CRustBTreeMapViewEntry!(),
+    CRustBTreeMapViewSize!(), CRustBTreeMapViewFind!(), CRustBTreeMapViewIterNew!(), CRustBTreeMapViewIterNext!(),
+    CRustBTreeMapViewIterFree!()>;
+}
+
+#endif
+"##);
+    ($p:r_type) <K: SwigTypeIsReprC, V: SwigTypeIsReprC> &BTreeMap<K, V> => CRustBTreeMapView!() {
+        $out = CRustBTreeMapView!() {
+            map: $p as *const _ as *const ::std::ffi::c_void,
+        };
+    };
+    ($p:f_type, req_modules = ["\"CRustBTreeMapViewModule!().h\""]) => "CppRustBTreeMap!()"
+        "CppRustBTreeMap!(){$p}";
+);
+
 #[allow(dead_code)]
 #[repr(C)]
 #[derive(Copy, Clone)]
@@ -1050,6 +1486,16 @@ foreign_typemap!(
        r#"$p.is_ok != 0 ?
             std::variant<void *, swig_f_type!(T)> { nullptr } :
             std::variant<void *, swig_f_type!(T)> { swig_foreign_from_i_type!(T, $p.data.err) }"#;
+
+   ($p:f_type, option = "CppVariant::Std23Expected", req_modules = ["\"CRustResModule!().h\"", "<expected>"]) => "std::expected<void, swig_f_type!(T)>"
+       r#"$p.is_ok != 0 ?
+            std::expected<void, swig_f_type!(T)> {} :
+            std::expected<void, swig_f_type!(T)> { std::unexpect, swig_foreign_from_i_type!(T, $p.data.err) }"#;
+
+   ($p:f_type, option = "CppVariant::TlExpected", req_modules = ["\"CRustResModule!().h\"", "<tl/expected.hpp>"]) => "tl::expected<void, swig_f_type!(T)>"
+       r#"$p.is_ok != 0 ?
+            tl::expected<void, swig_f_type!(T)> {} :
+            tl::expected<void, swig_f_type!(T)> { tl::unexpect, swig_foreign_from_i_type!(T, $p.data.err) }"#;
 );
 
 foreign_typemap!(
@@ -1101,13 +1547,23 @@ foreign_typemap!(
               std::variant<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T1, $p.data.ok) } :
               std::variant<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T2, $p.data.err) }"#;
 
+   ($p:f_type, option = "CppVariant::Std23Expected", req_modules = ["\"CRustResModule!().h\"", "<expected>"]) => "std::expected<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"$p.is_ok != 0 ?
+              std::expected<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T1, $p.data.ok) } :
+              std::expected<swig_f_type!(T1), swig_f_type!(T2)> { std::unexpect, swig_foreign_from_i_type!(T2, $p.data.err) }"#;
+
+   ($p:f_type, option = "CppVariant::TlExpected", req_modules = ["\"CRustResModule!().h\"", "<tl/expected.hpp>"]) => "tl::expected<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"$p.is_ok != 0 ?
+              tl::expected<swig_f_type!(T1), swig_f_type!(T2)> { swig_foreign_from_i_type!(T1, $p.data.ok) } :
+              tl::expected<swig_f_type!(T1), swig_f_type!(T2)> { tl::unexpect, swig_foreign_from_i_type!(T2, $p.data.err) }"#;
+
    ($p:r_type) <T1, T2> Result<T1, T2> <= CRustRes!() {
        $out = unsafe {
            if $p.is_ok != 0 {
                swig_from_i_type_to_rust!(T1, $p.data.ok, x)
                Ok(x)
            } else {
-               swig_from_i_type_to_rust!(T1, $p.data.err, x)
+               swig_from_i_type_to_rust!(T2, $p.data.err, x)
                Err(x)
            }
        };
@@ -1144,4 +1600,34 @@ foreign_typemap!(
                 $out.data.err = swig_foreign_to_i_type!(T2, $tmp);
                 $out.is_ok = 0;
             }"#;
+
+   ($p:f_type, option = "CppVariant::Std23Expected", $tmp:temporary,
+    req_modules = ["\"CRustResModule!().h\"", "<expected>"])
+       <= "std::expected<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"
+            $out;
+            if ($p.has_value()) {
+                swig_f_type!(T1) $tmp = std::move(*$p);
+                $out.data.ok = swig_foreign_to_i_type!(T1, $tmp);
+                $out.is_ok = 1;
+            } else {
+                swig_f_type!(T2) $tmp = std::move($p.error());
+                $out.data.err = swig_foreign_to_i_type!(T2, $tmp);
+                $out.is_ok = 0;
+            }"#;
+
+   ($p:f_type, option = "CppVariant::TlExpected", $tmp:temporary,
+    req_modules = ["\"CRustResModule!().h\"", "<tl/expected.hpp>"])
+       <= "tl::expected<swig_f_type!(T1), swig_f_type!(T2)>"
+       r#"
+            $out;
+            if ($p.has_value()) {
+                swig_f_type!(T1) $tmp = std::move(*$p);
+                $out.data.ok = swig_foreign_to_i_type!(T1, $tmp);
+                $out.is_ok = 1;
+            } else {
+                swig_f_type!(T2) $tmp = std::move($p.error());
+                $out.data.err = swig_foreign_to_i_type!(T2, $tmp);
+                $out.is_ok = 0;
+            }"#;
 );
@@ -9,6 +9,7 @@ use syn::spanned::Spanned;
 use crate::{
     code_parse::parse_fn_args,
     cpp::{map_type::map_repr_c_type, CppContext, CppForeignMethodSignature, MergeCItemsFlags},
+    doc_comments::{DocComment, ExportedItems},
     error::{panic_on_syn_error, DiagnosticError},
     file_cache::FileWriteCache,
     namegen::new_unique_name,
@@ -24,16 +25,22 @@ use crate::{
 pub(in crate::cpp) fn doc_comments_to_c_comments(
     doc_comments: &[String],
     class_comments: bool,
+    exported_items: &ExportedItems,
 ) -> String {
     let mut comments = String::new();
-    for (i, comment) in doc_comments.iter().enumerate() {
+    let lines = DocComment::parse(doc_comments).to_doxygen(exported_items);
+    for (i, comment) in lines.iter().enumerate() {
         if i != 0 {
             comments.push('\n');
         }
         if !class_comments {
             comments.push_str("    ");
         }
-        write!(&mut comments, "//{}", comment.trim()).unwrap();
+        if comment.is_empty() {
+            comments.push_str("///");
+        } else {
+            write!(&mut comments, "/// {}", comment).unwrap();
+        }
     }
     comments
 }
@@ -60,8 +67,34 @@ where
     buf
 }
 
-pub(in crate::cpp) fn c_class_type(class: &ForeignClassInfo) -> String {
-    format!("{}Opaque", class.name)
+pub(in crate::cpp) fn c_class_type(c_symbol_prefix: &str, class: &ForeignClassInfo) -> String {
+    format!("{}{}Opaque", c_symbol_prefix, class.name)
+}
+
+/// Name of C type or function from `define_c_type!`,
+/// Rust code and C++ code in namespace use name without prefix
+pub(in crate::cpp) fn c_item_name(c_symbol_prefix: &str, ident: &syn::Ident) -> String {
+    format!("{}{}", c_symbol_prefix, ident)
+}
+
+/// Without prefix C name is the same as in C++ code,
+/// otherwise make it visible in namespace without prefix
+fn write_cpp_alias_for_c_item(out: &mut FileWriteCache, namespace_name: &str, decl: &str) {
+    use std::io::Write;
+
+    write!(
+        out,
+        r#"
+#ifdef __cplusplus
+namespace {namespace} {{
+{decl}
+}} // namespace {namespace}
+#endif
+"#,
+        namespace = namespace_name,
+        decl = decl,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
 }
 
 pub(in crate::cpp) fn cpp_generate_args_with_types<'a, NI: Iterator<Item = &'a str>>(
@@ -208,7 +241,16 @@ pub(in crate::cpp) fn generate_c_type(
                     }
                 }
                 add_func_forward_decl(ctx, f, src_id, module_name)?;
-                ctx.rust_code.push(f.into_token_stream());
+                if ctx.cfg.c_symbol_prefix.is_empty() {
+                    ctx.rust_code.push(f.into_token_stream());
+                } else {
+                    let mut f = f.clone();
+                    let export_name = c_item_name(&ctx.cfg.c_symbol_prefix, &f.sig.ident);
+                    f.attrs.retain(|a| !a.path.is_ident("no_mangle"));
+                    f.attrs
+                        .push(syn::parse_quote! { #[export_name = #export_name] });
+                    ctx.rust_code.push(f.into_token_stream());
+                }
                 {
                     let common_files = &mut ctx.common_files;
                     let out: &mut FileWriteCache = file_for_module!(ctx, common_files, module_name);
@@ -268,7 +310,8 @@ fn do_generate_c_type(
 ) -> Result<(), DiagnosticError> {
     use std::io::Write;
 
-    let s_id = format!("{} {}", ctype.c_type_prefix(), ctype.name());
+    let c_name = c_item_name(&ctx.cfg.c_symbol_prefix, ctype.name());
+    let s_id = format!("{} {}", ctype.c_type_prefix(), c_name);
     {
         let common_files = &mut ctx.common_files;
         let file_out: &mut FileWriteCache = file_for_module!(ctx, common_files, c_type_header_name);
@@ -391,6 +434,13 @@ extern "C" {
 "##,
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
+    if !ctx.cfg.c_symbol_prefix.is_empty() {
+        write_cpp_alias_for_c_item(
+            file_out,
+            &ctx.cfg.namespace_name,
+            &format!("using {} = {};", ctype.name(), s_id),
+        );
+    }
     file_out.define_item(s_id);
     Ok(())
 }
@@ -415,31 +465,25 @@ extern "C" {
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
-    let mut fn_decl_out = Vec::with_capacity(100);
     let mut includes = FxHashSet::<SmolStr>::default();
 
-    match f.sig.output {
-        syn::ReturnType::Default => {
-            fn_decl_out
-                .write_all(b"void")
-                .expect(WRITE_TO_MEM_FAILED_MSG);
-        }
+    let ret_type = match f.sig.output {
+        syn::ReturnType::Default => "void".to_string(),
         syn::ReturnType::Type(_, ref ty) => {
             let rty = ctx.conv_map.find_or_alloc_rust_type(ty, src_id);
             let fti = map_repr_c_type(ctx, &rty, (src_id, rty.ty.span()))?;
             for inc in &fti.provides_by_module {
                 includes.insert(inc.clone());
             }
-            fn_decl_out
-                .write_all(fti.base.name.as_str().as_bytes())
-                .expect(WRITE_TO_MEM_FAILED_MSG);
+            fti.base.name.to_string()
         }
-    }
-    write!(&mut fn_decl_out, " {}(", f.sig.ident).expect(WRITE_TO_MEM_FAILED_MSG);
+    };
 
     let fn_args = parse_fn_args(f.sig.inputs.clone())
         .map_err(|err| DiagnosticError::from_syn_err(src_id, err))?
         .0;
+    let mut args_with_types = String::new();
+    let mut args = String::new();
     for (i, arg) in fn_args.iter().enumerate() {
         match arg {
             FnArg::SelfArg(sp, _) => {
@@ -456,20 +500,21 @@ extern "C" {
                     includes.insert(inc.clone());
                 }
                 if i != 0 {
-                    fn_decl_out.write_all(b", ").expect(WRITE_TO_MEM_FAILED_MSG);
+                    args_with_types.push_str(", ");
+                    args.push_str(", ");
                 }
                 write!(
-                    &mut fn_decl_out,
+                    &mut args_with_types,
                     "{} {}",
                     fti.base.name.as_str(),
                     named_arg.name
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
+                args.push_str(&named_arg.name);
             }
         }
     }
-
-    fn_decl_out.write_all(b");").expect(WRITE_TO_MEM_FAILED_MSG);
+    let c_name = c_item_name(&ctx.cfg.c_symbol_prefix, &f.sig.ident);
 
     let common_files = &mut ctx.common_files;
     let out: &mut FileWriteCache = file_for_module!(ctx, common_files, c_type_header_name);
@@ -479,7 +524,7 @@ extern "C" {
             writeln!(out, "#include {}", inc).expect(WRITE_TO_MEM_FAILED_MSG);
         }
     }
-    out.write_all(&fn_decl_out).expect(WRITE_TO_MEM_FAILED_MSG);
+    write!(out, "{} {}({});", ret_type, c_name, args_with_types).expect(WRITE_TO_MEM_FAILED_MSG);
 
     out.write_all(
         br##"
@@ -489,5 +534,15 @@ extern "C" {
 "##,
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
+    if !ctx.cfg.c_symbol_prefix.is_empty() {
+        write_cpp_alias_for_c_item(
+            out,
+            &ctx.cfg.namespace_name,
+            &format!(
+                "inline {} {}({}) {{ return ::{}({}); }}",
+                ret_type, f.sig.ident, args_with_types, c_name, args,
+            ),
+        );
+    }
     Ok(())
 }
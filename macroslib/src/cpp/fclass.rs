@@ -18,7 +18,7 @@ use crate::{
     file_cache::FileWriteCache,
     namegen::new_unique_name,
     typemap::{
-        ast::{list_lifetimes, strip_lifetimes},
+        ast::{if_result_return_ok_err_types, list_lifetimes, strip_lifetimes},
         ty::RustType,
         utils::{
             convert_to_heap_pointer, create_suitable_types_for_constructor_and_self,
@@ -28,8 +28,8 @@ use crate::{
         ForeignTypeInfo, TypeConvCodeSubstParam, TypeMap, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
         TO_VAR_TYPE_TEMPLATE,
     },
-    types::{ForeignClassInfo, MethodAccess, MethodVariant, SelfTypeVariant},
-    KNOWN_CLASS_DERIVES, PLAIN_CLASS, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
+    types::{ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, SelfTypeVariant},
+    CppVariant, KNOWN_CLASS_DERIVES, PLAIN_CLASS, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::cpp) fn generate(ctx: &mut CppContext, class: &ForeignClassInfo) -> Result<()> {
@@ -94,8 +94,9 @@ fn do_generate(
         };
     }
 
-    let c_class_type = cpp_code::c_class_type(class);
-    let class_doc_comments = cpp_code::doc_comments_to_c_comments(&class.doc_comments, true);
+    let c_class_type = cpp_code::c_class_type(&ctx.cfg.c_symbol_prefix, class);
+    let class_doc_comments =
+        cpp_code::doc_comments_to_c_comments(&class.doc_comments, true, &ctx.exported_items);
 
     generte_c_header_preamble(ctx, &class_doc_comments, &c_class_type, &mut c_include_f);
     let plain_class = need_plain_class(class);
@@ -177,10 +178,22 @@ May be you need to use `private constructor = empty;` syntax?",
     let mut need_destructor = false;
     //because of VC++ has problem with cross-references of types
     let mut inline_impl = String::new();
+    let impl_in_source = ctx.cfg.separate_impl_sources;
+    // methods of template class defined in source file should be instantiated explicitly
+    let mut explicit_instantiations = Vec::<String>::new();
+    // methods that can not be instantiated explicitly, so they stay in header
+    let mut header_impl = String::new();
 
     for (method, f_method) in class.methods.iter().zip(methods_sign) {
         c_include_f
-            .write_all(cpp_code::doc_comments_to_c_comments(&method.doc_comments, false).as_bytes())
+            .write_all(
+                cpp_code::doc_comments_to_c_comments(
+                    &method.doc_comments,
+                    false,
+                    &ctx.exported_items,
+                )
+                .as_bytes(),
+            )
             .expect(WRITE_TO_MEM_FAILED_MSG);
 
         let method_access = match method.access {
@@ -196,9 +209,16 @@ May be you need to use `private constructor = empty;` syntax?",
         }
         last_cpp_access = Some(method_access);
         cpp_include_f
-            .write_all(cpp_code::doc_comments_to_c_comments(&method.doc_comments, false).as_bytes())
+            .write_all(
+                cpp_code::doc_comments_to_c_comments(
+                    &method.doc_comments,
+                    false,
+                    &ctx.exported_items,
+                )
+                .as_bytes(),
+            )
             .expect(WRITE_TO_MEM_FAILED_MSG);
-        let c_func_name = c_func_name(class, method);
+        let c_func_name = c_func_name(ctx.cfg, class, method);
         let c_args_with_types =
             cpp_code::c_generate_args_with_types(f_method, method.arg_names_without_self(), false);
         let comma_c_args_with_types = if c_args_with_types.is_empty() {
@@ -341,6 +361,25 @@ May be you need to use `private constructor = empty;` syntax?",
         };
         //rename types like "struct Foo" to "Foo" to make VC++ compiler happy
         let cpp_ret_type = cpp_ret_type.as_str().replace("struct", "");
+        let ret_ok_err_types = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let ret_rust_ty = ctx.conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+                if_result_return_ok_err_types(&ret_rust_ty)
+            }
+        };
+        // with `use_exceptions` conversation of `Result` throws exception in case of error
+        let may_throw = ctx.cfg.use_exceptions && ret_ok_err_types.is_some();
+        let noexcept = if may_throw { "" } else { " noexcept" };
+        let in_source = impl_in_source
+            && match ret_ok_err_types {
+                Some((ref ok_ty, ref err_ty)) => {
+                    !is_variant_with_same_types(ctx, class, method, ok_ty, err_ty)?
+                }
+                None => true,
+            };
+        let inline = if in_source { "" } else { "inline " };
+        let impl_start = inline_impl.len();
 
         let input_to_output_ret_code = if let Some((_, ref arg_name)) = input_to_output_arg {
             format!(
@@ -368,7 +407,8 @@ May be you need to use `private constructor = empty;` syntax?",
                 writeln!(
                     cpp_include_f,
                     r#"
-    static {cpp_ret_type} {method_name}({cpp_args_with_types}) noexcept;"#,
+    static {cpp_ret_type} {method_name}({cpp_args_with_types}){noexcept};"#,
+                    noexcept = noexcept,
                     method_name = method_name,
                     cpp_ret_type = cpp_ret_type,
                     cpp_args_with_types = cpp_args_with_types,
@@ -376,13 +416,31 @@ May be you need to use `private constructor = empty;` syntax?",
                 .expect(WRITE_TO_MEM_FAILED_MSG);
 
                 if !plain_class {
+                    let own_data_variants: &[&str] = if in_source {
+                        &["true", "false"]
+                    } else {
+                        &[]
+                    };
+                    for own_data in own_data_variants {
+                        explicit_instantiations.push(format!(
+                            "{cpp_ret_type} {class_name}<{own_data}>::{method_name}({cpp_args_with_types}){noexcept}",
+                            cpp_ret_type = cpp_ret_type,
+                            class_name = class_name,
+                            own_data = own_data,
+                            method_name = method_name,
+                            cpp_args_with_types = cpp_args_with_types,
+                            noexcept = noexcept,
+                        ));
+                    }
                     write!(
                         &mut inline_impl,
                         r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) noexcept
+    {inline}{cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){noexcept}
     {{
 {conv_args_code}"#,
+                        inline = inline,
+                        noexcept = noexcept,
                         cpp_ret_type = cpp_ret_type,
                         class_name = class_name,
                         method_name = method_name,
@@ -393,9 +451,11 @@ May be you need to use `private constructor = empty;` syntax?",
                     write!(
                         &mut inline_impl,
                         r#"
-    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}) noexcept
+    {inline}{cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}){noexcept}
     {{
 {conv_args_code}"#,
+                        inline = inline,
+                        noexcept = noexcept,
                         cpp_ret_type = cpp_ret_type,
                         class_name = class_name,
                         method_name = method_name,
@@ -440,6 +500,15 @@ May be you need to use `private constructor = empty;` syntax?",
                 } else {
                     ""
                 };
+                let cpp_qualifiers = format!(
+                    "{}{}",
+                    if self_variant.is_read_only() {
+                        " const"
+                    } else {
+                        ""
+                    },
+                    noexcept
+                );
                 writeln!(
                     c_include_f,
                     r#"
@@ -455,37 +524,58 @@ May be you need to use `private constructor = empty;` syntax?",
                 writeln!(
                     cpp_include_f,
                     r#"
-    {cpp_ret_type} {method_name}({cpp_args_with_types}) {const_if_readonly}noexcept;"#,
+    {cpp_ret_type} {method_name}({cpp_args_with_types}){cpp_qualifiers};"#,
                     method_name = method_name,
                     cpp_ret_type = cpp_ret_type,
                     cpp_args_with_types = cpp_args_with_types,
-                    const_if_readonly = const_if_readonly,
+                    cpp_qualifiers = cpp_qualifiers,
                 )
                 .expect(WRITE_TO_MEM_FAILED_MSG);
 
                 if !plain_class {
+                    // `self_` of `Ref` class is pointer to const, so only methods with `&self` are valid for it
+                    let own_data_variants: &[&str] = if !in_source {
+                        &[]
+                    } else if self_variant.is_read_only() {
+                        &["true", "false"]
+                    } else {
+                        &["true"]
+                    };
+                    for own_data in own_data_variants {
+                        explicit_instantiations.push(format!(
+                            "{cpp_ret_type} {class_name}<{own_data}>::{method_name}({cpp_args_with_types}){cpp_qualifiers}",
+                            cpp_ret_type = cpp_ret_type,
+                            class_name = class_name,
+                            own_data = own_data,
+                            method_name = method_name,
+                            cpp_args_with_types = cpp_args_with_types,
+                            cpp_qualifiers = cpp_qualifiers,
+                        ));
+                    }
                     write!(&mut inline_impl, r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) {const_if_readonly}noexcept
+    {inline}{cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){cpp_qualifiers}
     {{
 {conv_args_code}"#,
+                           inline = inline,
                            cpp_args_with_types = cpp_args_with_types,
                            method_name = method_name,
                            class_name = class_name,
                            cpp_ret_type = cpp_ret_type,
-                           const_if_readonly = const_if_readonly,
+                           cpp_qualifiers = cpp_qualifiers,
                            conv_args_code = conv_args_code,
                     )
                 } else {
                     write!(&mut inline_impl, r#"
-    inline {cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}) {const_if_readonly}noexcept
+    {inline}{cpp_ret_type} {class_name}::{method_name}({cpp_args_with_types}){cpp_qualifiers}
     {{
 {conv_args_code}"#,
+                           inline = inline,
                            cpp_args_with_types = cpp_args_with_types,
                            method_name = method_name,
                            class_name = class_name,
                            cpp_ret_type = cpp_ret_type,
-                           const_if_readonly = const_if_readonly,
+                           cpp_qualifiers = cpp_qualifiers,
                            conv_args_code = conv_args_code,
                     )
                 }
@@ -591,6 +681,10 @@ May be you need to use `private constructor = empty;` syntax?",
                 }
             }
         }
+        if !in_source && impl_in_source {
+            header_impl.push_str(&inline_impl[impl_start..]);
+            inline_impl.truncate(impl_start);
+        }
     }
 
     if need_destructor {
@@ -603,7 +697,7 @@ May be you need to use `private constructor = empty;` syntax?",
         );
 
         let unpack_code = unpack_from_heap_pointer(&this_type, "this", false);
-        let c_destructor_name = format!("{}_delete", class.name);
+        let c_destructor_name = format!("{}{}_delete", ctx.cfg.c_symbol_prefix, class.name);
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
@@ -696,7 +790,45 @@ private:
     }
     .expect(WRITE_TO_MEM_FAILED_MSG);
     // Write method implementations.
-    if ctx.cfg.separate_impl_headers {
+    if impl_in_source {
+        cpp_include_f
+            .write_all(header_impl.as_bytes())
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        for inst in &explicit_instantiations {
+            writeln!(cpp_include_f, "extern template {};", inst).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        writeln!(
+            cpp_include_f,
+            r#"
+}} // namespace {namespace}"#,
+            namespace = ctx.cfg.namespace_name
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        let cpp_src_path = ctx.cfg.output_dir.join(format!("{}.cpp", class.name));
+        let mut cpp_src_f = FileWriteCache::new(&cpp_src_path, ctx.generated_foreign_files);
+        let header_name = match ctx.cfg.single_header_name {
+            Some(ref single_header_name) => single_header_name.clone(),
+            None => cpp_code::cpp_header_name(class),
+        };
+        writeln!(
+            cpp_src_f,
+            r#"// Automatically generated by flapigen
+#include "{header_name}"
+
+namespace {namespace} {{"#,
+            header_name = header_name,
+            namespace = ctx.cfg.namespace_name,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        for inst in &explicit_instantiations {
+            writeln!(&mut inline_impl, "    template {};", inst).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        write_methods_impls(&mut cpp_src_f, &ctx.cfg.namespace_name, &inline_impl)
+            .map_err(map_write_err!(cpp_src_path))?;
+        cpp_src_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(cpp_src_path))?;
+    } else if ctx.cfg.separate_impl_headers || ctx.cfg.single_header_name.is_some() {
         writeln!(
             cpp_include_f,
             r#"
@@ -992,6 +1124,37 @@ fn write_methods_impls(
     )
 }
 
+/// `std::variant<T, T>` and `boost::variant<T, T>` can not be constructed from `T`,
+/// so conversation of such `Result` compiles only until method is instantiated
+fn is_variant_with_same_types(
+    ctx: &mut CppContext,
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+    ok_ty: &Type,
+    err_ty: &Type,
+) -> Result<bool> {
+    if ctx.cfg.use_exceptions
+        || !matches!(ctx.cfg.cpp_variant, CppVariant::Std17 | CppVariant::Boost)
+    {
+        return Ok(false);
+    }
+    let mut cpp_type_names = Vec::with_capacity(2);
+    for ty in &[ok_ty, err_ty] {
+        let rust_ty = ctx.conv_map.find_or_alloc_rust_type(ty, class.src_id);
+        let f_type = map_type(
+            ctx,
+            &rust_ty,
+            Direction::Outgoing,
+            (class.src_id, method.rust_id.span()),
+        )?;
+        cpp_type_names.push(match f_type.cpp_converter {
+            Some(cpp_converter) => cpp_converter.typename,
+            None => f_type.base.name,
+        });
+    }
+    Ok(cpp_type_names[0] == cpp_type_names[1])
+}
+
 fn find_suitable_foreign_types_for_methods(
     ctx: &mut CppContext,
     class: &ForeignClassInfo,
@@ -1278,7 +1441,7 @@ fn genearte_copy_stuff(
                     ),
                 )
             })?;
-        let c_clone_func = c_func_name(class, &class.methods[pos]);
+        let c_clone_func = c_func_name(ctx.cfg, class, &class.methods[pos]);
 
         writeln!(
             cpp_include_f,
@@ -1335,7 +1498,7 @@ fn genearte_copy_stuff(
             panic_on_syn_error("clone method for smart_ptr_derived class", unpack_code, err)
         });
 
-        let clone_fn_name = do_c_func_name(class, MethodAccess::Private, "clone");
+        let clone_fn_name = do_c_func_name(ctx.cfg, class, MethodAccess::Private, "clone");
         let clone_fn_name = Ident::new(&clone_fn_name, Span::call_site());
         let this_type_ty = this_type.to_type_without_lifetimes();
         let this_type_for_method_ty = this_type_for_method.to_type_without_lifetimes();
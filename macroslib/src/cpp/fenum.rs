@@ -11,13 +11,17 @@ use crate::{
     typemap::{
         ast::{parse_ty_with_given_span, TypeName},
         ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS},
-        TypeConvCode, FROM_VAR_TEMPLATE,
+        utils::{rust_code_for_bitflags, rust_code_for_bitflags_item_bits},
+        TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
     types::ForeignEnumInfo,
     WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::cpp) fn generate_enum(ctx: &mut CppContext, fenum: &ForeignEnumInfo) -> Result<()> {
+    if fenum.is_bitflags {
+        return generate_bitflags(ctx, fenum);
+    }
     if (fenum.items.len() as u64) >= u64::from(u32::max_value()) {
         return Err(DiagnosticError::new(
             fenum.src_id,
@@ -90,7 +94,8 @@ fn generate_c_code_for_enum(
         .output_dir
         .join(cpp_code::cpp_header_name_for_enum(enum_info));
     let mut file = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
-    let enum_doc_comments = cpp_code::doc_comments_to_c_comments(&enum_info.doc_comments, true);
+    let enum_doc_comments =
+        cpp_code::doc_comments_to_c_comments(&enum_info.doc_comments, true, &ctx.exported_items);
     let enum_class_keyword = if ctx.cfg.use_enum_class {
         "class"
     } else {
@@ -114,7 +119,8 @@ enum {enum_class_keyword} {enum_name} {{"#,
 
     for (i, item) in enum_info.items.iter().enumerate() {
         //Enums are aligned left, so we pass true to get left aligned comments.
-        let mut doc_comments = cpp_code::doc_comments_to_c_comments(&item.doc_comments, true);
+        let mut doc_comments =
+            cpp_code::doc_comments_to_c_comments(&item.doc_comments, true, &ctx.exported_items);
         if !doc_comments.is_empty() && !doc_comments.ends_with('\n') {
             doc_comments.push('\n');
         }
@@ -122,7 +128,7 @@ enum {enum_class_keyword} {enum_name} {{"#,
             file,
             "{doc_comments}{item_name} = {index}{separator}",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments,
             separator = if i == enum_info.items.len() - 1 {
                 "\n"
@@ -153,9 +159,9 @@ fn generate_rust_trait_for_enum(ctx: &mut CppContext, enum_info: &ForeignEnumInf
     let mut arms_to_u32 = Vec::with_capacity(enum_info.items.len());
     let mut arms_from_u32 = Vec::with_capacity(enum_info.items.len());
     assert!((enum_info.items.len() as u64) <= u64::from(u32::max_value()));
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         let item_name = &item.rust_name;
-        let idx = i as u32;
+        let idx = item.value;
         arms_to_u32.push(quote! { #item_name => #idx });
         arms_from_u32.push(quote! { #idx => #item_name });
     }
@@ -181,3 +187,236 @@ fn generate_rust_trait_for_enum(ctx: &mut CppContext, enum_info: &ForeignEnumInf
 
     Ok(())
 }
+
+fn generate_bitflags(ctx: &mut CppContext, fenum: &ForeignEnumInfo) -> Result<()> {
+    trace!("bitflags_ti: {}", fenum.name);
+    let flags_ti: Type = parse_ty_with_given_span(&fenum.name.to_string(), fenum.name.span())
+        .map_err(|err| DiagnosticError::from_syn_err(fenum.src_id, err))?;
+    let flags_rty = ctx.conv_map.find_or_alloc_rust_type_that_implements(
+        &flags_ti,
+        &["SwigForeignBitflags"],
+        fenum.src_id,
+    );
+
+    let item_bits_func = format!("{}{}_item_bits", ctx.cfg.c_symbol_prefix, fenum.name);
+    generate_cpp_code_for_bitflags(ctx, fenum, &item_bits_func)?;
+    ctx.rust_code.push(rust_code_for_bitflags(fenum));
+    ctx.rust_code
+        .push(rust_code_for_bitflags_item_bits(fenum, &item_bits_func));
+
+    let u32_rty = ctx
+        .conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_type! { u32 });
+
+    let flags_ftype = ForeignTypeS {
+        name: TypeName::new(fenum.name.to_string(), (fenum.src_id, fenum.name.span())),
+        provides_by_module: vec![
+            format!("\"{}\"", cpp_code::cpp_header_name_for_enum(fenum)).into()
+        ],
+        into_from_rust: Some(ForeignConversationRule {
+            rust_ty: flags_rty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: u32_rty.to_idx(),
+                conv_code: Rc::new(TypeConvCode::new(
+                    format!(
+                        "{flags_name}::from_bits_truncate({var})",
+                        flags_name = fenum.name,
+                        var = FROM_VAR_TEMPLATE
+                    ),
+                    invalid_src_id_span(),
+                )),
+            }),
+        }),
+        from_into_rust: Some(ForeignConversationRule {
+            rust_ty: flags_rty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: u32_rty.to_idx(),
+                conv_code: Rc::new(TypeConvCode::new(
+                    format!("{}.bits()", FROM_VAR_TEMPLATE),
+                    invalid_src_id_span(),
+                )),
+            }),
+        }),
+        name_prefix: None,
+    };
+    ctx.conv_map.alloc_foreign_type(flags_ftype)?;
+
+    // C++ class can not hold unknown bits, see `from_bits` there
+    ctx.conv_map.add_conversation_rule(
+        u32_rty.to_idx(),
+        flags_rty.to_idx(),
+        TypeConvCode::new2(
+            format!(
+                "let {} = {}::from_bits_truncate({} as _);",
+                TO_VAR_TEMPLATE, fenum.name, FROM_VAR_TEMPLATE
+            ),
+            invalid_src_id_span(),
+        )
+        .into(),
+    );
+    ctx.conv_map.add_conversation_rule(
+        flags_rty.to_idx(),
+        u32_rty.to_idx(),
+        TypeConvCode::new2(
+            format!(
+                "let {} = {}.as_i32() as u32;",
+                TO_VAR_TEMPLATE, FROM_VAR_TEMPLATE
+            ),
+            invalid_src_id_span(),
+        )
+        .into(),
+    );
+    Ok(())
+}
+
+fn generate_cpp_code_for_bitflags(
+    ctx: &mut CppContext,
+    fenum: &ForeignEnumInfo,
+    item_bits_func: &str,
+) -> Result<()> {
+    let path = ctx
+        .cfg
+        .output_dir
+        .join(cpp_code::cpp_header_name_for_enum(fenum));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let doc_comments =
+        cpp_code::doc_comments_to_c_comments(&fenum.doc_comments, true, &ctx.exported_items);
+
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+#pragma once
+
+#include <cstdint>
+
+extern "C" {{
+uint32_t {item_bits_func}(uint32_t item);
+}}
+
+namespace {namespace} {{
+{doc_comments}
+class {flags_name} final {{
+public:"#,
+        namespace = ctx.cfg.namespace_name,
+        doc_comments = doc_comments,
+        flags_name = fenum.name,
+        item_bits_func = item_bits_func,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    // values of Rust constants are unknown during code generation, so ask Rust about them
+    for (i, item) in fenum.items.iter().enumerate() {
+        let mut doc_comments =
+            cpp_code::doc_comments_to_c_comments(&item.doc_comments, false, &ctx.exported_items);
+        if !doc_comments.is_empty() {
+            doc_comments = doc_comments.replace('\n', "\n    ");
+            doc_comments.insert_str(0, "    ");
+            doc_comments.push('\n');
+        }
+        writeln!(
+            file,
+            "{doc_comments}    static {flags_name} {item_name}() noexcept {{ return {flags_name}({item_bits_func}({i}), 0); }}",
+            doc_comments = doc_comments,
+            flags_name = fenum.name,
+            item_name = item.name,
+            item_bits_func = item_bits_func,
+            i = i,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    let all_bits = if fenum.items.is_empty() {
+        "0u".to_string()
+    } else {
+        fenum
+            .items
+            .iter()
+            .map(|item| format!("{}().bits_", item.name))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+
+    writeln!(
+        file,
+        r#"
+    constexpr {flags_name}() noexcept
+        : bits_(0)
+    {{
+    }}
+    /// \return all known flags
+    static {flags_name} all() noexcept {{ return {flags_name}({all_bits}, 0); }}
+    /// Convert bits to flags
+    /// \return false and not modify `flags` if there are unknown bits
+    static bool from_bits(uint32_t bits, {flags_name} &flags) noexcept
+    {{
+        if ((bits & ~all().bits_) != 0) {{
+            return false;
+        }}
+        flags = {flags_name}(bits, 0);
+        return true;
+    }}
+    /// Convert bits to flags, unknown bits are dropped
+    static {flags_name} from_bits_truncate(uint32_t bits) noexcept
+    {{
+        return {flags_name}(bits & all().bits_, 0);
+    }}
+    /// \return bits of flags, the same as Rust uses
+    constexpr uint32_t bits() const noexcept {{ return bits_; }}
+    constexpr bool empty() const noexcept {{ return bits_ == 0; }}
+    /// \return true if all flags from `o` are set
+    constexpr bool contains({flags_name} o) const noexcept {{ return (bits_ & o.bits_) == o.bits_; }}
+    {flags_name} &operator|=({flags_name} o) noexcept
+    {{
+        bits_ |= o.bits_;
+        return *this;
+    }}
+    {flags_name} &operator&=({flags_name} o) noexcept
+    {{
+        bits_ &= o.bits_;
+        return *this;
+    }}
+    {flags_name} operator~() const noexcept {{ return {flags_name}(~bits_ & all().bits_, 0); }}
+    friend constexpr {flags_name} operator|({flags_name} a, {flags_name} b) noexcept
+    {{
+        return {flags_name}(a.bits_ | b.bits_, 0);
+    }}
+    friend constexpr {flags_name} operator&({flags_name} a, {flags_name} b) noexcept
+    {{
+        return {flags_name}(a.bits_ & b.bits_, 0);
+    }}
+    friend constexpr bool operator==({flags_name} a, {flags_name} b) noexcept
+    {{
+        return a.bits_ == b.bits_;
+    }}
+    friend constexpr bool operator!=({flags_name} a, {flags_name} b) noexcept
+    {{
+        return a.bits_ != b.bits_;
+    }}
+
+private:
+    constexpr {flags_name}(uint32_t bits, int) noexcept
+        : bits_(bits)
+    {{
+    }}
+    uint32_t bits_;
+}};
+}} // namespace {namespace}"#,
+        flags_name = fenum.name,
+        all_bits = all_bits,
+        namespace = ctx.cfg.namespace_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    let mut cnt = file.take_content();
+    extend_foreign_enum(fenum, &mut cnt, ctx.enum_ext_handlers)?;
+    file.replace_content(cnt);
+    file.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::map_any_err_to_our_err(format!(
+            "write to {} failed: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    Ok(())
+}
@@ -33,7 +33,7 @@ pub(in crate::cpp) fn generate_interface(
         .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
     rust_code_generate_interface(ctx, interface, &f_methods)?;
 
-    let c_struct_name = format!("C_{}", interface.name);
+    let c_struct_name = c_interface_struct_name(ctx, interface);
     let rust_struct_pointer = format!("*const {}", c_struct_name);
     let rust_ty: Type = parse_ty_with_given_span(&rust_struct_pointer, interface.name.span())
         .map_err(|err| DiagnosticError::from_syn_err(interface.src_id, err))?;
@@ -132,7 +132,7 @@ fn rust_code_generate_interface(
 ) -> Result<()> {
     use std::fmt::Write;
 
-    let struct_with_funcs = format!("C_{}", interface.name);
+    let struct_with_funcs = c_interface_struct_name(ctx, interface);
 
     let mut code = format!(
         r#"
@@ -369,22 +369,25 @@ fn cpp_code_generate_interface(
     use std::fmt::Write;
 
     let c_interface_struct_header = c_interface_header(interface);
+    let c_struct_name = c_interface_struct_name(ctx, interface);
     let c_path = ctx.cfg.output_dir.join(&c_interface_struct_header);
     let mut file_c = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
     let cpp_path = ctx.cfg.output_dir.join(cpp_interface_header(interface));
     let mut file_cpp = FileWriteCache::new(&cpp_path, ctx.generated_foreign_files);
-    let interface_comments = cpp_code::doc_comments_to_c_comments(&interface.doc_comments, true);
+    let interface_comments =
+        cpp_code::doc_comments_to_c_comments(&interface.doc_comments, true, &ctx.exported_items);
 
     writeln!(
         file_c,
         r#"// Automatically generated by flapigen
 #pragma once
 {doc_comments}
-struct C_{interface_name} {{
+struct {c_struct_name} {{
     void *opaque;
     //! call by Rust side when callback not need anymore
     void (*C_{interface_name}_deref)(void *opaque);"#,
         interface_name = interface.name,
+        c_struct_name = c_struct_name,
         doc_comments = interface_comments
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -433,7 +436,11 @@ struct C_{interface_name} {{
             r#"{doc_comments}
     {c_ret_type} (*{method_name})({single_args_with_types}void *opaque);"#,
             method_name = method.name,
-            doc_comments = cpp_code::doc_comments_to_c_comments(&method.doc_comments, false),
+            doc_comments = cpp_code::doc_comments_to_c_comments(
+                &method.doc_comments,
+                false,
+                &ctx.exported_items
+            ),
             single_args_with_types = cpp_code::c_generate_args_with_types(
                 f_method,
                 method.arg_names_without_self(),
@@ -448,7 +455,11 @@ struct C_{interface_name} {{
             r#"{doc_comments}
     virtual {cpp_ret_type} {method_name}({single_args_with_types}) noexcept = 0;"#,
             method_name = method.name,
-            doc_comments = cpp_code::doc_comments_to_c_comments(&method.doc_comments, false),
+            doc_comments = cpp_code::doc_comments_to_c_comments(
+                &method.doc_comments,
+                false,
+                &ctx.exported_items
+            ),
             single_args_with_types =
                 cpp_code::cpp_generate_args_with_types(f_method, method.arg_names_without_self()),
             cpp_ret_type = cpp_ret_type,
@@ -544,10 +555,10 @@ public:
     virtual ~{interface_name}() noexcept {{}}
 {virtual_methods}
 
-    static C_{interface_name} to_c_interface(std::unique_ptr<{interface_name}> p)
+    static {c_struct_name} to_c_interface(std::unique_ptr<{interface_name}> p)
     {{
         assert(p != nullptr);
-        C_{interface_name} ret;
+        {c_struct_name} ret;
         ret.opaque = p.release();
 {cpp_fill_c_interface_struct}
         return ret;
@@ -557,6 +568,7 @@ private:
 }};
 }} // namespace {namespace_name}"##,
         interface_name = interface.name,
+        c_struct_name = c_struct_name,
         includes = includes,
         doc_comments = interface_comments,
         c_interface_struct_header = c_interface_struct_header,
@@ -577,6 +589,10 @@ private:
     Ok(())
 }
 
+fn c_interface_struct_name(ctx: &CppContext, interface: &ForeignInterface) -> String {
+    format!("{}C_{}", ctx.cfg.c_symbol_prefix, interface.name)
+}
+
 fn c_interface_header(interface: &ForeignInterface) -> String {
     format!("c_{}.h", interface.name)
 }
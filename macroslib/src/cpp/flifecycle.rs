@@ -0,0 +1,91 @@
+use proc_macro2::Span;
+use quote::quote;
+use std::io::Write;
+
+use crate::{
+    cpp::CppContext,
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    types::ForeignLifecycle,
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+const C_HEADER_NAME: &str = "c_lifecycle.h";
+const CPP_HEADER_NAME: &str = "lifecycle.hpp";
+
+/// Generate `init()`/`shutdown()` functions, that call `foreign_lifecycle!` hooks
+pub(in crate::cpp) fn generate_lifecycle(
+    ctx: &mut CppContext,
+    lifecycle: &ForeignLifecycle,
+) -> Result<()> {
+    let c_prefix = ctx.cfg.namespace_name.replace("::", "_");
+    let c_init_name = format!("{}_init", c_prefix);
+    let c_shutdown_name = format!("{}_shutdown", c_prefix);
+
+    for (c_func_name, hook) in &[
+        (&c_init_name, lifecycle.on_load.as_ref()),
+        (&c_shutdown_name, lifecycle.on_unload.as_ref()),
+    ] {
+        let c_func_name = syn::Ident::new(c_func_name, Span::call_site());
+        let hook_call = hook.map(|hook| quote!(#hook();));
+        ctx.rust_code.push(quote! {
+            #[no_mangle]
+            pub extern "C" fn #c_func_name() {
+                #hook_call
+            }
+        });
+    }
+
+    let c_path = ctx.cfg.output_dir.join(C_HEADER_NAME);
+    let mut c_header = FileWriteCache::new(&c_path, ctx.generated_foreign_files);
+    write!(
+        c_header,
+        r#"// Automatically generated by flapigen
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+void {c_init_name}(void);
+void {c_shutdown_name}(void);
+
+#ifdef __cplusplus
+}}
+#endif
+"#,
+        c_init_name = c_init_name,
+        c_shutdown_name = c_shutdown_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    c_header
+        .update_file_if_necessary()
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+
+    let cpp_path = ctx.cfg.output_dir.join(CPP_HEADER_NAME);
+    let mut cpp_header = FileWriteCache::new(&cpp_path, ctx.generated_foreign_files);
+    write!(
+        cpp_header,
+        r#"// Automatically generated by flapigen
+#pragma once
+
+#include "{c_header_name}"
+
+namespace {namespace} {{
+/// Initialize library, should be called before usage of any other API
+inline void init() noexcept {{ {c_init_name}(); }}
+/// Release resources of library, no API should be used after this call
+inline void shutdown() noexcept {{ {c_shutdown_name}(); }}
+}} // namespace {namespace}
+"#,
+        c_header_name = C_HEADER_NAME,
+        namespace = ctx.cfg.namespace_name,
+        c_init_name = c_init_name,
+        c_shutdown_name = c_shutdown_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    cpp_header
+        .update_file_if_necessary()
+        .map_err(DiagnosticError::map_any_err_to_our_err)?;
+    Ok(())
+}
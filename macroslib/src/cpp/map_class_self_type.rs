@@ -20,6 +20,7 @@ pub(in crate::cpp) fn register_typemap_for_self_type(
     class: &ForeignClassInfo,
     this_type: RustType,
     self_desc: &SelfTypeDesc,
+    c_symbol_prefix: &str,
 ) -> Result<()> {
     let span = this_type.ty.span();
 
@@ -52,6 +53,7 @@ pub(in crate::cpp) fn register_typemap_for_self_type(
         class,
         void_ptr_rust_ty.to_idx(),
         const_void_ptr_rust_ty.to_idx(),
+        c_symbol_prefix,
     )?;
     register_rust_ty_conversation_rules(
         conv_map,
@@ -75,6 +77,7 @@ pub(in crate::cpp) fn register_typemap_for_self_type(
         const_void_ptr_rust_ty.to_idx(),
         this_type_ref.to_idx(),
         this_type_mut_ref.to_idx(),
+        c_symbol_prefix,
     )?;
     Ok(())
 }
@@ -84,10 +87,11 @@ fn register_intermidiate_pointer_types(
     class: &ForeignClassInfo,
     void_ptr_rust_ty: RustTypeIdx,
     const_void_ptr_rust_ty: RustTypeIdx,
+    c_symbol_prefix: &str,
 ) -> Result<()> {
     let c_ftype = ForeignTypeS {
         name: TypeName::new(
-            format!("{} *", cpp_code::c_class_type(class)),
+            format!("{} *", cpp_code::c_class_type(c_symbol_prefix, class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::c_header_name(class)).into()],
@@ -105,7 +109,7 @@ fn register_intermidiate_pointer_types(
 
     let c_const_ftype = ForeignTypeS {
         name: TypeName::new(
-            format!("const {} *", cpp_code::c_class_type(class)),
+            format!("const {} *", cpp_code::c_class_type(c_symbol_prefix, class)),
             (class.src_id, class.name.span()),
         ),
         provides_by_module: vec![format!("\"{}\"", cpp_code::c_header_name(class)).into()],
@@ -251,6 +255,7 @@ fn register_main_foreign_types(
     const_void_ptr_rust_ty: RustTypeIdx,
     this_type_ref: RustTypeIdx,
     this_type_mut_ref: RustTypeIdx,
+    c_symbol_prefix: &str,
 ) -> Result<()> {
     debug!(
         "register_main_foreign_types: this {}, self {}",
@@ -268,7 +273,7 @@ fn register_main_foreign_types(
                     format!(
                         "{class_name}(static_cast<{c_type} *>({var}))",
                         class_name = class.name,
-                        c_type = cpp_code::c_class_type(class),
+                        c_type = cpp_code::c_class_type(c_symbol_prefix, class),
                         var = FROM_VAR_TEMPLATE
                     ),
                     invalid_src_id_span(),
@@ -304,7 +309,7 @@ fn register_main_foreign_types(
                 conv_code: Rc::new(TypeConvCode::new(
                     format!(
                         "static_cast<const {} *>({})",
-                        cpp_code::c_class_type(class),
+                        cpp_code::c_class_type(c_symbol_prefix, class),
                         FROM_VAR_TEMPLATE
                     ),
                     invalid_src_id_span(),
@@ -334,7 +339,7 @@ fn register_main_foreign_types(
                         format!(
                             "{class}Ref{{ static_cast<const {c_type} *>({var}) }}",
                             class = class.name,
-                            c_type = cpp_code::c_class_type(class),
+                            c_type = cpp_code::c_class_type(c_symbol_prefix, class),
                             var = FROM_VAR_TEMPLATE
                         ),
                         invalid_src_id_span(),
@@ -361,7 +366,7 @@ fn register_main_foreign_types(
                 conv_code: Rc::new(TypeConvCode::new(
                     format!(
                         "static_cast<{} *>({})",
-                        cpp_code::c_class_type(class),
+                        cpp_code::c_class_type(c_symbol_prefix, class),
                         FROM_VAR_TEMPLATE
                     ),
                     invalid_src_id_span(),
@@ -395,7 +400,7 @@ fn register_main_foreign_types(
                         conv_code: Rc::new(TypeConvCode::new(
                             format!(
                                 "static_cast<{} *>({})",
-                                cpp_code::c_class_type(class),
+                                cpp_code::c_class_type(c_symbol_prefix, class),
                                 FROM_VAR_TEMPLATE
                             ),
                             invalid_src_id_span(),
@@ -427,7 +432,7 @@ fn register_main_foreign_types(
                         conv_code: Rc::new(TypeConvCode::new(
                             format!(
                                 "static_cast<const {} *>({})",
-                                cpp_code::c_class_type(class),
+                                cpp_code::c_class_type(c_symbol_prefix, class),
                                 FROM_VAR_TEMPLATE
                             ),
                             invalid_src_id_span(),
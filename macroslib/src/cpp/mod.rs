@@ -29,14 +29,23 @@ static_assert(sizeof(uintptr_t) == sizeof(uint8_t) * {sizeof_usize},
     }};
 }
 
+mod cmake;
 mod cpp_code;
 mod fclass;
 mod fenum;
 mod finterface;
+mod flifecycle;
 mod map_class_self_type;
 mod map_type;
-
-use std::{io::Write, mem, path::PathBuf, rc::Rc};
+mod single_header;
+
+use std::{
+    fs,
+    io::{Read, Write},
+    mem,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use log::{debug, trace};
 use proc_macro2::TokenStream;
@@ -47,6 +56,7 @@ use syn::spanned::Spanned;
 
 use crate::{
     cpp::{map_class_self_type::register_typemap_for_self_type, map_type::map_type},
+    doc_comments::{exported_items, ExportedItems},
     error::{invalid_src_id_span, DiagnosticError, Result},
     extension::{ClassExtHandlers, EnumExtHandlers, ExtHandlers, MethodExtHandlers},
     file_cache::FileWriteCache,
@@ -61,8 +71,8 @@ use crate::{
         CItem, CItems, ForeignTypeInfo, TypeConvCode, TypeMapConvRuleInfo,
     },
     types::{ForeignClassInfo, ForeignMethod, ItemToExpand, MethodAccess, MethodVariant},
-    CppConfig, CppOptional, CppStrView, CppVariant, LanguageGenerator, SourceCode, TypeMap,
-    SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
+    CppConfig, CppOptional, CppSlice, CppStrView, CppVariant, LanguageGenerator, SourceCode,
+    TypeMap, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
 #[derive(Debug)]
@@ -296,7 +306,13 @@ impl CppConfig {
                 }
             }
 
-            register_typemap_for_self_type(conv_map, class, this_type, self_desc)?;
+            register_typemap_for_self_type(
+                conv_map,
+                class,
+                this_type,
+                self_desc,
+                &self.c_symbol_prefix,
+            )?;
         }
         conv_map.find_or_alloc_rust_type(&class.self_type_as_ty(), class.src_id);
         Ok(())
@@ -313,6 +329,7 @@ struct CppContext<'a> {
     class_ext_handlers: &'a ClassExtHandlers,
     method_ext_handlers: &'a MethodExtHandlers,
     enum_ext_handlers: &'a EnumExtHandlers,
+    exported_items: ExportedItems,
 }
 
 impl LanguageGenerator for CppConfig {
@@ -326,6 +343,15 @@ impl LanguageGenerator for CppConfig {
         ext_handlers: ExtHandlers,
     ) -> Result<Vec<TokenStream>> {
         let mut ret = Vec::with_capacity(items.len());
+        let c_class_types = items
+            .iter()
+            .filter_map(|item| match item {
+                ItemToExpand::Class(fclass) => {
+                    Some(cpp_code::c_class_type(&self.c_symbol_prefix, fclass))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
         let mut files = FxHashMap::<SmolStr, FileWriteCache>::default();
         let mut generated_foreign_files = FxHashSet::default();
         {
@@ -339,6 +365,7 @@ impl LanguageGenerator for CppConfig {
                 class_ext_handlers: ext_handlers.class_ext_handlers,
                 method_ext_handlers: ext_handlers.method_ext_handlers,
                 enum_ext_handlers: ext_handlers.enum_ext_handlers,
+                exported_items: exported_items(&items),
             };
             init(&mut ctx, code)?;
             for item in &items {
@@ -353,10 +380,17 @@ impl LanguageGenerator for CppConfig {
                     ItemToExpand::Interface(finterface) => {
                         finterface::generate_interface(&mut ctx, &finterface)?
                     }
+                    ItemToExpand::Lifecycle(lifecycle) => {
+                        flifecycle::generate_lifecycle(&mut ctx, &lifecycle)?
+                    }
                 }
             }
         }
 
+        let common_headers = files
+            .keys()
+            .map(|x| x.to_string())
+            .collect::<FxHashSet<_>>();
         for (module_name, c_header_f) in files {
             let c_header_path = self.output_dir.join(module_name.as_str());
             c_header_f.update_file_if_necessary().map_err(|err| {
@@ -368,10 +402,31 @@ impl LanguageGenerator for CppConfig {
             })?;
         }
 
+        if let Some(header_name) = self.single_header_name.as_ref() {
+            let helpers = code
+                .iter()
+                .map(|x| x.id_of_code.as_str())
+                .collect::<FxHashSet<_>>();
+            single_header::generate(
+                self,
+                header_name,
+                &helpers,
+                &common_headers,
+                &c_class_types,
+                &mut generated_foreign_files,
+            )?;
+        }
+
+        if let Some(package_name) = self.cmake_package_name.as_ref() {
+            cmake::generate_package_config(self, package_name, &mut generated_foreign_files)?;
+        }
+
         if remove_not_generated_files {
             remove_files_if(&self.output_dir, |path| {
                 if let Some(ext) = path.extension() {
-                    if (ext == "h" || ext == "hpp") && !generated_foreign_files.contains(path) {
+                    if (ext == "h" || ext == "hpp" || (ext == "cpp" && is_generated_source(path)))
+                        && !generated_foreign_files.contains(path)
+                    {
                         return true;
                     }
                 }
@@ -384,17 +439,29 @@ impl LanguageGenerator for CppConfig {
     }
 }
 
-fn c_func_name(class: &ForeignClassInfo, method: &ForeignMethod) -> String {
-    do_c_func_name(class, method.access, &method.short_name())
+/// `.cpp` files of user can be in the same directory, so check that flapigen generated it
+fn is_generated_source(path: &Path) -> bool {
+    const GENERATED_MARK: &str = "// Automatically generated by flapigen\n";
+    let mut buf = [0u8; GENERATED_MARK.len()];
+    fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut buf))
+        .map(|()| buf == GENERATED_MARK.as_bytes())
+        .unwrap_or(false)
+}
+
+fn c_func_name(cfg: &CppConfig, class: &ForeignClassInfo, method: &ForeignMethod) -> String {
+    do_c_func_name(cfg, class, method.access, &method.short_name())
 }
 
 fn do_c_func_name(
+    cfg: &CppConfig,
     class: &ForeignClassInfo,
     method_access: MethodAccess,
     method_short_name: &str,
 ) -> String {
     format!(
-        "{access}{class_name}_{func}",
+        "{prefix}{access}{class_name}_{func}",
+        prefix = cfg.c_symbol_prefix,
         access = match method_access {
             MethodAccess::Private => "private_",
             MethodAccess::Protected => "protected_",
@@ -425,13 +492,20 @@ fn register_c_type(
     tmap: &mut TypeMap,
     c_types: &CItems,
     fcode: &FileWriteCache,
+    c_symbol_prefix: &str,
     src_id: SourceId,
 ) -> Result<bool> {
     let mut something_defined = false;
     for c_type in &c_types.items {
         let (f_ident, c_name) = match c_type {
-            CItem::Struct(ref s) => (&s.ident, format!("struct {}", s.ident)),
-            CItem::Union(ref u) => (&u.ident, format!("union {}", u.ident)),
+            CItem::Struct(ref s) => (
+                &s.ident,
+                format!("struct {}", cpp_code::c_item_name(c_symbol_prefix, &s.ident)),
+            ),
+            CItem::Union(ref u) => (
+                &u.ident,
+                format!("union {}", cpp_code::c_item_name(c_symbol_prefix, &u.ident)),
+            ),
             CItem::Fn(_) => continue,
         };
         if fcode.is_item_defined(&c_name) {
@@ -487,6 +561,7 @@ fn merge_rule(ctx: &mut CppContext, mut rule: TypeMapConvRuleInfo) -> Result<()>
         opts.extend(CppOptional::iter().map(|x| -> &'static str { x.into() }));
         opts.extend(CppVariant::iter().map(|x| -> &'static str { x.into() }));
         opts.extend(CppStrView::iter().map(|x| -> &'static str { x.into() }));
+        opts.extend(CppSlice::iter().map(|x| -> &'static str { x.into() }));
         opts
     };
 
@@ -496,6 +571,7 @@ fn merge_rule(ctx: &mut CppContext, mut rule: TypeMapConvRuleInfo) -> Result<()>
         opts.insert(ctx.cfg.cpp_variant.into());
         opts.insert(ctx.cfg.cpp_optional.into());
         opts.insert(ctx.cfg.cpp_str_view.into());
+        opts.insert(ctx.cfg.cpp_slice.into());
         opts
     };
 
@@ -549,7 +625,13 @@ fn merge_c_types(
         let module_name = &c_types.header_name;
         let common_files = &mut ctx.common_files;
         let c_header_f = file_for_module!(ctx, common_files, module_name);
-        register_c_type(ctx.conv_map, &c_types, c_header_f, rule_src_id)?;
+        register_c_type(
+            ctx.conv_map,
+            &c_types,
+            c_header_f,
+            &ctx.cfg.c_symbol_prefix,
+            rule_src_id,
+        )?;
     }
     cpp_code::generate_c_type(ctx, &c_types, flags, rule_src_id)?;
 
@@ -0,0 +1,27 @@
+#pragma once
+
+#include <memory>
+#include <stdexcept>
+#include <string>
+#include <utility>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+/// Thrown instead of return of `Err` from Rust code,
+/// `what()` returns `Display` representation of Rust error.
+template <typename E> class RustError : public std::runtime_error {
+public:
+    RustError(E err, const std::string &msg)
+        : std::runtime_error(msg), err_(std::make_shared<E>(std::move(err)))
+    {
+    }
+    /// Rust error converted to C++ type
+    const E &error() const noexcept { return *err_; }
+    E &error() noexcept { return *err_; }
+
+private:
+    // exception object should be copyable, but E can be move only type
+    std::shared_ptr<E> err_;
+};
+
+} // namespace RUST_SWIG_USER_NAMESPACE
@@ -0,0 +1,101 @@
+#pragma once
+
+#include <cstddef>
+#include <iterator>
+#include <utility>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+template <typename K, typename V, typename CMapView, typename CEntry, size_t (*SizeFunc)(CMapView),
+          const V *(*FindFunc)(CMapView, K), void *(*IterNewFunc)(CMapView),
+          CEntry (*IterNextFunc)(void *), void (*IterFreeFunc)(void *)>
+class RustMap final : private CMapView {
+public:
+    using key_type = K;
+    using mapped_type = V;
+    using value_type = std::pair<K, V>;
+
+    class const_iterator final {
+    public:
+        using iterator_category = std::input_iterator_tag;
+        using value_type = std::pair<K, V>;
+        using difference_type = std::ptrdiff_t;
+        using pointer = const value_type *;
+        using reference = value_type;
+
+        const_iterator() noexcept
+            : it_(nullptr)
+        {
+            cur_.key = nullptr;
+            cur_.value = nullptr;
+        }
+        explicit const_iterator(void *it) noexcept
+            : it_(it)
+        {
+            next();
+        }
+        const_iterator(const const_iterator &) = delete;
+        const_iterator &operator=(const const_iterator &) = delete;
+        const_iterator(const_iterator &&o) noexcept
+            : it_(o.it_)
+            , cur_(o.cur_)
+        {
+            o.reset();
+        }
+        const_iterator &operator=(const_iterator &&o) noexcept
+        {
+            free_mem();
+            it_ = o.it_;
+            cur_ = o.cur_;
+            o.reset();
+            return *this;
+        }
+        ~const_iterator() noexcept { free_mem(); }
+        const K &key() const noexcept { return *cur_.key; }
+        const V &value() const noexcept { return *cur_.value; }
+        value_type operator*() const noexcept { return value_type(*cur_.key, *cur_.value); }
+        const_iterator &operator++() noexcept
+        {
+            next();
+            return *this;
+        }
+        bool operator==(const const_iterator &o) const noexcept { return cur_.key == o.cur_.key; }
+        bool operator!=(const const_iterator &o) const noexcept { return !(*this == o); }
+
+    private:
+        void next() noexcept
+        {
+            cur_ = IterNextFunc(it_);
+            if (cur_.key == nullptr) {
+                free_mem();
+            }
+        }
+        void free_mem() noexcept
+        {
+            if (it_ != nullptr) {
+                IterFreeFunc(it_);
+                it_ = nullptr;
+            }
+        }
+        void reset() noexcept
+        {
+            it_ = nullptr;
+            cur_.key = nullptr;
+            cur_.value = nullptr;
+        }
+        void *it_;
+        CEntry cur_;
+    };
+    using iterator = const_iterator;
+
+    explicit RustMap(const CMapView &o) noexcept { this->map = o.map; }
+    size_t size() const noexcept { return SizeFunc(*this); }
+    bool empty() const noexcept { return size() == 0; }
+    /// \return pointer to value inside Rust map or nullptr if there is no such key
+    const V *find(const K &key) const noexcept { return FindFunc(*this, key); }
+    bool contains(const K &key) const noexcept { return find(key) != nullptr; }
+    const_iterator begin() const noexcept { return const_iterator{ IterNewFunc(*this) }; }
+    const_iterator end() const noexcept { return const_iterator{}; }
+};
+
+} // namespace RUST_SWIG_USER_NAMESPACE
@@ -0,0 +1,146 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    CppConfig, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Merge all generated headers into `output_dir/header_name` and remove them.
+/// Each `#include "..."` of generated header is replaced by its content
+/// the first time it is seen, as `#pragma once` would do.
+/// To resolve circular dependencies between classes, headers are placed in such order:
+/// common C headers, C headers of classes, forward declarations of C++ classes,
+/// declarations of C++ classes and then implementations of their methods.
+/// `helpers` are inlined only if some other header includes them.
+pub(in crate::cpp) fn generate(
+    cfg: &CppConfig,
+    header_name: &str,
+    helpers: &FxHashSet<&str>,
+    common_headers: &FxHashSet<String>,
+    c_class_types: &[String],
+    generated_foreign_files: &mut FxHashSet<PathBuf>,
+) -> Result<()> {
+    let mut headers = FxHashMap::<String, (PathBuf, String)>::default();
+    for path in generated_foreign_files.iter() {
+        if !is_header(path) {
+            continue;
+        }
+        let name = match path.file_name().and_then(|x| x.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let cnt = fs::read_to_string(path).map_err(|err| {
+            DiagnosticError::map_any_err_to_our_err(format!(
+                "read of {} failed: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        headers.insert(name, (path.clone(), cnt));
+    }
+
+    let mut roots = headers
+        .keys()
+        .filter(|name| !helpers.contains(name.as_str()))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    roots.sort_by_key(|name| {
+        let rank = if common_headers.contains(*name) {
+            0
+        } else if name.ends_with(".h") {
+            1
+        } else if name.ends_with("_fwd.hpp") {
+            2
+        } else if name.ends_with("_impl.hpp") {
+            4
+        } else {
+            3
+        };
+        (rank, *name)
+    });
+
+    let path = cfg.output_dir.join(header_name);
+    let mut out = Vec::new();
+    // C functions of one class can use opaque type of other class and vice versa
+    for c_class_type in c_class_types {
+        writeln!(out, "typedef struct {0} {0};", c_class_type).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    let mut included = FxHashSet::default();
+    for name in roots {
+        inline_header(name, &headers, &mut included, &mut out);
+    }
+
+    for (header_path, _) in headers.values() {
+        generated_foreign_files.remove(header_path);
+        fs::remove_file(header_path).map_err(|err| {
+            DiagnosticError::map_any_err_to_our_err(format!(
+                "remove of {} failed: {}",
+                header_path.display(),
+                err
+            ))
+        })?;
+    }
+
+    let mut header_f = FileWriteCache::new(&path, generated_foreign_files);
+    header_f
+        .write_all(b"// Automatically generated by flapigen\n#pragma once\n")
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    header_f.write_all(&out).expect(WRITE_TO_MEM_FAILED_MSG);
+    header_f.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::map_any_err_to_our_err(format!(
+            "write to {} failed: {}",
+            path.display(),
+            err
+        ))
+    })?;
+    Ok(())
+}
+
+fn is_header(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext == "h" || ext == "hpp",
+        None => false,
+    }
+}
+
+fn inline_header(
+    name: &str,
+    headers: &FxHashMap<String, (PathBuf, String)>,
+    included: &mut FxHashSet<String>,
+    out: &mut Vec<u8>,
+) {
+    if !included.insert(name.to_string()) {
+        return;
+    }
+    let cnt = &headers[name].1;
+    writeln!(out, "\n// {}", name).expect(WRITE_TO_MEM_FAILED_MSG);
+    for line in cnt.lines() {
+        let trimmed = line.trim();
+        if trimmed == "#pragma once" || trimmed == "// Automatically generated by flapigen" {
+            continue;
+        }
+        if let Some(dep) = local_include(trimmed) {
+            if headers.contains_key(dep) {
+                inline_header(dep, headers, included, out);
+                continue;
+            }
+        }
+        writeln!(out, "{}", line).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+}
+
+/// Return `name` for `#include "name"`
+fn local_include(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("include")?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some(&rest[..end])
+}
@@ -0,0 +1,844 @@
+//! Conversation of rustdoc comments (Markdown with rustdoc conventions)
+//! into documentation formats of foreign languages.
+//!
+//! Sections `# Arguments`, `# Returns` and `# Errors` are extracted from
+//! the comment, so they can be rendered as `@param`, `@return`, `@throws`
+//! and analogs, intra-doc links to exported items become links
+//! in foreign documentation.
+
+use heck::CamelCase;
+use rustc_hash::FxHashSet;
+use smol_str::SmolStr;
+
+use crate::types::ItemToExpand;
+
+/// Names of classes, enums and callbacks visible from foreign language
+pub(crate) type ExportedItems = FxHashSet<SmolStr>;
+
+pub(crate) fn exported_items(items: &[ItemToExpand]) -> ExportedItems {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ItemToExpand::Class(fclass) => Some(fclass.name.to_string().into()),
+            ItemToExpand::Interface(finterface) => Some(finterface.name.to_string().into()),
+            ItemToExpand::Enum(fenum) => Some(fenum.name.to_string().into()),
+            ItemToExpand::Lifecycle(_) => None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct DocComment {
+    body: Vec<DocBlock>,
+    /// `# Arguments` section: argument name and its description
+    params: Vec<(String, String)>,
+    /// `# Returns` section
+    returns: String,
+    /// `# Errors` section
+    errors: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum DocBlock {
+    Paragraph(Vec<String>),
+    Code { rust: bool, lines: Vec<String> },
+    List(Vec<String>),
+    Heading(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Body,
+    Params,
+    Returns,
+    Errors,
+}
+
+impl DocComment {
+    pub(crate) fn parse(doc_comments: &[String]) -> DocComment {
+        let mut ret = DocComment::default();
+        let mut section = Section::Body;
+        let mut paragraph = Vec::<String>::new();
+        let mut code: Option<(bool, Vec<String>)> = None;
+        let mut in_list = false;
+
+        for line in doc_comments.iter().flat_map(|x| x.lines()) {
+            let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+            let trimmed = line.trim_start();
+            if let Some((rust, mut lines)) = code.take() {
+                if trimmed.starts_with("```") {
+                    ret.push_block(section, DocBlock::Code { rust, lines });
+                } else {
+                    // rustdoc hides such lines in Rust examples
+                    if !(rust && (trimmed == "#" || trimmed.starts_with("# "))) {
+                        lines.push(line.to_string());
+                    }
+                    code = Some((rust, lines));
+                }
+                continue;
+            }
+            if let Some(lang) = trimmed.strip_prefix("```") {
+                ret.flush_paragraph(section, &mut paragraph);
+                in_list = false;
+                code = Some((is_rust_code_block(lang), vec![]));
+            } else if trimmed.starts_with('#') && !trimmed.starts_with("#[") {
+                ret.flush_paragraph(section, &mut paragraph);
+                in_list = false;
+                let title = trimmed.trim_start_matches('#').trim();
+                section = match title.to_lowercase().as_str() {
+                    "arguments" | "parameters" | "args" => Section::Params,
+                    "returns" | "return value" => Section::Returns,
+                    "errors" => Section::Errors,
+                    _ => {
+                        ret.body.push(DocBlock::Heading(title.to_string()));
+                        Section::Body
+                    }
+                };
+            } else if trimmed.is_empty() {
+                ret.flush_paragraph(section, &mut paragraph);
+                in_list = false;
+            } else if let Some(item) = list_item(trimmed) {
+                ret.flush_paragraph(section, &mut paragraph);
+                in_list = true;
+                match section {
+                    Section::Params => ret.params.push(parse_param(item)),
+                    Section::Body => match ret.body.last_mut() {
+                        Some(DocBlock::List(ref mut items)) => items.push(item.to_string()),
+                        _ => ret.body.push(DocBlock::List(vec![item.to_string()])),
+                    },
+                    Section::Returns => append_text(&mut ret.returns, item),
+                    Section::Errors => append_text(&mut ret.errors, item),
+                }
+            } else if in_list && line.starts_with(' ') {
+                match (section, ret.body.last_mut(), ret.params.last_mut()) {
+                    (Section::Params, _, Some((_, ref mut descr))) => append_text(descr, trimmed),
+                    (Section::Body, Some(DocBlock::List(ref mut items)), _) => {
+                        let last = items.last_mut().expect("list should not be empty");
+                        append_text(last, trimmed);
+                    }
+                    _ => paragraph.push(trimmed.to_string()),
+                }
+            } else {
+                in_list = false;
+                paragraph.push(trimmed.to_string());
+            }
+        }
+        if let Some((rust, lines)) = code {
+            ret.push_block(section, DocBlock::Code { rust, lines });
+        }
+        ret.flush_paragraph(section, &mut paragraph);
+        ret
+    }
+
+    fn flush_paragraph(&mut self, section: Section, paragraph: &mut Vec<String>) {
+        if paragraph.is_empty() {
+            return;
+        }
+        let lines = std::mem::take(paragraph);
+        match section {
+            Section::Body => self.body.push(DocBlock::Paragraph(lines)),
+            Section::Params => match self.params.last_mut() {
+                Some((_, ref mut descr)) => append_text(descr, &lines.join(" ")),
+                None => self.body.push(DocBlock::Paragraph(lines)),
+            },
+            Section::Returns => append_text(&mut self.returns, &lines.join(" ")),
+            Section::Errors => append_text(&mut self.errors, &lines.join(" ")),
+        }
+    }
+
+    fn push_block(&mut self, section: Section, block: DocBlock) {
+        if section != Section::Body {
+            if let DocBlock::Code { ref lines, .. } = block {
+                let text = lines.iter().map(|x| x.trim()).collect::<Vec<_>>().join(" ");
+                self.flush_paragraph(section, &mut vec![format!("`{}`", text)]);
+                return;
+            }
+        }
+        self.body.push(block);
+    }
+
+    /// Lines of Javadoc comment, without `/**`, ` * ` and ` */`
+    pub(crate) fn to_javadoc(&self, exported: &ExportedItems) -> Vec<String> {
+        let fmt = JavadocFmt;
+        let mut out = Vec::new();
+        for block in &self.body {
+            if !out.is_empty() {
+                out.push(String::new());
+            }
+            match block {
+                DocBlock::Paragraph(lines) => {
+                    for (i, line) in lines.iter().enumerate() {
+                        let mut line = render_inline(&fmt, line, exported);
+                        // raw Javadoc tags should be kept at the start of line
+                        if i == 0 && out.len() > 1 && !line.starts_with('@') {
+                            line.insert_str(0, "<p>");
+                        }
+                        out.push(line);
+                    }
+                }
+                DocBlock::Code { lines, .. } => {
+                    out.push("<pre>".into());
+                    out.extend(lines.iter().map(|x| javadoc_escape(&html_escape(x))));
+                    out.push("</pre>".into());
+                }
+                DocBlock::List(items) => {
+                    out.push("<ul>".into());
+                    for item in items {
+                        out.push(format!("<li>{}</li>", render_inline(&fmt, item, exported)));
+                    }
+                    out.push("</ul>".into());
+                }
+                DocBlock::Heading(title) => out.push(format!(
+                    "<p><b>{}</b>",
+                    render_inline(&fmt, title, exported)
+                )),
+            }
+        }
+        let mut tags = Vec::new();
+        for (name, descr) in &self.params {
+            tags.push(format!(
+                "@param {} {}",
+                name,
+                render_inline(&fmt, descr, exported)
+            ));
+        }
+        if !self.returns.is_empty() {
+            tags.push(format!(
+                "@return {}",
+                render_inline(&fmt, &self.returns, exported)
+            ));
+        }
+        if !self.errors.is_empty() {
+            tags.push(format!(
+                "@throws Exception {}",
+                render_inline(&fmt, &self.errors, exported)
+            ));
+        }
+        if !tags.is_empty() && !out.is_empty() {
+            out.push(String::new());
+        }
+        out.extend(tags);
+        out
+    }
+
+    /// Lines of Doxygen comment, without `///`
+    pub(crate) fn to_doxygen(&self, exported: &ExportedItems) -> Vec<String> {
+        let fmt = DoxygenFmt;
+        let mut out = Vec::new();
+        for block in &self.body {
+            if !out.is_empty() {
+                out.push(String::new());
+            }
+            match block {
+                DocBlock::Paragraph(lines) => {
+                    out.extend(lines.iter().map(|x| render_inline(&fmt, x, exported)))
+                }
+                DocBlock::Code { lines, .. } => {
+                    out.push("@code".into());
+                    out.extend(lines.iter().cloned());
+                    out.push("@endcode".into());
+                }
+                DocBlock::List(items) => out.extend(
+                    items
+                        .iter()
+                        .map(|x| format!("- {}", render_inline(&fmt, x, exported))),
+                ),
+                DocBlock::Heading(title) => out.push(format!("@par {}", title)),
+            }
+        }
+        let mut tags = Vec::new();
+        for (name, descr) in &self.params {
+            tags.push(format!(
+                "@param {} {}",
+                name,
+                render_inline(&fmt, descr, exported)
+            ));
+        }
+        if !self.returns.is_empty() {
+            tags.push(format!(
+                "@return {}",
+                render_inline(&fmt, &self.returns, exported)
+            ));
+        }
+        if !self.errors.is_empty() {
+            tags.push("@par Errors".into());
+            tags.push(render_inline(&fmt, &self.errors, exported));
+        }
+        if !tags.is_empty() && !out.is_empty() {
+            out.push(String::new());
+        }
+        out.extend(tags);
+        out
+    }
+
+    /// Lines of C# XML documentation comment, without `///`
+    pub(crate) fn to_csharp_xml(&self, exported: &ExportedItems) -> Vec<String> {
+        let fmt = CSharpXmlFmt;
+        let mut out = Vec::new();
+        if !self.body.is_empty() {
+            out.push("<summary>".into());
+            for (idx, block) in self.body.iter().enumerate() {
+                match block {
+                    DocBlock::Paragraph(lines) => {
+                        let lines = lines.iter().map(|x| render_inline(&fmt, x, exported));
+                        if idx == 0 {
+                            out.extend(lines);
+                        } else {
+                            out.push("<para>".into());
+                            out.extend(lines);
+                            out.push("</para>".into());
+                        }
+                    }
+                    DocBlock::Code { lines, .. } => {
+                        out.push("<code>".into());
+                        out.extend(lines.iter().map(|x| html_escape(x)));
+                        out.push("</code>".into());
+                    }
+                    DocBlock::List(items) => {
+                        out.push(r#"<list type="bullet">"#.into());
+                        for item in items {
+                            out.push(format!(
+                                "<item><description>{}</description></item>",
+                                render_inline(&fmt, item, exported)
+                            ));
+                        }
+                        out.push("</list>".into());
+                    }
+                    DocBlock::Heading(title) => {
+                        out.push(format!("<para><b>{}</b></para>", html_escape(title)))
+                    }
+                }
+            }
+            out.push("</summary>".into());
+        }
+        for (name, descr) in &self.params {
+            out.push(format!(
+                r#"<param name="{}">{}</param>"#,
+                name,
+                render_inline(&fmt, descr, exported)
+            ));
+        }
+        if !self.returns.is_empty() {
+            out.push(format!(
+                "<returns>{}</returns>",
+                render_inline(&fmt, &self.returns, exported)
+            ));
+        }
+        if !self.errors.is_empty() {
+            out.push(format!(
+                r#"<exception cref="Error">{}</exception>"#,
+                render_inline(&fmt, &self.errors, exported)
+            ));
+        }
+        out
+    }
+
+    /// Lines of Python docstring in Google style
+    pub(crate) fn to_python_docstring(&self, exported: &ExportedItems) -> Vec<String> {
+        const INDENT: &str = "    ";
+        let fmt = PythonFmt;
+        let mut out = Vec::new();
+        for block in &self.body {
+            if !out.is_empty() {
+                out.push(String::new());
+            }
+            match block {
+                DocBlock::Paragraph(lines) => {
+                    out.extend(lines.iter().map(|x| render_inline(&fmt, x, exported)))
+                }
+                DocBlock::Code { rust, lines } => {
+                    out.push(if *rust {
+                        ".. code-block:: rust".into()
+                    } else {
+                        ".. code-block::".into()
+                    });
+                    out.push(String::new());
+                    out.extend(lines.iter().map(|x| format!("{}{}", INDENT, x)));
+                }
+                DocBlock::List(items) => out.extend(
+                    items
+                        .iter()
+                        .map(|x| format!("- {}", render_inline(&fmt, x, exported))),
+                ),
+                DocBlock::Heading(title) => out.push(format!("{}:", title)),
+            }
+        }
+        let add_section = |out: &mut Vec<String>, title: &str, lines: Vec<String>| {
+            if !out.is_empty() {
+                out.push(String::new());
+            }
+            out.push(title.into());
+            out.extend(lines.into_iter().map(|x| format!("{}{}", INDENT, x)));
+        };
+        if !self.params.is_empty() {
+            let lines = self
+                .params
+                .iter()
+                .map(|(name, descr)| format!("{}: {}", name, render_inline(&fmt, descr, exported)))
+                .collect();
+            add_section(&mut out, "Args:", lines);
+        }
+        if !self.returns.is_empty() {
+            let lines = vec![render_inline(&fmt, &self.returns, exported)];
+            add_section(&mut out, "Returns:", lines);
+        }
+        if !self.errors.is_empty() {
+            let lines = vec![format!(
+                "Error: {}",
+                render_inline(&fmt, &self.errors, exported)
+            )];
+            add_section(&mut out, "Raises:", lines);
+        }
+        out
+    }
+}
+
+fn is_rust_code_block(lang: &str) -> bool {
+    lang.split(',').map(str::trim).all(|attr| {
+        matches!(
+            attr,
+            "" | "rust" | "ignore" | "no_run" | "should_panic" | "compile_fail" | "edition2018"
+        ) || attr.starts_with("edition")
+    })
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("* ")
+        .or_else(|| line.strip_prefix("- "))
+        .or_else(|| line.strip_prefix("+ "))
+        .map(str::trim)
+}
+
+/// Parse list item like "`name` - description" or "name: description"
+fn parse_param(item: &str) -> (String, String) {
+    let (name, rest) = if let Some(item) = item.strip_prefix('`') {
+        match item.find('`') {
+            Some(end) => (&item[..end], &item[end + 1..]),
+            None => (item, ""),
+        }
+    } else {
+        let end = item
+            .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .unwrap_or(item.len());
+        (&item[..end], &item[end..])
+    };
+    let descr = rest.trim_start_matches(|ch: char| ch.is_whitespace() || ch == '-' || ch == ':');
+    (name.to_string(), descr.to_string())
+}
+
+fn append_text(text: &mut String, more: &str) {
+    if !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(more);
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `*/` inside of comment would close it
+fn javadoc_escape(text: &str) -> String {
+    text.replace("*/", "*&#47;").replace('@', "&#64;")
+}
+
+#[derive(Debug, PartialEq)]
+enum Inline<'a> {
+    Text(&'a str),
+    Code(&'a str),
+    Link {
+        label: Option<&'a str>,
+        target: &'a str,
+    },
+    Url {
+        label: &'a str,
+        url: &'a str,
+    },
+}
+
+fn parse_inline(mut text: &str) -> Vec<Inline<'_>> {
+    let mut ret = Vec::new();
+    while !text.is_empty() {
+        let pos = match text.find(['`', '[']) {
+            Some(pos) => pos,
+            None => {
+                ret.push(Inline::Text(text));
+                break;
+            }
+        };
+        if pos > 0 {
+            ret.push(Inline::Text(&text[..pos]));
+        }
+        text = &text[pos..];
+        if text.starts_with('`') {
+            let n = text.len() - text.trim_start_matches('`').len();
+            let fence = &text[..n];
+            match text[n..].find(fence) {
+                Some(end) => {
+                    ret.push(Inline::Code(text[n..n + end].trim()));
+                    text = &text[n + end + n..];
+                }
+                None => {
+                    ret.push(Inline::Text(fence));
+                    text = &text[n..];
+                }
+            }
+        } else {
+            match parse_link(text) {
+                Some((link, len)) => {
+                    ret.push(link);
+                    text = &text[len..];
+                }
+                None => {
+                    ret.push(Inline::Text("["));
+                    text = &text[1..];
+                }
+            }
+        }
+    }
+    ret
+}
+
+/// Parse `[label](target)`, `[label][target]` or `[target]`
+fn parse_link(text: &str) -> Option<(Inline<'_>, usize)> {
+    debug_assert!(text.starts_with('['));
+    let close = text.find(']')?;
+    let label = &text[1..close];
+    if label.is_empty() || label.contains('[') {
+        return None;
+    }
+    let after = &text[close + 1..];
+    let (open_ch, close_ch) = match after.chars().next() {
+        Some('(') => ('(', ')'),
+        Some('[') => ('[', ']'),
+        _ => {
+            let target = label.trim_matches('`');
+            let is_path = !target.is_empty()
+                && target
+                    .chars()
+                    .all(|ch| ch.is_alphanumeric() || "_:@()!".contains(ch));
+            return if is_path {
+                Some((
+                    Inline::Link {
+                        label: None,
+                        target: label,
+                    },
+                    close + 1,
+                ))
+            } else {
+                None
+            };
+        }
+    };
+    debug_assert!(after.starts_with(open_ch));
+    let end = after.find(close_ch)?;
+    let target = after[1..end].trim();
+    let len = close + 1 + end + 1;
+    if target.contains("://") || target.starts_with("mailto:") {
+        Some((Inline::Url { label, url: target }, len))
+    } else {
+        Some((
+            Inline::Link {
+                label: Some(label),
+                target,
+            },
+            len,
+        ))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LinkTarget<'a> {
+    Item(&'a str),
+    /// member of item, `None` means `Self`
+    Member(Option<&'a str>, &'a str),
+    Unresolved,
+}
+
+fn resolve_link<'a>(target: &'a str, exported: &ExportedItems) -> LinkTarget<'a> {
+    let mut target = target.trim().trim_matches('`');
+    if let Some(pos) = target.find('@') {
+        if target[..pos].chars().all(|ch| ch.is_ascii_alphabetic()) {
+            target = &target[pos + 1..];
+        }
+    }
+    let target = target.trim_end_matches("()").trim_end_matches('!');
+    let path = target
+        .split("::")
+        .filter(|x| !x.is_empty() && !matches!(*x, "crate" | "self" | "super"))
+        .collect::<Vec<_>>();
+    match path.as_slice() {
+        [.., item] if exported.contains(*item) => LinkTarget::Item(item),
+        [.., "Self", member] => LinkTarget::Member(None, member),
+        [.., item, member] if exported.contains(*item) => LinkTarget::Member(Some(item), member),
+        _ => LinkTarget::Unresolved,
+    }
+}
+
+trait DocFormat {
+    fn text(&self, text: &str) -> String;
+    fn code(&self, code: &str) -> String;
+    fn link(&self, label: Option<&str>, target: LinkTarget) -> String;
+    fn url(&self, label: &str, url: &str) -> String;
+}
+
+fn render_inline(fmt: &dyn DocFormat, text: &str, exported: &ExportedItems) -> String {
+    let mut ret = String::with_capacity(text.len());
+    for inline in parse_inline(text) {
+        let s = match inline {
+            Inline::Text(text) => fmt.text(text),
+            Inline::Code(code) => fmt.code(code),
+            Inline::Link { label, target } => match resolve_link(target, exported) {
+                LinkTarget::Unresolved => {
+                    let label = label.unwrap_or(target);
+                    if label.starts_with('`') && label.ends_with('`') {
+                        fmt.code(label.trim_matches('`'))
+                    } else if label == target {
+                        fmt.text(&format!("[{}]", label))
+                    } else {
+                        fmt.text(label)
+                    }
+                }
+                resolved => fmt.link(label.map(|x| x.trim_matches('`')), resolved),
+            },
+            Inline::Url { label, url } => fmt.url(label, url),
+        };
+        ret.push_str(&s);
+    }
+    ret
+}
+
+struct JavadocFmt;
+
+impl DocFormat for JavadocFmt {
+    fn text(&self, text: &str) -> String {
+        text.replace("*/", "*&#47;")
+    }
+    fn code(&self, code: &str) -> String {
+        let balanced = code.matches('{').count() == code.matches('}').count();
+        if balanced && !code.contains("*/") {
+            format!("{{@code {}}}", code)
+        } else {
+            format!("<code>{}</code>", javadoc_escape(&html_escape(code)))
+        }
+    }
+    fn link(&self, label: Option<&str>, target: LinkTarget) -> String {
+        let target = match target {
+            LinkTarget::Item(item) => item.to_string(),
+            LinkTarget::Member(Some(item), member) => format!("{}#{}", item, member),
+            LinkTarget::Member(None, member) => format!("#{}", member),
+            LinkTarget::Unresolved => unreachable!(),
+        };
+        match label {
+            Some(label) => format!("{{@link {} {}}}", target, label),
+            None => format!("{{@link {}}}", target),
+        }
+    }
+    fn url(&self, label: &str, url: &str) -> String {
+        format!("<a href=\"{}\">{}</a>", url, label)
+    }
+}
+
+struct DoxygenFmt;
+
+impl DocFormat for DoxygenFmt {
+    fn text(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn code(&self, code: &str) -> String {
+        format!("`{}`", code)
+    }
+    fn link(&self, label: Option<&str>, target: LinkTarget) -> String {
+        let target = match target {
+            LinkTarget::Item(item) => item.to_string(),
+            LinkTarget::Member(Some(item), member) => format!("{}::{}", item, member),
+            LinkTarget::Member(None, member) => member.to_string(),
+            LinkTarget::Unresolved => unreachable!(),
+        };
+        match label {
+            Some(label) if label != target => format!("@ref {} \"{}\"", target, label),
+            _ => target,
+        }
+    }
+    fn url(&self, label: &str, url: &str) -> String {
+        format!("[{}]({})", label, url)
+    }
+}
+
+struct CSharpXmlFmt;
+
+impl DocFormat for CSharpXmlFmt {
+    fn text(&self, text: &str) -> String {
+        html_escape(text)
+    }
+    fn code(&self, code: &str) -> String {
+        format!("<c>{}</c>", html_escape(code))
+    }
+    fn link(&self, label: Option<&str>, target: LinkTarget) -> String {
+        let target = match target {
+            LinkTarget::Item(item) => item.to_string(),
+            LinkTarget::Member(Some(item), member) => {
+                format!("{}.{}", item, member.to_camel_case())
+            }
+            LinkTarget::Member(None, member) => member.to_camel_case(),
+            LinkTarget::Unresolved => unreachable!(),
+        };
+        match label {
+            Some(label) => format!("<see cref=\"{}\">{}</see>", target, html_escape(label)),
+            None => format!("<see cref=\"{}\"/>", target),
+        }
+    }
+    fn url(&self, label: &str, url: &str) -> String {
+        format!("<see href=\"{}\">{}</see>", url, html_escape(label))
+    }
+}
+
+struct PythonFmt;
+
+impl DocFormat for PythonFmt {
+    fn text(&self, text: &str) -> String {
+        text.to_string()
+    }
+    fn code(&self, code: &str) -> String {
+        format!("``{}``", code)
+    }
+    fn link(&self, _label: Option<&str>, target: LinkTarget) -> String {
+        match target {
+            LinkTarget::Item(item) => format!(":class:`{}`", item),
+            LinkTarget::Member(Some(item), member) => format!(":obj:`{}.{}`", item, member),
+            LinkTarget::Member(None, member) => format!(":obj:`{}`", member),
+            LinkTarget::Unresolved => unreachable!(),
+        }
+    }
+    fn url(&self, label: &str, url: &str) -> String {
+        format!("`{} <{}>`_", label, url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(text: &str) -> Vec<String> {
+        text.lines().map(|x| format!(" {}", x)).collect()
+    }
+
+    #[test]
+    fn test_parse_doc_comment() {
+        let exported: ExportedItems = ["Foo".into(), "Boo".into()].iter().cloned().collect();
+        let comment =
+            DocComment::parse(&doc(r#"Create new [`Foo`], see also [Boo::f] and [`Vec`].
+
+```
+# let x = 1;
+let foo = Foo::new(x);
+```
+
+# Arguments
+
+* `id` - some number
+* `desc` - more
+  information
+
+# Errors
+
+If `id` is negative
+"#));
+        assert_eq!(
+            vec![
+                "Create new {@link Foo}, see also {@link Boo#f} and {@code Vec}.",
+                "",
+                "<pre>",
+                "let foo = Foo::new(x);",
+                "</pre>",
+                "",
+                "@param id some number",
+                "@param desc more information",
+                "@throws Exception If {@code id} is negative",
+            ],
+            comment.to_javadoc(&exported)
+        );
+        assert_eq!(
+            vec![
+                "<summary>",
+                "Create new <see cref=\"Foo\"/>, see also <see cref=\"Boo.F\"/> and <c>Vec</c>.",
+                "<code>",
+                "let foo = Foo::new(x);",
+                "</code>",
+                "</summary>",
+                "<param name=\"id\">some number</param>",
+                "<param name=\"desc\">more information</param>",
+                "<exception cref=\"Error\">If <c>id</c> is negative</exception>",
+            ],
+            comment.to_csharp_xml(&exported)
+        );
+        assert_eq!(
+            vec![
+                "Create new Foo, see also Boo::f and `Vec`.",
+                "",
+                "@code",
+                "let foo = Foo::new(x);",
+                "@endcode",
+                "",
+                "@param id some number",
+                "@param desc more information",
+                "@par Errors",
+                "If `id` is negative",
+            ],
+            comment.to_doxygen(&exported)
+        );
+        assert_eq!(
+            vec![
+                "Create new :class:`Foo`, see also :obj:`Boo.f` and ``Vec``.",
+                "",
+                ".. code-block:: rust",
+                "",
+                "    let foo = Foo::new(x);",
+                "",
+                "Args:",
+                "    id: some number",
+                "    desc: more information",
+                "",
+                "Raises:",
+                "    Error: If ``id`` is negative",
+            ],
+            comment.to_python_docstring(&exported)
+        );
+    }
+
+    #[test]
+    fn test_javadoc_keeps_plain_text() {
+        let exported = ExportedItems::default();
+        let comment = DocComment::parse(&doc(
+            "first line\nsecond line\n\nsecond paragraph\n\n@param x - some number",
+        ));
+        assert_eq!(
+            vec![
+                "first line",
+                "second line",
+                "",
+                "<p>second paragraph",
+                "",
+                "@param x - some number",
+            ],
+            comment.to_javadoc(&exported)
+        );
+        let comment = DocComment::parse(&doc(
+            "See [docs](https://example.com), [x] and `a */ b`\n# Panics\n- if x < 0",
+        ));
+        assert_eq!(
+            vec![
+                "See <a href=\"https://example.com\">docs</a>, [x] and <code>a *&#47; b</code>",
+                "",
+                "<p><b>Panics</b>",
+                "",
+                "<ul>",
+                "<li>if x < 0</li>",
+                "</ul>",
+            ],
+            comment.to_javadoc(&exported)
+        );
+    }
+}
@@ -1,18 +1,21 @@
-mod classes;
 mod map_type;
 
 use super::*;
+use c_abi::{
+    classes,
+    map_type::{self as c_abi_map_type, CAbiForeignMethodSignature},
+    CAbiContext,
+};
 use ast::{TypeName};
 use error::{ResultDiagnostic, ResultSynDiagnostic, invalid_src_id_span};
+use doc_comments::{exported_items, DocComment, ExportedItems};
 use file_cache::FileWriteCache;
 use heck::CamelCase;
 use itertools::Itertools;
-use map_type::{DotNetForeignMethodSignature, NameGenerator};
+use map_type::NameGenerator;
 use quote::quote;
 use rustc_hash::FxHashSet;
-use smol_str::SmolStr;
 use std::{
-    collections::{HashMap, HashSet},
     fs::{self, File},
     rc::Rc,
 };
@@ -28,11 +31,9 @@ use types::{
 
 pub struct DotNetGenerator<'a> {
     config: &'a DotNetConfig,
-    conv_map: &'a mut TypeMap,
-    rust_code: Vec<TokenStream>,
+    ctx: CAbiContext<'a>,
     cs_file: FileWriteCache,
-    additional_cs_code_for_types: HashMap<SmolStr, String>,
-    known_c_items_modules: HashSet<SmolStr>,
+    exported_items: ExportedItems,
 }
 
 impl<'a> DotNetGenerator<'a> {
@@ -42,21 +43,26 @@ impl<'a> DotNetGenerator<'a> {
 
         Ok(Self {
             config,
-            conv_map,
-            rust_code: Vec::new(),
+            ctx: CAbiContext::new(conv_map),
             cs_file,
-            additional_cs_code_for_types: HashMap::new(),
-            known_c_items_modules: HashSet::new(),
+            exported_items: ExportedItems::default(),
         })
     }
 
     fn generate(mut self, items: Vec<ItemToExpand>) -> Result<Vec<TokenStream>> {
+        self.exported_items = exported_items(&items);
         for item in &items {
             match item {
                 ItemToExpand::Class(class) => {
-                    classes::register_class(self.conv_map, class)?;
+                    classes::register_class(self.ctx.conv_map, class, "IntPtr")?;
                 }
                 ItemToExpand::Enum(fenum) => self.generate_enum(fenum)?,
+                ItemToExpand::Lifecycle(lifecycle) => {
+                    return Err(DiagnosticError::new2(
+                        lifecycle.src_id_span(),
+                        "foreign_lifecycle is not supported yet for .NET",
+                    ));
+                }
                 _ => unimplemented!("Interfaces not supported yet for .NET"),
             }
         }
@@ -72,7 +78,7 @@ impl<'a> DotNetGenerator<'a> {
 
         self.finish()?;
         self.cs_file.update_file_if_necessary()?;
-        Ok(self.rust_code)
+        Ok(self.ctx.rust_code)
     }
 
     fn create_cs_project(
@@ -154,42 +160,58 @@ namespace {managed_lib_name}
         Ok(cs_file)
     }
 
+    /// Doc comment lines, all lines except the first one are prefixed by `indent`
+    fn docstring(&self, doc_comments: &[String], indent: &str) -> String {
+        DocComment::parse(doc_comments)
+            .to_csharp_xml(&self.exported_items)
+            .iter()
+            .map(|doc_line| "/// ".to_owned() + doc_line)
+            .join(&format!("\n{}", indent))
+    }
+
     fn generate_enum(&mut self, fenum: &ForeignEnumInfo) -> Result<()> {
         let enum_name = &fenum.name;
-        let enum_variants = fenum
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, enum_item)| format!("{} = {}", enum_item.name.to_string().to_camel_case(), i))
-            .join(",");
-        let docstring = fenum.doc_comments.iter().map(|doc_line| {
-            "/// ".to_owned() + doc_line
-        }).join("\n");
-        write!(
-            self.cs_file,            
-            r#"
+        if fenum.is_bitflags {
+            self.write_bitflags_cs_code(fenum)?;
+        } else {
+            let enum_variants = fenum
+                .items
+                .iter()
+                .map(|enum_item| {
+                    format!(
+                        "{} = {}",
+                        enum_item.name.to_string().to_camel_case(),
+                        enum_item.value
+                    )
+                })
+                .join(",");
+            let docstring = self.docstring(&fenum.doc_comments, "    ");
+            write!(
+                self.cs_file,
+                r#"
     {docstring}
     public enum {enum_name} {{
         {enum_variants}
     }}"#,
-            docstring = docstring,
-            enum_name = enum_name,
-            enum_variants = enum_variants,
-        )
-        .with_note("Write to memory failed")?;
+                docstring = docstring,
+                enum_name = enum_name,
+                enum_variants = enum_variants,
+            )
+            .with_note("Write to memory failed")?;
+        }
 
         let span = fenum.span();
 
-        let enum_type = self.conv_map.find_or_alloc_rust_type(
-            &parse_type_spanned_checked!(span, #enum_name),
-            fenum.src_id,
-        );
-        let intermediate_type = self.conv_map.find_or_alloc_rust_type(
+        let enum_type = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(&parse_type_spanned_checked!(span, #enum_name), fenum.src_id);
+        let intermediate_type = self.ctx.conv_map.find_or_alloc_rust_type(
             &parse_type_spanned_checked!(span, /* #enum_name */ u32),
             fenum.src_id,
         );
 
-        self.conv_map.alloc_foreign_type(ForeignTypeS {
+        self.ctx.conv_map.alloc_foreign_type(ForeignTypeS {
             name: TypeName::new(enum_name.to_string(), (fenum.src_id, fenum.name.span())),
             provides_by_module: vec![],
             into_from_rust: Some(ForeignConversationRule {
@@ -198,11 +220,19 @@ namespace {managed_lib_name}
                     input_to_output: false,
                     intermediate_ty: intermediate_type.to_idx(),
                     conv_code: Rc::new(TypeConvCode::new(
-                        format!(
-                            "({enum_name}){from}",
-                            enum_name = enum_name,
-                            from = FROM_VAR_TEMPLATE,
-                        ),
+                        if fenum.is_bitflags {
+                            format!(
+                                "{enum_name}.FromBits({from})",
+                                enum_name = enum_name,
+                                from = FROM_VAR_TEMPLATE,
+                            )
+                        } else {
+                            format!(
+                                "({enum_name}){from}",
+                                enum_name = enum_name,
+                                from = FROM_VAR_TEMPLATE,
+                            )
+                        },
                         invalid_src_id_span(),
                     )),
                 }),
@@ -213,7 +243,15 @@ namespace {managed_lib_name}
                     input_to_output: false,
                     intermediate_ty: intermediate_type.to_idx(),
                     conv_code: Rc::new(TypeConvCode::new(
-                        format!("(uint){from}", from = FROM_VAR_TEMPLATE),
+                        if fenum.is_bitflags {
+                            format!(
+                                "{enum_name}.ToBits({from})",
+                                enum_name = enum_name,
+                                from = FROM_VAR_TEMPLATE
+                            )
+                        } else {
+                            format!("(uint){from}", from = FROM_VAR_TEMPLATE)
+                        },
                         invalid_src_id_span(),
                     )),
                 }),
@@ -221,7 +259,7 @@ namespace {managed_lib_name}
             name_prefix: None,
         })?;
 
-        self.conv_map.alloc_foreign_type(ForeignTypeS {
+        self.ctx.conv_map.alloc_foreign_type(ForeignTypeS {
             name: TypeName::new(
                 format!("/* {} */ uint", enum_name),
                 (fenum.src_id, fenum.name.span()),
@@ -238,31 +276,59 @@ namespace {managed_lib_name}
             name_prefix: None,
         })?;
 
-        let (arms_to_u32, arms_from_u32): (Vec<_>, Vec<_>) = fenum.items.iter().enumerate().map(|(i, item)| {
-            let item_name = &item.rust_name;
-            let idx = i as u32;
-            (quote! { #item_name => #idx }, quote! { #idx => #item_name })
-        }).unzip();
-
-        let rust_enum_name = &fenum.name;
-        self.rust_code.push(quote! {
-            impl SwigForeignEnum for #rust_enum_name {
-                fn as_u32(&self) -> u32 {
-                    match *self {
-                        #(#arms_to_u32),*
+        if fenum.is_bitflags {
+            // C# code checks bits, so Rust gets only known flags
+            let flags_name = &fenum.name;
+            self.ctx.rust_code.push(quote! {
+                impl SwigForeignEnum for #flags_name {
+                    fn as_u32(&self) -> u32 {
+                        self.bits() as u32
+                    }
+                    fn from_u32(x: u32) -> Self {
+                        #flags_name::from_bits_truncate(x as _)
                     }
                 }
-                fn from_u32(x: u32) -> Self {
-                    match x {
-                        #(#arms_from_u32),*
-                        ,
-                        _ => panic!(concat!("{} not expected for ", stringify!(#rust_enum_name)), x),
+            });
+            self.ctx
+                .rust_code
+                .push(typemap::utils::rust_code_for_bitflags_width_check(fenum));
+            self.ctx
+                .rust_code
+                .push(typemap::utils::rust_code_for_bitflags_item_bits(
+                    fenum,
+                    &format!("{}_item_bits", flags_name),
+                ));
+        } else {
+            let (arms_to_u32, arms_from_u32): (Vec<_>, Vec<_>) = fenum
+                .items
+                .iter()
+                .map(|item| {
+                    let item_name = &item.rust_name;
+                    let idx = item.value;
+                    (quote! { #item_name => #idx }, quote! { #idx => #item_name })
+                })
+                .unzip();
+
+            let rust_enum_name = &fenum.name;
+            self.ctx.rust_code.push(quote! {
+                impl SwigForeignEnum for #rust_enum_name {
+                    fn as_u32(&self) -> u32 {
+                        match *self {
+                            #(#arms_to_u32),*
+                        }
+                    }
+                    fn from_u32(x: u32) -> Self {
+                        match x {
+                            #(#arms_from_u32),*
+                            ,
+                            _ => panic!(concat!("{} not expected for ", stringify!(#rust_enum_name)), x),
+                        }
                     }
                 }
-            }
-        });
+            });
+        }
 
-        self.conv_map.add_conversation_rule(
+        self.ctx.conv_map.add_conversation_rule(
             intermediate_type.to_idx(),
             enum_type.to_idx(),
             TypeConvCode::new2(
@@ -272,7 +338,7 @@ namespace {managed_lib_name}
             .into(),
         );
 
-        self.conv_map.add_conversation_rule(
+        self.ctx.conv_map.add_conversation_rule(
             enum_type.to_idx(),
             intermediate_type.to_idx(),
             TypeConvCode::new2(
@@ -285,6 +351,99 @@ namespace {managed_lib_name}
         Ok(())
     }
 
+    /// Values of Rust constants are unknown during code generation,
+    /// so flags are `struct` with bits of items that are asked from Rust
+    fn write_bitflags_cs_code(&mut self, fenum: &ForeignEnumInfo) -> Result<()> {
+        let flags_name = &fenum.name;
+        let item_bits_func = format!("{}_item_bits", flags_name);
+        let flags_items = fenum
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let docstring = self.docstring(&item.doc_comments, "        ");
+                let docstring = if docstring.is_empty() {
+                    docstring
+                } else {
+                    docstring + "\n        "
+                };
+                format!(
+                    "{}public static readonly {} {} = new {}({}({}));",
+                    docstring,
+                    flags_name,
+                    item.name.to_string().to_camel_case(),
+                    flags_name,
+                    item_bits_func,
+                    i
+                )
+            })
+            .join("\n        ");
+        let all_bits = if fenum.items.is_empty() {
+            "0".to_string()
+        } else {
+            fenum
+                .items
+                .iter()
+                .map(|item| format!("{}.bits", item.name.to_string().to_camel_case()))
+                .join(" | ")
+        };
+        let docstring = self.docstring(&fenum.doc_comments, "    ");
+        write!(
+            self.cs_file,
+            r#"
+    {docstring}
+    public struct {flags_name} : System.IEquatable<{flags_name}> {{
+        [DllImport("{native_lib_name}", CallingConvention = CallingConvention.Cdecl)]
+        private static extern uint {item_bits_func}(uint item);
+
+        {flags_items}
+        /// <summary>All known flags</summary>
+        public static readonly {flags_name} All = new {flags_name}({all_bits});
+
+        private readonly uint bits;
+
+        private {flags_name}(uint bits) {{
+            this.bits = bits;
+        }}
+
+        /// <summary>Bits of flags, the same as Rust uses</summary>
+        public uint Bits => bits;
+
+        /// <summary>Check that all flags from `other` are set</summary>
+        public bool HasFlag({flags_name} other) => (bits & other.bits) == other.bits;
+
+        /// <summary>Convert flags to bits</summary>
+        public static uint ToBits({flags_name} flags) => flags.bits;
+
+        /// <summary>Convert bits to flags, throws if there are unknown bits</summary>
+        public static {flags_name} FromBits(uint bits) {{
+            if ((bits & ~All.bits) != 0) {{
+                throw new System.ArgumentOutOfRangeException(nameof(bits), bits, "Unknown bits for {flags_name}");
+            }}
+            return new {flags_name}(bits);
+        }}
+
+        public static {flags_name} operator |({flags_name} a, {flags_name} b) => new {flags_name}(a.bits | b.bits);
+        public static {flags_name} operator &({flags_name} a, {flags_name} b) => new {flags_name}(a.bits & b.bits);
+        public static {flags_name} operator ~({flags_name} a) => new {flags_name}(~a.bits & All.bits);
+        public static bool operator ==({flags_name} a, {flags_name} b) => a.bits == b.bits;
+        public static bool operator !=({flags_name} a, {flags_name} b) => a.bits != b.bits;
+
+        public bool Equals({flags_name} other) => bits == other.bits;
+        public override bool Equals(object obj) => obj is {flags_name} other && Equals(other);
+        public override int GetHashCode() => bits.GetHashCode();
+    }}"#,
+            docstring = docstring,
+            flags_name = flags_name,
+            flags_items = flags_items,
+            all_bits = all_bits,
+            item_bits_func = item_bits_func,
+            native_lib_name = self.config.native_lib_name,
+        )
+        .with_note("Write to memory failed")?;
+        Ok(())
+    }
+
     fn generate_class_methods(&mut self, class: &ForeignClassInfo) -> Result<()> {
         self.generate_rust_destructor(class)?;
         self.generate_dotnet_class_code(class)?;
@@ -304,10 +463,11 @@ namespace {managed_lib_name}
             let class_name = &class.name;
             let storage_ty = &self_desc.constructor_ret_type;
             let storage_type = self
+                .ctx
                 .conv_map
                 .find_or_alloc_rust_type(storage_ty, class.src_id);
             let smart_ptr_type =
-                classes::SmartPointerType::new(&storage_type, self.conv_map, class.src_id);
+                classes::SmartPointerType::new(&storage_type, self.ctx.conv_map, class.src_id);
             let intermediate_ptr_type = smart_ptr_type.intermediate_ptr_ty(storage_ty);
             let destructor_name = parse_str::<Ident>(&format!("{}_delete", class_name)).unwrap();
 
@@ -318,18 +478,15 @@ namespace {managed_lib_name}
                     ::std::mem::drop(Box::from_raw(this))
                 }
             };
-            self.rust_code.push(destructor_code);
+            self.ctx.rust_code.push(destructor_code);
         }
         Ok(())
     }
 
     fn generate_dotnet_class_code(&mut self, class: &ForeignClassInfo) -> Result<()> {
         let class_name = class.name.to_string();
-        let docstring = class.doc_comments.iter().map(|doc_line| {
-            "/// ".to_owned() + doc_line
-        }).join("\n");
-
         if let Some(_) = class.self_desc {
+            let docstring = self.docstring(&class.doc_comments, "    ");
             let rust_destructor_name = class_name.clone() + "_delete";
 
             write!(
@@ -369,6 +526,7 @@ namespace {managed_lib_name}
             )
             .with_note("Write to memory failed")?;
         } else {
+            let docstring = self.docstring(&class.doc_comments, "");
             writeln!(
                 self.cs_file,
                 "{docstring}\npublic static class {class_name} {{",
@@ -390,11 +548,11 @@ namespace {managed_lib_name}
             return Ok(());
         }
         let foreign_method_signature =
-            map_type::make_foreign_method_signature(self, class, method)?;
+            c_abi_map_type::make_foreign_method_signature(&mut self.ctx, class, method)?;
 
         self.write_rust_glue_code(class, &foreign_method_signature)?;
         self.write_pinvoke_function_signature(class, &foreign_method_signature)?;
-        self.write_dotnet_wrapper_function(class, &foreign_method_signature)?;
+        self.write_dotnet_wrapper_function(class, method, &foreign_method_signature)?;
 
         Ok(())
     }
@@ -402,7 +560,7 @@ namespace {managed_lib_name}
     fn write_rust_glue_code(
         &mut self,
         class: &ForeignClassInfo,
-        foreign_method_signature: &DotNetForeignMethodSignature,
+        foreign_method_signature: &CAbiForeignMethodSignature,
     ) -> Result<()> {
         let method_name = &foreign_method_signature.name;
         let full_method_name = format!("{}_{}", class.name, method_name);
@@ -412,8 +570,8 @@ namespace {managed_lib_name}
                 .input
                 .iter()
                 .map(|arg| -> Result<String> {
-                    let (mut deps, conversion) = arg.rust_conversion_code(self.conv_map)?;
-                    self.rust_code.append(&mut deps);
+                    let (mut deps, conversion) = arg.rust_conversion_code(self.ctx.conv_map)?;
+                    self.ctx.rust_code.append(&mut deps);
                     Ok(conversion)
                 }),
             |mut iter| iter.join(""),
@@ -433,8 +591,8 @@ namespace {managed_lib_name}
 
         let (mut deps, convert_output_code) = foreign_method_signature
             .output
-            .rust_conversion_code(self.conv_map)?;
-        self.rust_code.append(&mut deps);
+            .rust_conversion_code(self.ctx.conv_map)?;
+        self.ctx.rust_code.append(&mut deps);
 
         let rust_code_str = format!(
             r#"
@@ -461,7 +619,8 @@ namespace {managed_lib_name}
             convert_output_code = convert_output_code,
             call = foreign_method_signature.rust_function_call,
         );
-        self.rust_code
+        self.ctx
+            .rust_code
             .push(syn::parse_str(&rust_code_str).with_syn_src_id(class.src_id)?);
         Ok(())
     }
@@ -469,7 +628,7 @@ namespace {managed_lib_name}
     fn write_pinvoke_function_signature(
         &mut self,
         class: &ForeignClassInfo,
-        foreign_method_signature: &DotNetForeignMethodSignature,
+        foreign_method_signature: &CAbiForeignMethodSignature,
     ) -> Result<()> {
         let method_name = &foreign_method_signature.name;
         let full_method_name = format!("{}_{}", class.name, method_name);
@@ -479,7 +638,7 @@ namespace {managed_lib_name}
             .map(|a| {
                 format!(
                     "{} {}",
-                    a.type_info.dotnet_intermediate_type,
+                    a.type_info.foreign_intermediate_type,
                     a.arg_name.dotnet_variable_name()
                 )
             })
@@ -494,7 +653,7 @@ namespace {managed_lib_name}
             return_type = foreign_method_signature
                 .output
                 .type_info
-                .dotnet_intermediate_type,
+                .foreign_intermediate_type,
             method_name = full_method_name,
             args = pinvoke_args_str,
         )
@@ -506,7 +665,8 @@ namespace {managed_lib_name}
     fn write_dotnet_wrapper_function(
         &mut self,
         class: &ForeignClassInfo,
-        foreign_method_signature: &DotNetForeignMethodSignature,
+        method: &ForeignMethod,
+        foreign_method_signature: &CAbiForeignMethodSignature,
     ) -> Result<()> {
         let mut name_generator = NameGenerator::new();
         let maybe_static_str = if foreign_method_signature.variant == MethodVariant::StaticMethod {
@@ -533,7 +693,7 @@ namespace {managed_lib_name}
             .map(|arg| {
                 format!(
                     "{} {}",
-                    arg.type_info.dotnet_type,
+                    arg.type_info.foreign_type,
                     NameGenerator::first_variant(arg.arg_name.dotnet_variable_name())
                 )
             })
@@ -555,7 +715,7 @@ namespace {managed_lib_name}
                 .join("\n            ");
 
         let returns_something =
-            foreign_method_signature.output.type_info.dotnet_type != "void" && !is_constructor;
+            foreign_method_signature.output.type_info.foreign_type != "void" && !is_constructor;
         let maybe_return_bind = if returns_something {
             "var __ret_0 = "
         } else if is_constructor {
@@ -572,7 +732,11 @@ namespace {managed_lib_name}
             String::new()
         };
         
-        let is_result_void = foreign_method_signature.output.type_info.dotnet_type.contains("ResultVoid");
+        let is_result_void = foreign_method_signature
+            .output
+            .type_info
+            .foreign_type
+            .contains("ResultVoid");
         let maybe_return = if returns_something && !is_result_void {
             format!("return {};", name_generator.last_variant("__ret"))
         } else {
@@ -595,9 +759,9 @@ namespace {managed_lib_name}
             {maybe_return}
         }}
 "#,
-            docstring = foreign_method_signature.docstring,
+            docstring = self.docstring(&method.doc_comments, "        "),
             maybe_static = maybe_static_str,
-            dotnet_return_type = foreign_method_signature.output.type_info.dotnet_type,
+            dotnet_return_type = foreign_method_signature.output.type_info.foreign_type,
             method_name = method_name,
             dotnet_args = dotnet_args_str,
             dotnet_input_conversion = dotnet_input_conversion,
@@ -613,8 +777,13 @@ namespace {managed_lib_name}
     }
 
     fn finish(&mut self) -> Result<()> {
-        for (_, cs_code) in self.additional_cs_code_for_types.drain() {
-            write!(self.cs_file, "{}", cs_code)?;
+        let native_lib_name = &self.config.native_lib_name;
+        for (_, cs_code) in self.ctx.additional_foreign_code.drain() {
+            write!(
+                self.cs_file,
+                "{}",
+                cs_code.replace("{native_lib_name}", native_lib_name)
+            )?;
         }
         writeln!(self.cs_file, "}} // namespace",)?;
         Ok(())
@@ -10,7 +10,9 @@ use syn::{spanned::Spanned, Type};
 use super::{
     calc_this_type_for_method, java_class_full_name, java_class_name_to_jni, java_code,
     map_type::map_type, method_name, rust_code, JavaContext, JavaConverter, JavaForeignTypeInfo,
-    JniForeignMethodSignature, INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME, REACHABILITY_FENCE_CLASS,
+    JniForeignMethodSignature, JniNativeMethod, BORROW_OWNER_TEMPLATE, INTERNAL_PTR_MARKER,
+    JAVA_BORROW_OWNER_FIELD, JAVA_CHECKED_SELF_GETTER, JAVA_RECORD_NATIVE_CLASS,
+    JAVA_RUST_SELF_NAME, NATIVE_OBJECT_OWNER, REACHABILITY_FENCE_CLASS,
 };
 use crate::{
     error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
@@ -28,11 +30,37 @@ use crate::{
         ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
     },
     types::{ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, SelfTypeVariant},
-    JavaConfig, JavaReachabilityFence, CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT,
-    WRITE_TO_MEM_FAILED_MSG,
+    JavaCallbackExceptionPolicy, JavaConfig, JavaReachabilityFence, CLONE_TRAIT, COPY_TRAIT,
+    JAVA_RECORD, SMART_PTR_COPY_TRAIT, WRITE_TO_MEM_FAILED_MSG,
 };
 
+/// Header of Java record: list of components and their types,
+/// plus public methods that call native methods from nested class
+pub(in crate::java_jni) struct JavaRecordHeader<'a> {
+    pub components: String,
+    pub components_sign: &'a [JniForeignMethodSignature],
+    pub methods: String,
+}
+
 pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassInfo) -> Result<()> {
+    generate_class(ctx, class, None)
+}
+
+/// Generate Java record instead of class, `class` should contain
+/// only methods that are not record components
+pub(in crate::java_jni) fn generate_record(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    header: JavaRecordHeader,
+) -> Result<()> {
+    generate_class(ctx, class, Some(header))
+}
+
+fn generate_class(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    record: Option<JavaRecordHeader>,
+) -> Result<()> {
     debug!(
         "generate: begin for {}, this_type_for_method {:?}",
         class.name, class.self_desc
@@ -44,9 +72,14 @@ pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassI
         class,
         &f_methods_sign,
         ctx.cfg.null_annotation_package.as_deref(),
+        record.as_ref(),
     )?;
     debug!("generate: java code done");
-    generate_rust_code(ctx, class, &f_methods_sign)?;
+    let jni_class_name = match record {
+        Some(_) => format!("{}${}", class.name, JAVA_RECORD_NATIVE_CLASS),
+        None => class.name.to_string(),
+    };
+    generate_rust_code(ctx, class, &jni_class_name, &f_methods_sign)?;
 
     let class_name = class.name.to_string();
 
@@ -85,26 +118,61 @@ fn generate_java_code(
     class: &ForeignClassInfo,
     methods_sign: &[JniForeignMethodSignature],
     null_annotation_package: Option<&str>,
+    record: Option<&JavaRecordHeader>,
 ) -> Result<()> {
     let path = ctx.cfg.output_dir.join(format!("{}.java", class.name));
     let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
 
-    let imports = java_code::get_null_annotation_imports(null_annotation_package, methods_sign);
+    let imports = java_code::get_null_annotation_imports(
+        null_annotation_package,
+        methods_sign
+            .iter()
+            .chain(record.iter().flat_map(|x| x.components_sign.iter())),
+    );
 
-    let class_doc_comments = java_code::doc_comments_to_java_comments(&class.doc_comments, true);
+    let class_doc_comments =
+        java_code::doc_comments_to_java_comments(&class.doc_comments, true, &ctx.exported_items);
+    let class_name = class.name.to_string();
+    let borrowed = ctx.borrowed_classes.contains(class_name.as_str());
+    let lending = ctx.lending_classes.contains(class_name.as_str());
+    let implements = if lending {
+        format!(" implements {}", NATIVE_OBJECT_OWNER)
+    } else {
+        String::new()
+    };
+    let class_decl = match record {
+        Some(record) => format!("record {}({})", class.name, record.components),
+        None => format!("final class {}", class.name),
+    };
     writeln!(
         file,
         r#"// Automatically generated by flapigen
 package {package_name};
 {imports}
 {doc_comments}
-public final class {class_name} {{"#,
+public {class_decl}{implements} {{"#,
         package_name = ctx.cfg.package_name,
         imports = imports,
-        class_name = class.name,
+        class_decl = class_decl,
         doc_comments = class_doc_comments,
+        implements = implements,
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
+    let record_natives = record.filter(|_| !class.methods.is_empty());
+    if let Some(record) = record_natives {
+        write!(
+            file,
+            "{methods}\n    private static final class {native_class} {{",
+            methods = record.methods,
+            native_class = JAVA_RECORD_NATIVE_CLASS,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    let self_raw_ptr = if borrowed {
+        format!("{}()", JAVA_CHECKED_SELF_GETTER)
+    } else {
+        JAVA_RUST_SELF_NAME.to_string()
+    };
 
     let mut have_methods = false;
     let mut have_constructor = false;
@@ -113,7 +181,11 @@ public final class {class_name} {{"#,
         write!(
             &mut file,
             "{doc_comments}",
-            doc_comments = java_code::doc_comments_to_java_comments(&method.doc_comments, false)
+            doc_comments = java_code::doc_comments_to_java_comments(
+                &method.doc_comments,
+                false,
+                &ctx.exported_items
+            )
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
 
@@ -196,6 +268,23 @@ public final class {class_name} {{"#,
             }
             MethodVariant::Constructor => ("long", "long", String::new()),
         };
+        let ret_conv_code = match method.variant {
+            MethodVariant::Method(_) => ret_conv_code.replace(BORROW_OWNER_TEMPLATE, "this"),
+            MethodVariant::StaticMethod | MethodVariant::Constructor
+                if ret_conv_code.contains(BORROW_OWNER_TEMPLATE) =>
+            {
+                return Err(DiagnosticError::new(
+                    class.src_id,
+                    method.rust_id.span(),
+                    format!(
+                        "Method {} returns reference, but there is no object that owns it.\n\
+                         Only methods with `self` can return references to objects",
+                        method.short_name()
+                    ),
+                ));
+            }
+            MethodVariant::StaticMethod | MethodVariant::Constructor => ret_conv_code,
+        };
         let intermidiate_ret_type_code = if ret_conv_code.is_empty() {
             &ret_type
         } else {
@@ -316,7 +405,7 @@ public final class {class_name} {{"#,
                         file,
                         r#"
         {intermidiate_ret_type} {ret_name} = {func_name}({rust_self_name}{args});{ret_conv_code}"#,
-                        rust_self_name = JAVA_RUST_SELF_NAME,
+                        rust_self_name = self_raw_ptr,
                         ret_conv_code = ret_conv_code,
                         ret_name = ret_name,
                         intermidiate_ret_type =
@@ -330,7 +419,7 @@ public final class {class_name} {{"#,
                         file,
                         r#"
         {func_name}({rust_self_name}{args});"#,
-                        rust_self_name = JAVA_RUST_SELF_NAME,
+                        rust_self_name = self_raw_ptr,
                         func_name = func_name,
                         args = args_for_call_internal,
                     )
@@ -427,6 +516,10 @@ public final class {class_name} {{"#,
         }
     }
 
+    if record_natives.is_some() {
+        writeln!(file, "    }}").expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
     if have_methods && !have_constructor {
         return Err(DiagnosticError::new(
             class.src_id,
@@ -438,7 +531,56 @@ May be you need to use `private constructor = empty;` syntax?",
             ),
         ));
     }
-    if have_constructor {
+    if have_constructor && borrowed {
+        writeln!(
+            file,
+            r#"
+    public synchronized void delete() {{
+        if ({rust_self_name} != 0) {{
+            if ({owner_field} == null) {{
+                do_delete({rust_self_name});
+            }}
+            {rust_self_name} = 0;
+            {owner_field} = null;
+       }}
+    }}
+    @Override
+    protected void finalize() throws Throwable {{
+        try {{
+            delete();
+        }}
+        finally {{
+             super.finalize();
+        }}
+    }}
+    private static native void do_delete(long me);
+    /*package*/ {class_name}({internal_ptr_marker} marker, long ptr) {{
+        assert marker == {internal_ptr_marker}.RAW_PTR;
+        this.{rust_self_name} = ptr;
+    }}
+    /*package*/ {class_name}({internal_ptr_marker} marker, long ptr, {owner_interface} owner) {{
+        assert marker == {internal_ptr_marker}.BORROWED_PTR;
+        this.{rust_self_name} = ptr;
+        this.{owner_field} = owner;
+    }}
+    /*package*/ long {checked_getter}() {{
+        if ({owner_field} != null && !{owner_field}.isNativeObjectAlive()) {{
+            throw new IllegalStateException("{class_name}: owner of borrowed object was deleted");
+        }}
+        return {rust_self_name};
+    }}
+    /*package*/ long {rust_self_name};
+    /** Owner of memory of borrowed object, {{@code null}} if this object owns memory itself */
+    /*package*/ {owner_interface} {owner_field};"#,
+            rust_self_name = JAVA_RUST_SELF_NAME,
+            class_name = class.name,
+            internal_ptr_marker = INTERNAL_PTR_MARKER,
+            owner_interface = NATIVE_OBJECT_OWNER,
+            owner_field = JAVA_BORROW_OWNER_FIELD,
+            checked_getter = JAVA_CHECKED_SELF_GETTER,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    } else if have_constructor {
         writeln!(
             file,
             r#"
@@ -470,9 +612,30 @@ May be you need to use `private constructor = empty;` syntax?",
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
 
+    if lending {
+        let alive_check = if borrowed {
+            format!(
+                "{rust_self_name} != 0 && ({owner_field} == null || {owner_field}.isNativeObjectAlive())",
+                rust_self_name = JAVA_RUST_SELF_NAME,
+                owner_field = JAVA_BORROW_OWNER_FIELD,
+            )
+        } else {
+            format!("{} != 0", JAVA_RUST_SELF_NAME)
+        };
+        writeln!(
+            file,
+            r#"    @Override
+    public boolean isNativeObjectAlive() {{
+        return {alive_check};
+    }}"#,
+            alive_check = alive_check,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
     //utility class, so add private constructor
     //to prevent object creation
-    if !have_constructor && !have_methods {
+    if !have_constructor && !have_methods && record.is_none() {
         writeln!(
             file,
             r#"
@@ -490,7 +653,7 @@ May be you need to use `private constructor = empty;` syntax?",
     extend_foreign_class(
         class,
         &mut cnt,
-        &[CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT],
+        &[CLONE_TRAIT, COPY_TRAIT, SMART_PTR_COPY_TRAIT, JAVA_RECORD],
         ctx.class_ext_handlers,
         ctx.method_ext_handlers,
     )?;
@@ -504,6 +667,7 @@ May be you need to use `private constructor = empty;` syntax?",
 fn generate_rust_code(
     ctx: &mut JavaContext,
     class: &ForeignClassInfo,
+    jni_class_name: &str,
     f_methods_sign: &[JniForeignMethodSignature],
 ) -> Result<()> {
     //to handle java method overload
@@ -608,7 +772,7 @@ May be you need to use `private constructor = empty;` syntax?",
         let method_overloading = gen_fnames[&java_method_name] > 1;
         let jni_func_name = rust_code::generate_jni_func_name(
             ctx,
-            &class.name.to_string(),
+            jni_class_name,
             (class.src_id, class.span()),
             &java_method_name,
             method.variant,
@@ -616,6 +780,26 @@ May be you need to use `private constructor = empty;` syntax?",
             method_overloading,
         )?;
         trace!("generate_rust_code jni name: {}", jni_func_name);
+        let have_native = match method.variant {
+            MethodVariant::Constructor => !method.is_dummy_constructor(),
+            MethodVariant::Method(_) | MethodVariant::StaticMethod => true,
+        };
+        if have_native {
+            // static method without conversations is native itself, so it uses external types
+            let use_transition_types = match method.variant {
+                MethodVariant::StaticMethod => java_method_name != method.short_name().as_str(),
+                MethodVariant::Method(_) | MethodVariant::Constructor => true,
+            };
+            rust_code::add_native_method(
+                ctx,
+                jni_class_name,
+                &java_method_name,
+                method.variant,
+                f_method,
+                use_transition_types,
+                &jni_func_name,
+            );
+        }
 
         let mut known_names: FxHashSet<SmolStr> =
             method.arg_names_without_self().map(|x| x.into()).collect();
@@ -726,11 +910,18 @@ May be you need to use `private constructor = empty;` syntax?",
             },
             false,
         )?;
+        ctx.native_methods.push(JniNativeMethod {
+            class_name: class.name.to_string().into(),
+            java_method_name: "do_delete".into(),
+            input: vec!["long".into()],
+            output: "void".into(),
+            rust_func_name: jni_destructor_name.clone(),
+        });
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{linkage} fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{rethrow_guard}
     let this: *mut {this_type} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
     }};
@@ -738,6 +929,8 @@ pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong
     drop(this);
 }}
 "#,
+            rethrow_guard = rethrow_guard_code(ctx),
+            linkage = rust_code::jni_func_linkage(ctx),
             jni_destructor_name = jni_destructor_name,
             unpack_code = unpack_code,
             this_type = this_type_for_method,
@@ -832,6 +1025,16 @@ fn find_suitable_foreign_types_for_methods(
     Ok(ret)
 }
 
+/// Code to throw exceptions, caught by callbacks, on return to Java
+fn rethrow_guard_code(ctx: &JavaContext) -> &'static str {
+    match ctx.cfg.callback_exception_policy {
+        JavaCallbackExceptionPolicy::Rethrow => {
+            "    let _swig_rethrow_guard = JniRethrowGuard::new(env);"
+        }
+        JavaCallbackExceptionPolicy::Log | JavaCallbackExceptionPolicy::Panic => "",
+    }
+}
+
 fn generate_static_method(ctx: &mut JavaContext, mc: &MethodContext) -> Result<()> {
     let jni_ret_type = mc.f_method.output.base.correspoding_rust_type.typename();
     let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
@@ -856,14 +1059,16 @@ fn generate_static_method(ctx: &mut JavaContext, mc: &MethodContext) -> Result<(
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
+{linkage} fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
+{rethrow_guard}
 {convert_input_code}
     let mut {ret_name}: {real_output_typename} = {call};
 {convert_output_code}
     {ret_name}
 }}
 "#,
+        rethrow_guard = rethrow_guard_code(ctx),
+        linkage = rust_code::jni_func_linkage(ctx),
         func_name = mc.jni_func_name,
         decl_func_args = mc.decl_func_args,
         jni_ret_type = jni_ret_type,
@@ -921,8 +1126,8 @@ fn generate_constructor(
     let code = format!(
         r#"
 #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{linkage} fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{rethrow_guard}
 {convert_input_code}
     let this: {real_output_typename} = {call};
 {convert_this}
@@ -930,6 +1135,8 @@ pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) ->
     this as jlong
 }}
 "#,
+        rethrow_guard = rethrow_guard_code(ctx),
+        linkage = rust_code::jni_func_linkage(ctx),
         func_name = mc.jni_func_name,
         convert_this = convert_this,
         decl_func_args = mc.decl_func_args,
@@ -1005,9 +1212,9 @@ fn generate_method(
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
-#[no_mangle]
-pub extern "C"
+{linkage}
  fn {func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {decl_func_args}) -> {jni_ret_type} {{
+{rethrow_guard}
 {convert_input_code}
     let this: {this_type_ref} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
@@ -1018,6 +1225,8 @@ pub extern "C"
     {ret_name}
 }}
 "#,
+        rethrow_guard = rethrow_guard_code(ctx),
+        linkage = rust_code::jni_func_linkage(ctx),
         func_name = mc.jni_func_name,
         decl_func_args = mc.decl_func_args,
         convert_input_code = convert_input_code,
@@ -1,12 +1,13 @@
 use log::trace;
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use std::{io::Write, rc::Rc};
 use syn::{Ident, Type};
 
 use super::{
     java_class_full_name, java_class_name_to_jni, java_code::doc_comments_to_java_comments,
-    map_write_err, JavaContext,
+    map_write_err, rust_code, JavaContext, JavaForeignTypeInfo, JniForeignMethodSignature,
+    JniNativeMethod,
 };
 use crate::{
     error::{invalid_src_id_span, DiagnosticError, Result},
@@ -14,13 +15,18 @@ use crate::{
     typemap::{
         ast::{parse_ty_with_given_span, TypeName},
         ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS},
-        RustTypeIdx, TypeConvCode, TypeConvEdge, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+        utils::rust_code_for_bitflags,
+        ForeignTypeInfo, RustTypeIdx, TypeConvCode, TypeConvEdge, FROM_VAR_TEMPLATE,
+        TO_VAR_TEMPLATE,
     },
-    types::ForeignEnumInfo,
+    types::{ForeignEnumInfo, MethodVariant},
     WRITE_TO_MEM_FAILED_MSG,
 };
 
 const C_LIKE_ENUM_TRAIT: &str = "SwigForeignCLikeEnum";
+const BITFLAGS_TRAIT: &str = "SwigForeignBitflags";
+/// Java native method that returns bits of item of bitflags
+const ITEM_BITS_METHOD: &str = "do_itemBits";
 
 pub(in crate::java_jni) fn generate_enum(
     ctx: &mut JavaContext,
@@ -28,6 +34,9 @@ pub(in crate::java_jni) fn generate_enum(
 ) -> Result<()> {
     let enum_name = &fenum.name;
     trace!("generate_enum: enum {}", enum_name);
+    if fenum.is_bitflags {
+        return generate_bitflags(ctx, fenum);
+    }
     if (fenum.items.len() as u64) >= (i32::max_value() as u64) {
         return Err(DiagnosticError::new(
             fenum.src_id,
@@ -97,13 +106,228 @@ pub(in crate::java_jni) fn generate_enum(
     Ok(())
 }
 
+/// Items of bitflags are represented as Java enum, and value as `EnumSet` of them
+fn generate_bitflags(ctx: &mut JavaContext, fenum: &ForeignEnumInfo) -> Result<()> {
+    let flags_ti: Type = parse_ty_with_given_span(&fenum.name.to_string(), fenum.name.span())
+        .map_err(|err| DiagnosticError::from_syn_err(fenum.src_id, err))?;
+    let flags_rty = ctx.conv_map.find_or_alloc_rust_type_that_implements(
+        &flags_ti,
+        &[BITFLAGS_TRAIT],
+        fenum.src_id,
+    );
+
+    generate_java_code_for_bitflags(ctx, fenum)?;
+    ctx.rust_code.push(rust_code_for_bitflags(fenum));
+    generate_rust_code_for_bitflags_item_bits(ctx, fenum)?;
+
+    let jint_rty = ctx.conv_map.ty_to_rust_type(&parse_type! { jint });
+    let java_set_type = format!("java.util.EnumSet<{}>", fenum.name);
+
+    let flags_ftype = ForeignTypeS {
+        name: TypeName::new(java_set_type.clone(), (fenum.src_id, fenum.name.span())),
+        provides_by_module: vec![],
+        into_from_rust: Some(ForeignConversationRule {
+            rust_ty: flags_rty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: jint_rty.to_idx(),
+                conv_code: Rc::new(TypeConvCode::new(
+                    format!(
+                        "        {java_set_type} {out} = {flags_name}.fromBits({var});",
+                        java_set_type = java_set_type,
+                        out = TO_VAR_TEMPLATE,
+                        flags_name = fenum.name,
+                        var = FROM_VAR_TEMPLATE
+                    ),
+                    invalid_src_id_span(),
+                )),
+            }),
+        }),
+        from_into_rust: Some(ForeignConversationRule {
+            rust_ty: flags_rty.to_idx(),
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty: jint_rty.to_idx(),
+                conv_code: Rc::new(TypeConvCode::new(
+                    format!(
+                        "        int {out} = {flags_name}.toBits({var});",
+                        out = TO_VAR_TEMPLATE,
+                        flags_name = fenum.name,
+                        var = FROM_VAR_TEMPLATE
+                    ),
+                    invalid_src_id_span(),
+                )),
+            }),
+        }),
+        name_prefix: None,
+    };
+    ctx.conv_map.alloc_foreign_type(flags_ftype)?;
+    Ok(())
+}
+
+fn generate_java_code_for_bitflags(ctx: &mut JavaContext, fenum: &ForeignEnumInfo) -> Result<()> {
+    let path = ctx.cfg.output_dir.join(format!("{}.java", fenum.name));
+    let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
+    let flags_doc_comments =
+        doc_comments_to_java_comments(&fenum.doc_comments, true, &ctx.exported_items);
+    writeln!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package_name};
+
+{doc_comments}
+public enum {flags_name} {{"#,
+        package_name = ctx.cfg.package_name,
+        flags_name = fenum.name,
+        doc_comments = flags_doc_comments,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    for (i, item) in fenum.items.iter().enumerate() {
+        let mut doc_comments =
+            doc_comments_to_java_comments(&item.doc_comments, false, &ctx.exported_items);
+        if !doc_comments.is_empty() {
+            if !doc_comments.ends_with('\n') {
+                doc_comments.push('\n');
+            }
+            doc_comments.push_str("    ");
+        }
+        writeln!(
+            file,
+            "    {doc_comments}{item_name}{separator}",
+            item_name = item.name,
+            doc_comments = doc_comments,
+            separator = if i == fenum.items.len() - 1 { ';' } else { ',' },
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    if fenum.items.is_empty() {
+        writeln!(file, "    ;").expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    writeln!(
+        file,
+        r#"
+    private static native int {item_bits_method}(int item);
+    private static final int[] BITS;
+    private static final int ALL_BITS;
+    static {{
+        {flags_name}[] flags = values();
+        BITS = new int[flags.length];
+        int allBits = 0;
+        for (int i = 0; i < flags.length; ++i) {{
+            BITS[i] = {item_bits_method}(i);
+            allBits |= BITS[i];
+        }}
+        ALL_BITS = allBits;
+    }}
+
+    /**
+     * Bits of this flag, the same as Rust side uses
+     */
+    public final int bits() {{
+        return BITS[ordinal()];
+    }}
+
+    /**
+     * Convert set of flags to bits, the same as Rust side uses
+     */
+    public static int toBits(java.util.Set<{flags_name}> flags) {{
+        int bits = 0;
+        for ({flags_name} flag : flags) {{
+            bits |= flag.bits();
+        }}
+        return bits;
+    }}
+
+    /**
+     * Convert bits to set of flags
+     * @throws IllegalArgumentException if there are unknown bits
+     */
+    public static java.util.EnumSet<{flags_name}> fromBits(int bits) {{
+        if ((bits & ~ALL_BITS) != 0) {{
+            throw new IllegalArgumentException("Unknown bits for {flags_name}: " + bits);
+        }}
+        java.util.EnumSet<{flags_name}> flags = java.util.EnumSet.noneOf({flags_name}.class);
+        for ({flags_name} flag : values()) {{
+            if (flag.bits() != 0 && (bits & flag.bits()) == flag.bits()) {{
+                flags.add(flag);
+            }}
+        }}
+        return flags;
+    }}
+}}"#,
+        flags_name = fenum.name,
+        item_bits_method = ITEM_BITS_METHOD,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+
+    file.update_file_if_necessary()
+        .map_err(|err| DiagnosticError::new(fenum.src_id, fenum.span(), map_write_err(err)))?;
+    Ok(())
+}
+
+/// Values of Rust constants are unknown during code generation,
+/// so Java asks Rust about bits of each item of bitflags
+fn generate_rust_code_for_bitflags_item_bits(
+    ctx: &mut JavaContext,
+    fenum: &ForeignEnumInfo,
+) -> Result<()> {
+    let jint_rty = ctx.conv_map.ty_to_rust_type(&parse_type! { jint });
+    let flags_name = fenum.name.to_string();
+    let jint_info = || ForeignTypeInfo {
+        name: "int".into(),
+        correspoding_rust_type: jint_rty.clone(),
+    };
+    let func_name = rust_code::generate_jni_func_name(
+        ctx,
+        &flags_name,
+        (fenum.src_id, fenum.span()),
+        ITEM_BITS_METHOD,
+        MethodVariant::StaticMethod,
+        &JniForeignMethodSignature {
+            output: jint_info().into(),
+            input: vec![JavaForeignTypeInfo {
+                base: jint_info(),
+                java_converter: None,
+                annotation: None,
+            }],
+        },
+        false,
+    )?;
+    ctx.native_methods.push(JniNativeMethod {
+        class_name: flags_name.into(),
+        java_method_name: ITEM_BITS_METHOD.into(),
+        input: vec!["int".into()],
+        output: "int".into(),
+        rust_func_name: func_name.clone(),
+    });
+    let linkage: TokenStream = rust_code::jni_func_linkage(ctx)
+        .parse()
+        .expect("linkage of JNI function should be valid Rust code");
+    let func_name = Ident::new(&func_name, Span::call_site());
+    let indexes = (0..fenum.items.len()).map(|i| Literal::i32_unsuffixed(i as i32));
+    let rust_names = fenum.items.iter().map(|x| &x.rust_name);
+    ctx.rust_code.push(quote! {
+        #[allow(non_snake_case)]
+        #linkage fn #func_name(_: *mut JNIEnv, _: jclass, item: jint) -> jint {
+            match item {
+                #(#indexes => #rust_names.bits() as jint,)*
+                _ => 0,
+            }
+        }
+    });
+    Ok(())
+}
+
 fn generate_java_code_for_enum(
     ctx: &mut JavaContext,
     fenum: &ForeignEnumInfo,
 ) -> std::result::Result<(), String> {
     let path = ctx.cfg.output_dir.join(format!("{}.java", fenum.name));
     let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
-    let enum_doc_comments = doc_comments_to_java_comments(&fenum.doc_comments, true);
+    let enum_doc_comments =
+        doc_comments_to_java_comments(&fenum.doc_comments, true, &ctx.exported_items);
     writeln!(
         file,
         r#"// Automatically generated by flapigen
@@ -118,7 +342,8 @@ public enum {enum_name} {{"#,
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
     for (i, item) in fenum.items.iter().enumerate() {
-        let mut doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        let mut doc_comments =
+            doc_comments_to_java_comments(&item.doc_comments, false, &ctx.exported_items);
         if !doc_comments.is_empty() {
             if !doc_comments.ends_with('\n') {
                 doc_comments.push('\n');
@@ -129,7 +354,7 @@ public enum {enum_name} {{"#,
             file,
             "    {doc_comments}{item_name}({index}){separator}",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments,
             separator = if i == fenum.items.len() - 1 { ';' } else { ',' },
         )
@@ -150,12 +375,12 @@ public enum {enum_name} {{"#,
     )
     .expect(WRITE_TO_MEM_FAILED_MSG);
 
-    for (i, item) in fenum.items.iter().enumerate() {
+    for item in &fenum.items {
         write!(
             file,
             r#"
             case {index}: return {item_name};"#,
-            index = i,
+            index = item.value,
             item_name = item.name
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
@@ -180,9 +405,9 @@ fn generate_rust_code_for_enum(ctx: &mut JavaContext, fenum: &ForeignEnumInfo) -
     let mut arms_to_jint = Vec::with_capacity(fenum.items.len());
     let mut arms_from_jint = Vec::with_capacity(fenum.items.len());
     assert!((fenum.items.len() as u64) <= u64::from(i32::max_value() as u32));
-    for (i, item) in fenum.items.iter().enumerate() {
+    for item in &fenum.items {
         let item_name = &item.rust_name;
-        let idx = i as i32;
+        let idx = item.value as i32;
         arms_to_jint.push(quote! { #item_name => #idx });
         arms_from_jint.push(quote! { #idx => #item_name });
     }
@@ -1,27 +1,25 @@
-use lazy_static::lazy_static;
 use petgraph::Direction;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use rustc_hash::FxHashMap;
 use std::io::Write;
-use syn::{spanned::Spanned, Ident};
+use syn::{spanned::Spanned, Ident, Type};
 
 use super::{
-    java_code, map_type::map_type, map_write_err, rust_code, JavaContext, JavaForeignTypeInfo,
-    JniForeignMethodSignature,
+    java_class_full_name, java_code, map_type::map_type, map_write_err, rust_code, JavaContext,
+    JavaForeignTypeInfo, JniForeignMethodSignature,
 };
 use crate::{
-    error::{panic_on_syn_error, DiagnosticError, Result},
+    error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
     file_cache::FileWriteCache,
     source_registry::SourceId,
     typemap::{
-        ast::{DisplayToTokens, TypeName},
+        ast::{if_result_return_ok_err_types, DisplayToTokens, TypeName},
         ty::RustType,
         utils::rust_to_foreign_convert_method_inputs,
         ForeignTypeInfo,
     },
-    types::ForeignInterface,
-    WRITE_TO_MEM_FAILED_MSG,
+    types::{ForeignInterface, ForeignInterfaceMethod},
+    JavaCallbackExceptionPolicy, WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn generate_interface(
@@ -47,6 +45,15 @@ pub(in crate::java_jni) fn generate_interface(
         my_jobj_ti,
         TypeName::from_ident(&interface.name, interface.src_id),
     )?;
+    let interface_name = interface.name.to_string();
+    ctx.java_type_to_jni_sig_map.insert(
+        interface_name.clone().into(),
+        format!(
+            "L{};",
+            java_class_full_name(&ctx.cfg.package_name, &interface_name)
+        )
+        .into(),
+    );
     Ok(())
 }
 
@@ -74,6 +81,14 @@ fn find_suitable_ftypes_for_interace_methods(
             let arg_span = (interace.src_id, named_arg.ty.span());
             let mut f_arg_type = map_type(ctx, &arg_rust_ty, Direction::Outgoing, arg_span)?;
             if let Some(java_conv) = f_arg_type.java_converter.as_ref() {
+                if is_unsigned_integer(arg_rust_ty.normalized_name.as_str()) {
+                    return Err(unsigned_callback_type_error(
+                        ctx,
+                        "argument",
+                        &arg_rust_ty,
+                        arg_span,
+                    ));
+                }
                 // it is hard to use Java code during callback, so may be
                 // there is way to convert it to jobject ?
                 ctx.conv_map
@@ -108,6 +123,10 @@ It is impossible to use this Java code:{}\nfor callback types conversation",
             }
             .into(),
             syn::ReturnType::Type(_, ref ret_ty) => {
+                // for `Result<T, E>` Java method returns `T` and throws exception
+                let ret_ty = callback_result_ok_err_types(ctx, interace, method)
+                    .map(|(ok_ty, _err_ty)| ok_ty)
+                    .unwrap_or_else(|| (**ret_ty).clone());
                 let rust_ret_ty = ctx
                     .conv_map
                     .find_or_alloc_rust_type(&ret_ty, interace.src_id);
@@ -118,6 +137,14 @@ It is impossible to use this Java code:{}\nfor callback types conversation",
                     rust_ret_ty.src_id_span(),
                 )?;
                 if let Some(conv) = f_ret_type.java_converter {
+                    if is_unsigned_integer(rust_ret_ty.normalized_name.as_str()) {
+                        return Err(unsigned_callback_type_error(
+                            ctx,
+                            "return",
+                            &rust_ret_ty,
+                            (interace.src_id, method.fn_decl.output.span()),
+                        ));
+                    }
                     return Err(DiagnosticError::new2(
                         rust_ret_ty.src_id_span(),
                         format!("Java code:\n```{}\n```\n required to convert Rust output type to Java type.
@@ -133,6 +160,29 @@ It is impossible to use for callback function.", conv.converter),
     Ok(f_methods)
 }
 
+fn is_unsigned_integer(rust_type_name: &str) -> bool {
+    matches!(rust_type_name, "u8" | "u16" | "u32" | "u64" | "usize")
+}
+
+/// Range of unsigned integer is checked in Java code,
+/// but Java code of typemap can not be used during callback call
+fn unsigned_callback_type_error(
+    ctx: &JavaContext,
+    what: &str,
+    rust_ty: &RustType,
+    span: SourceIdSpan,
+) -> DiagnosticError {
+    DiagnosticError::new2(
+        span,
+        format!(
+            "unsigned callback {} `{}` is not supported with JavaUnsignedIntegers::{:?}, \
+             because of range of value is checked in Java code, \
+             use JavaUnsignedIntegers::Widen or JavaUnsignedIntegers::SameWidth",
+            what, rust_ty, ctx.cfg.unsigned_integers
+        ),
+    )
+}
+
 fn generate_java_code_for_interface(
     ctx: &mut JavaContext,
     interface: &ForeignInterface,
@@ -142,8 +192,11 @@ fn generate_java_code_for_interface(
     let path = ctx.cfg.output_dir.join(format!("{}.java", interface.name));
     let mut file = FileWriteCache::new(&path, ctx.generated_foreign_files);
     let imports = java_code::get_null_annotation_imports(use_null_annotation, methods_sign);
-    let interface_comments =
-        java_code::doc_comments_to_java_comments(&interface.doc_comments, true);
+    let interface_comments = java_code::doc_comments_to_java_comments(
+        &interface.doc_comments,
+        true,
+        &ctx.exported_items,
+    );
     writeln!(
         file,
         r#"// Automatically generated by flapigen
@@ -163,9 +216,13 @@ public interface {interface_name} {{"#,
             file,
             r#"
 {doc_comments}
-    {output_type} {method_name}({single_args_with_types});"#,
+    {output_type} {method_name}({single_args_with_types}){exception_spec};"#,
             method_name = method.name,
-            doc_comments = java_code::doc_comments_to_java_comments(&method.doc_comments, false),
+            doc_comments = java_code::doc_comments_to_java_comments(
+                &method.doc_comments,
+                false,
+                &ctx.exported_items
+            ),
             single_args_with_types = java_code::args_with_java_types(
                 f_method,
                 method.arg_names_without_self(),
@@ -173,6 +230,11 @@ public interface {interface_name} {{"#,
                 use_null_annotation.is_some()
             ),
             output_type = f_method.output.base.name,
+            exception_spec = if callback_result_ok_err_types(ctx, interface, method).is_some() {
+                " throws Exception"
+            } else {
+                ""
+            },
         )
         .expect(WRITE_TO_MEM_FAILED_MSG);
     }
@@ -220,6 +282,7 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
     }
     new_conv_code.push_str(
         r#"
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, class) };
         Box::new(cb)
     }
 }
@@ -264,7 +327,7 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
         );
         assert!(!method.fn_decl.inputs.is_empty());
         let n_args = method.fn_decl.inputs.len() - 1;
-        let (args, type_size_asserts) = convert_args_for_variadic_function_call(f_method);
+        let (args, type_size_asserts) = convert_args_for_variadic_function_call(ctx, f_method);
 
         let (mut conv_deps, convert_args_code) = rust_to_foreign_convert_method_inputs(
             ctx.conv_map,
@@ -283,31 +346,56 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
             )
         });
         match method.fn_decl.output {
-            syn::ReturnType::Default => trait_impl_funcs.push(quote! {
-                #[allow(unused_mut)]
-                fn #func_name(#(#args_with_types),*) {
-                    #type_size_asserts
-                    let env = self.get_jni_env();
-                    if let Some(env) = env.env {
-                        #convert_args
-                        unsafe {
-                            (**env).CallVoidMethod.unwrap()(env, self.this,
-                                                            self.methods[#method_idx],
-                                                            #(#args),*);
-                            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
-                                (**env).ExceptionDescribe.unwrap()(env);
-                                (**env).ExceptionClear.unwrap()(env);
-                            }
-                        };
+            syn::ReturnType::Default => {
+                let exception_check =
+                    exception_check_code(ctx.cfg.callback_exception_policy, func_name);
+                trait_impl_funcs.push(quote! {
+                    #[allow(unused_mut)]
+                    fn #func_name(#(#args_with_types),*) {
+                        #type_size_asserts
+                        let env = self.get_jni_env();
+                        if let Some(env) = env.env {
+                            #convert_args
+                            unsafe {
+                                (**env).CallVoidMethod.unwrap()(env, self.this,
+                                                                self.methods[#method_idx],
+                                                                #(#args),*);
+                                #exception_check
+                            };
+                        }
                     }
-                }
-            }),
+                })
+            }
             syn::ReturnType::Type(_, ref ret_ty) => {
-                let real_output_type: RustType = ctx
-                    .conv_map
-                    .find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let result_ok_ty = callback_result_ok_err_types(ctx, interface, method)
+                    .map(|(ok_ty, _err_ty)| ok_ty);
+                let real_output_type: RustType = ctx.conv_map.find_or_alloc_rust_type(
+                    result_ok_ty.as_ref().unwrap_or(ret_ty),
+                    interface.src_id,
+                );
                 let jni_ret_type = &f_method.output.base.correspoding_rust_type;
+                if result_ok_ty.is_some() && jni_ret_type.normalized_name == "()" {
+                    trait_impl_funcs.push(quote! {
+                        #[allow(unused_mut)]
+                        fn #func_name(#(#args_with_types),*) -> #ret_ty {
+                            #type_size_asserts
+                            let env = self.get_jni_env();
+                            let env = env.env.expect(concat!("Can not get env for ", stringify!(#func_name)));
+
+                            #convert_args
+                            unsafe {
+                                (**env).CallVoidMethod.unwrap()(env, self.this,
+                                                                self.methods[#method_idx],
+                                                                #(#args),*);
+                            };
+                            if let Some(ex) = JavaException::take_pending(env) {
+                                return Err(From::from(ex));
+                            }
+                            Ok(())
+                        }
+                    });
+                    continue;
+                }
                 let (mut conv_deps, out_conv_code) = ctx.conv_map.convert_rust_types(
                     jni_ret_type.to_idx(),
                     real_output_type.to_idx(),
@@ -318,45 +406,75 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
                 )?;
                 ctx.rust_code.append(&mut conv_deps);
 
-                let jni_caller = match jni_ret_type.normalized_name.as_str() {
-                    "jboolean" => quote! { CallBooleanMethod },
-                    "jbyte" => quote! {CallByteMethod },
-                    "jshort" => quote! { CallShortMethod },
-                    "jint" => quote!{ CallIntMethod },
-                    "jlong" => quote!{ CallLongMethod },
-                    "jfloat" => quote!{ CallFloatMethod },
-                    "jdouble" => quote!{ CallDoubleMethod },
-                    "jobject" => quote!{ CallObjectMethod },
+                // choose by JNI signature, because of JNI type can be hidden behind alias
+                let jni_ret_sig =
+                    rust_code::java_type_name_to_jni_signature(ctx, &f_method.output.base.name);
+                let jni_caller = match jni_ret_sig.as_deref().and_then(|x| x.chars().next()) {
+                    Some('Z') => quote! { CallBooleanMethod },
+                    Some('B') => quote! {CallByteMethod },
+                    Some('S') => quote! { CallShortMethod },
+                    Some('I') => quote!{ CallIntMethod },
+                    Some('J') => quote!{ CallLongMethod },
+                    Some('F') => quote!{ CallFloatMethod },
+                    Some('D') => quote!{ CallDoubleMethod },
+                    Some('L') | Some('[') => quote!{ CallObjectMethod },
                     _ => return Err(DiagnosticError::new2(jni_ret_type.src_id_span(),
                                                           format!("Have not idea how to handle this type `{}` as return of callback function", jni_ret_type))),
                 };
                 let jni_ret_type = &jni_ret_type.ty;
-                let out_conv_code: TokenStream = syn::parse_str(&out_conv_code).unwrap_or_else(|err| {
-                    panic_on_syn_error("Internal: java_jni/finterface: out_conv_code", out_conv_code, err)
-                });
-                trait_impl_funcs.push(quote! {
-                    #[allow(unused_mut)]
-                    fn #func_name(#(#args_with_types),*) -> #ret_ty {
-                        #type_size_asserts
-                        let env = self.get_jni_env();
-                        let env = env.env.expect(concat!("Can not get env for ", stringify!(#func_name)));
+                let out_conv_code: TokenStream =
+                    syn::parse_str(&out_conv_code).unwrap_or_else(|err| {
+                        panic_on_syn_error(
+                            "Internal: java_jni/finterface: out_conv_code",
+                            out_conv_code,
+                            err,
+                        )
+                    });
+                if result_ok_ty.is_some() {
+                    trait_impl_funcs.push(quote! {
+                        #[allow(unused_mut)]
+                        fn #func_name(#(#args_with_types),*) -> #ret_ty {
+                            #type_size_asserts
+                            let env = self.get_jni_env();
+                            let env = env.env.expect(concat!("Can not get env for ", stringify!(#func_name)));
 
-                        #convert_args
-                        let mut ret: #jni_ret_type;
-                        unsafe {
-                            ret = (**env).#jni_caller.unwrap()(env, self.this,
-                                                            self.methods[#method_idx],
-                                                            #(#args),*);
-                            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
-                                (**env).ExceptionDescribe.unwrap()(env);
-                                (**env).ExceptionClear.unwrap()(env);
+                            #convert_args
+                            let mut ret: #jni_ret_type;
+                            unsafe {
+                                ret = (**env).#jni_caller.unwrap()(env, self.this,
+                                                                self.methods[#method_idx],
+                                                                #(#args),*);
+                            };
+                            if let Some(ex) = JavaException::take_pending(env) {
+                                return Err(From::from(ex));
                             }
-                        };
-                        #out_conv_code
-                        ret
-                    }
-                });  
+                            #out_conv_code
+                            Ok(ret)
+                        }
+                    });
+                } else {
+                    let exception_check =
+                        exception_check_code(ctx.cfg.callback_exception_policy, func_name);
+                    trait_impl_funcs.push(quote! {
+                        #[allow(unused_mut)]
+                        fn #func_name(#(#args_with_types),*) -> #ret_ty {
+                            #type_size_asserts
+                            let env = self.get_jni_env();
+                            let env = env.env.expect(concat!("Can not get env for ", stringify!(#func_name)));
+
+                            #convert_args
+                            let mut ret: #jni_ret_type;
+                            unsafe {
+                                ret = (**env).#jni_caller.unwrap()(env, self.this,
+                                                                self.methods[#method_idx],
+                                                                #(#args),*);
+                                #exception_check
+                            };
+                            #out_conv_code
+                            ret
+                        }
+                    });
+                }
             }
         }
     }
@@ -371,15 +489,47 @@ impl SwigFrom<jobject> for Box<dyn {trait_name}> {{
     Ok(())
 }
 
-lazy_static! {
-    static ref JNI_FOR_VARIADIC_C_FUNC_CALL: FxHashMap<&'static str, &'static str> = {
-        let mut m = FxHashMap::default();
-        m.insert("jboolean", "::std::ffi::c_uint");
-        m.insert("jbyte", "::std::ffi::c_int");
-        m.insert("jshort", "::std::ffi::c_int");
-        m.insert("jfloat", "f64");
-        m
-    };
+/// If callback method returns `Result<T, E>` return `(T, E)`
+fn callback_result_ok_err_types(
+    ctx: &mut JavaContext,
+    interface: &ForeignInterface,
+    method: &ForeignInterfaceMethod,
+) -> Option<(Type, Type)> {
+    match method.fn_decl.output {
+        syn::ReturnType::Default => None,
+        syn::ReturnType::Type(_, ref ret_ty) => {
+            let rust_ret_ty = ctx
+                .conv_map
+                .find_or_alloc_rust_type(ret_ty, interface.src_id);
+            if_result_return_ok_err_types(&rust_ret_ty)
+        }
+    }
+}
+
+/// Code to handle exception after call of Java method,
+/// should be placed inside `unsafe` block
+fn exception_check_code(policy: JavaCallbackExceptionPolicy, func_name: &Ident) -> TokenStream {
+    match policy {
+        JavaCallbackExceptionPolicy::Log => quote! {
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                log::error!(concat!(stringify!(#func_name), ": java throw exception"));
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+            }
+        },
+        JavaCallbackExceptionPolicy::Panic => quote! {
+            if let Some(ex) = JavaException::take_pending(env) {
+                panic!(concat!(stringify!(#func_name), ": java throw exception: {}\n{}"),
+                       ex, ex.stack_trace);
+            }
+        },
+        JavaCallbackExceptionPolicy::Rethrow => quote! {
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                log::debug!(concat!(stringify!(#func_name), ": java throw exception, rethrow it later"));
+                jni_stash_pending_exception(env);
+            }
+        },
+    }
 }
 
 // To use `C` function with variable number of arguments,
@@ -387,22 +537,17 @@ lazy_static! {
 // http://en.cppreference.com/w/c/language/conversion#Default_argument_promotions
 // for more details.
 // return arg with conversation plus asserts
+// JNI type is detected by JNI signature of Java type,
+// because of it can be hidden behind alias.
 fn convert_args_for_variadic_function_call(
+    ctx: &JavaContext,
     f_method: &JniForeignMethodSignature,
 ) -> (Vec<TokenStream>, TokenStream) {
     let mut ret = Vec::with_capacity(f_method.input.len());
     for (i, arg) in f_method.input.iter().enumerate() {
         let arg_name = Ident::new(&format!("a{}", i), Span::call_site());
-        if let Some(conv_type_str) = JNI_FOR_VARIADIC_C_FUNC_CALL
-            .get(&*arg.as_ref().correspoding_rust_type.normalized_name.as_str())
-        {
-            let conv_type: TokenStream = syn::parse_str(*conv_type_str).unwrap_or_else(|err| {
-                panic_on_syn_error(
-                    "java/jni internal error: can not parse type for variable conversation",
-                    conv_type_str.to_string(),
-                    err,
-                )
-            });
+        let jni_sig = rust_code::java_type_name_to_jni_signature(ctx, arg.as_ref().name.as_str());
+        if let Some(conv_type) = variadic_arg_promotion(jni_sig.as_deref()) {
             ret.push(quote!(#arg_name as #conv_type));
         } else {
             ret.push(quote!(#arg_name));
@@ -414,3 +559,14 @@ fn convert_args_for_variadic_function_call(
     };
     (ret, check_sizes)
 }
+
+/// Type to which argument with such JNI signature should be promoted
+/// to pass it to variadic function, like `Call*Method` or `NewObject`
+pub(in crate::java_jni) fn variadic_arg_promotion(jni_sig: Option<&str>) -> Option<TokenStream> {
+    match jni_sig {
+        Some("Z") => Some(quote!(::std::ffi::c_uint)),
+        Some("B") | Some("S") => Some(quote!(::std::ffi::c_int)),
+        Some("F") => Some(quote!(f64)),
+        _ => None,
+    }
+}
@@ -0,0 +1,708 @@
+//! Classes marked with `#[derive(JavaRecord)]` are represented as Java records:
+//! immutable values without pointer to Rust object, record components are
+//! read via getters of Rust type during conversation to Java,
+//! and the constructor is used to create Rust object from Java record
+
+use log::debug;
+use petgraph::Direction;
+use smol_str::SmolStr;
+use std::fmt::Write;
+use syn::{parse_quote, spanned::Spanned};
+
+use super::{
+    fclass::{self, JavaRecordHeader},
+    finterface::variadic_arg_promotion,
+    java_class_full_name, java_class_name_to_jni,
+    java_code::{self, filter_null_annotation},
+    map_type::map_type,
+    rust_code, JavaContext, JavaForeignTypeInfo, JniForeignMethodSignature, NullAnnotation,
+    JAVA_RECORD_NATIVE_CLASS,
+};
+use crate::{
+    error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result},
+    source_registry::SourceId,
+    typemap::{
+        ast::{if_result_return_ok_err_types, normalize_type, TypeName},
+        ty::{ForeignConversationRule, ForeignTypeS, RustType},
+        ForeignTypeInfo, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    types::{
+        FnArg, ForeignClassInfo, ForeignMethod, MethodAccess, MethodVariant, NamedArg,
+        SelfTypeDesc, SelfTypeVariant,
+    },
+    JAVA_RECORD, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Name of argument that replaces `self` in native methods of record
+const RECORD_SELF_ARG: &str = "self_";
+
+pub(in crate::java_jni) fn register_typemap_for_record(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    this_type: RustType,
+    self_desc: &SelfTypeDesc,
+) -> Result<()> {
+    debug!("register_typemap_for_record: {}", this_type);
+    if !class.copy_derived() && !class.clone_derived() {
+        return Err(DiagnosticError::new(
+            class.src_id,
+            class.span(),
+            format!(
+                "class {}: {} can be derived only together with Copy or Clone",
+                class.name, JAVA_RECORD
+            ),
+        ));
+    }
+    if normalize_type(&self_desc.self_type) != normalize_type(&self_desc.constructor_ret_type) {
+        return Err(DiagnosticError::new(
+            class.src_id,
+            self_desc.constructor_ret_type.span(),
+            format!(
+                "class {}: {} requires constructor that returns self type by value",
+                class.name, JAVA_RECORD
+            ),
+        ));
+    }
+    let record_rty = ctx.conv_map.find_or_alloc_rust_type_with_suffix(
+        &parse_type! { jobject },
+        &format!("{}Record", class.name),
+        class.src_id,
+    );
+    let span = this_type.ty.span();
+    let this_type_ty = this_type.to_type_without_lifetimes();
+    let gen_ty = parse_type_spanned_checked!(span, & #this_type_ty);
+    let this_type_ref = ctx.conv_map.find_or_alloc_rust_type(&gen_ty, class.src_id);
+
+    let (into_java, from_java) = record_conv_func_names(class);
+    let rules = [
+        (
+            this_type.to_idx(),
+            record_rty.to_idx(),
+            format!(
+                "        let {to_var}: jobject = {into_java}(env, &{from_var});",
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                into_java = into_java,
+            ),
+        ),
+        (
+            this_type_ref.to_idx(),
+            record_rty.to_idx(),
+            format!(
+                "        let {to_var}: jobject = {into_java}(env, {from_var});",
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                into_java = into_java,
+            ),
+        ),
+        (
+            record_rty.to_idx(),
+            this_type.to_idx(),
+            format!(
+                r#"
+        let {to_var}: {this_type} = match {from_java}(env, {from_var}) {{
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        }};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                this_type = this_type,
+                from_java = from_java,
+            ),
+        ),
+        (
+            record_rty.to_idx(),
+            this_type_ref.to_idx(),
+            format!(
+                r#"
+        let {to_var}: {this_type} = match {from_java}(env, {from_var}) {{
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        }};
+        let {to_var}: &{this_type} = &{to_var};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                this_type = this_type,
+                from_java = from_java,
+            ),
+        ),
+    ];
+    for (from, to, code) in rules.iter() {
+        ctx.conv_map.add_conversation_rule(
+            *from,
+            *to,
+            TypeConvCode::new2(code.as_str(), invalid_src_id_span()).into(),
+        );
+    }
+
+    let null_annot = if ctx.cfg.null_annotation_package.is_some() {
+        "@NonNull "
+    } else {
+        ""
+    };
+    ctx.conv_map.alloc_foreign_type(ForeignTypeS {
+        name: TypeName::new(
+            format!("{}{}", null_annot, class.name),
+            (class.src_id, class.name.span()),
+        ),
+        provides_by_module: vec![],
+        into_from_rust: Some(ForeignConversationRule {
+            rust_ty: record_rty.to_idx(),
+            intermediate: None,
+        }),
+        from_into_rust: Some(ForeignConversationRule {
+            rust_ty: record_rty.to_idx(),
+            intermediate: None,
+        }),
+        name_prefix: None,
+    })?;
+    Ok(())
+}
+
+/// Component of record: value returned by getter and
+/// argument of constructor with the same name
+struct RecordComponent<'a> {
+    name: SmolStr,
+    getter: &'a ForeignMethod,
+    getter_ret_ty: RustType,
+    ctor_arg_ty: RustType,
+    jni_sig: String,
+}
+
+pub(in crate::java_jni) fn generate(ctx: &mut JavaContext, class: &ForeignClassInfo) -> Result<()> {
+    debug!("generate record {}", class.name);
+    let mut constructors = class
+        .methods
+        .iter()
+        .filter(|m| m.variant == MethodVariant::Constructor && !m.is_dummy_constructor());
+    let ctor = match (constructors.next(), constructors.next()) {
+        (Some(ctor), None) => ctor,
+        _ => {
+            return Err(DiagnosticError::new(
+                class.src_id,
+                class.span(),
+                format!(
+                    "class {}: {} requires exactly one constructor, \
+                     with arguments that are record components",
+                    class.name, JAVA_RECORD
+                ),
+            ));
+        }
+    };
+
+    let mut components = Vec::<RecordComponent>::with_capacity(ctor.fn_decl.inputs.len());
+    let mut components_sign = Vec::<JniForeignMethodSignature>::with_capacity(components.len());
+    for arg in &ctor.fn_decl.inputs {
+        let arg = arg
+            .as_named_arg()
+            .map_err(|err| DiagnosticError::from_syn_err(class.src_id, err))?;
+        let getter = class
+            .methods
+            .iter()
+            .find(|m| is_getter(m) && m.short_name() == arg.name.as_str())
+            .ok_or_else(|| {
+                DiagnosticError::new(
+                    class.src_id,
+                    arg.span,
+                    format!(
+                        "class {}: {} requires getter `fn {}(&self) -> T` for constructor argument",
+                        class.name, JAVA_RECORD, arg.name
+                    ),
+                )
+            })?;
+        let getter_ret = match getter.fn_decl.output {
+            syn::ReturnType::Type(_, ref ty) => ty,
+            syn::ReturnType::Default => unreachable!("getter should return value"),
+        };
+        let getter_ret_ty = ctx
+            .conv_map
+            .find_or_alloc_rust_type(getter_ret, class.src_id);
+        let output = map_type(
+            ctx,
+            &getter_ret_ty,
+            Direction::Outgoing,
+            (class.src_id, getter_ret.span()),
+        )?;
+        let ctor_arg_ty = ctx.conv_map.find_or_alloc_rust_type(&arg.ty, class.src_id);
+        let input = map_type(
+            ctx,
+            &ctor_arg_ty,
+            Direction::Incoming,
+            (class.src_id, arg.ty.span()),
+        )?;
+        if output.java_converter.is_some() || input.java_converter.is_some() {
+            return Err(DiagnosticError::new(
+                class.src_id,
+                arg.ty.span(),
+                format!(
+                    "class {}: type of record component {} requires conversion on Java side, \
+                     this is not supported for records",
+                    class.name, arg.name
+                ),
+            ));
+        }
+        let java_type = filter_null_annotation(output.base.name.as_str());
+        if java_type.trim() != filter_null_annotation(input.base.name.as_str()).trim() {
+            return Err(DiagnosticError::new(
+                class.src_id,
+                arg.ty.span(),
+                format!(
+                    "class {}: record component {} has type {} in getter, but {} in constructor",
+                    class.name, arg.name, output.base.name, input.base.name
+                ),
+            ));
+        }
+        let jni_sig =
+            rust_code::java_type_name_to_jni_signature(ctx, &java_type).ok_or_else(|| {
+                DiagnosticError::new(
+                    class.src_id,
+                    arg.ty.span(),
+                    format!(
+                        "class {}: can not find JNI signature for type {} of record component {}",
+                        class.name, output.base.name, arg.name
+                    ),
+                )
+            })?;
+        components.push(RecordComponent {
+            name: arg.name.clone(),
+            getter,
+            getter_ret_ty,
+            ctor_arg_ty,
+            jni_sig,
+        });
+        components_sign.push(JniForeignMethodSignature {
+            output,
+            input: vec![input],
+        });
+    }
+
+    let use_null_annotation = ctx.cfg.null_annotation_package.is_some();
+    let mut header = String::new();
+    for (i, (comp, sign)) in components.iter().zip(components_sign.iter()).enumerate() {
+        if i != 0 {
+            header.push_str(", ");
+        }
+        let type_name = sign.output.base.name.as_str();
+        let annotation = match sign.output.annotation {
+            Some(NullAnnotation::NonNull) if use_null_annotation && !type_name.contains('@') => {
+                "@NonNull "
+            }
+            Some(NullAnnotation::Nullable) if use_null_annotation && !type_name.contains('@') => {
+                "@Nullable "
+            }
+            _ => "",
+        };
+        write!(&mut header, "{}{} {}", annotation, type_name, comp.name)
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+
+    generate_rust_conv_funcs(ctx, class, ctor, &components, &components_sign)?;
+
+    let (methods, java_wrappers) = methods_to_static(ctx, class, &components, use_null_annotation)?;
+    let record_class = ForeignClassInfo {
+        src_id: class.src_id,
+        name: class.name.clone(),
+        methods,
+        self_desc: None,
+        foreign_code: class.foreign_code.clone(),
+        doc_comments: class.doc_comments.clone(),
+        derive_list: class.derive_list.clone(),
+    };
+    fclass::generate_record(
+        ctx,
+        &record_class,
+        JavaRecordHeader {
+            components: header,
+            components_sign: &components_sign,
+            methods: java_wrappers,
+        },
+    )
+}
+
+fn is_getter(method: &ForeignMethod) -> bool {
+    matches!(
+        method.variant,
+        MethodVariant::Method(SelfTypeVariant::Rptr)
+            | MethodVariant::Method(SelfTypeVariant::Default)
+    ) && method.fn_decl.inputs.len() == 1
+        && method.fn_decl.output != syn::ReturnType::Default
+}
+
+/// records are immutable, so there is no need in `clone` required by `Copy`
+fn is_clone(method: &ForeignMethod) -> bool {
+    method.variant == MethodVariant::Method(SelfTypeVariant::Rptr)
+        && method.fn_decl.inputs.len() == 1
+        && method
+            .rust_id
+            .segments
+            .last()
+            .map(|seg| seg.ident == "clone")
+            .unwrap_or(false)
+}
+
+fn record_conv_func_names(class: &ForeignClassInfo) -> (String, String) {
+    (
+        format!("swig_{}_into_java_record", class.name),
+        format!("swig_{}_from_java_record", class.name),
+    )
+}
+
+/// Generate Rust functions to create Java record from Rust object and back
+fn generate_rust_conv_funcs(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    ctor: &ForeignMethod,
+    components: &[RecordComponent],
+    components_sign: &[JniForeignMethodSignature],
+) -> Result<()> {
+    let (into_java, from_java) = record_conv_func_names(class);
+    let class_name_upper = class.name.to_string().to_uppercase();
+    let class_id = format!("FOREIGN_RECORD_{}", class_name_upper);
+    let class_name_for_jni = java_class_name_to_jni(&java_class_full_name(
+        &ctx.cfg.package_name,
+        &class.name.to_string(),
+    ));
+    let this_type = class.self_type_as_ty();
+    let this_type = normalize_type(&this_type);
+
+    let mut get_components = String::new();
+    let mut ctor_sig = String::from("(");
+    let mut ctor_args = String::new();
+    let mut delete_refs = String::new();
+    let mut get_fields = String::new();
+    let mut conv_fields = String::new();
+    let mut delete_field_refs = String::new();
+    for (comp, sign) in components.iter().zip(components_sign) {
+        let jni_ty = sign.output.base.correspoding_rust_type.clone();
+        let call = comp.getter.generate_code_to_call_rust_func();
+        let (mut deps, conv_code) = ctx.conv_map.convert_rust_types(
+            comp.getter_ret_ty.to_idx(),
+            jni_ty.to_idx(),
+            &comp.name,
+            &comp.name,
+            "jobject",
+            (class.src_id, comp.getter.span()),
+        )?;
+        ctx.rust_code.append(&mut deps);
+        writeln!(
+            &mut get_components,
+            "    let mut {name}: {ty} = {call};\n{conv_code}",
+            name = comp.name,
+            ty = comp.getter_ret_ty,
+            call = call,
+            conv_code = conv_code,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        ctor_sig.push_str(&comp.jni_sig);
+        match variadic_arg_promotion(Some(&comp.jni_sig)) {
+            Some(promote_to) => write!(&mut ctor_args, ", {} as {}", comp.name, promote_to),
+            None => write!(&mut ctor_args, ", {}", comp.name),
+        }
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        let is_object = comp.jni_sig.starts_with('L') || comp.jni_sig.starts_with('[');
+        if is_object {
+            writeln!(
+                &mut delete_refs,
+                "    unsafe {{ (**env).DeleteLocalRef.unwrap()(env, {}) }};",
+                comp.name
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+
+        let jni_in_ty = sign.input[0].base.correspoding_rust_type.clone();
+        let get_field_func = if is_object {
+            "GetObjectField"
+        } else {
+            match comp.jni_sig.as_str() {
+                "Z" => "GetBooleanField",
+                "B" => "GetByteField",
+                "C" => "GetCharField",
+                "S" => "GetShortField",
+                "I" => "GetIntField",
+                "J" => "GetLongField",
+                "F" => "GetFloatField",
+                "D" => "GetDoubleField",
+                _ => unreachable!("unknown JNI signature {}", comp.jni_sig),
+            }
+        };
+        let (mut deps, conv_code) = ctx.conv_map.convert_rust_types(
+            jni_in_ty.to_idx(),
+            comp.ctor_arg_ty.to_idx(),
+            &comp.name,
+            &comp.name,
+            &this_type,
+            (class.src_id, ctor.span()),
+        )?;
+        ctx.rust_code.append(&mut deps);
+        writeln!(
+            &mut get_fields,
+            r#"    let field: jfieldID = swig_jni_get_field_id!({class_id}_{field_id}_FIELD, {class_id}, "{name}", "{sig}");
+    let mut {name}: {jni_ty} = unsafe {{ (**env).{get_field_func}.unwrap()(env, obj, field) }};"#,
+            field_id = comp.name.to_uppercase(),
+            class_id = class_id,
+            name = comp.name,
+            sig = comp.jni_sig,
+            jni_ty = jni_in_ty.typename(),
+            get_field_func = get_field_func,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        conv_fields.push_str(&conv_code);
+        if is_object {
+            writeln!(
+                &mut delete_field_refs,
+                "    unsafe {{ (**env).DeleteLocalRef.unwrap()(env, {}) }};",
+                comp.name
+            )
+            .expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+    }
+    ctor_sig.push_str(")V");
+
+    let code = format!(
+        r#"
+#[allow(dead_code, unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+fn {into_java}(env: *mut JNIEnv, this: &{this_type}) -> jobject {{
+{get_components}
+    let class: jclass = swig_jni_find_class!({class_id}, "{class_name_for_jni}");
+    let ctor: jmethodID = swig_jni_get_method_id!({class_id}_CTOR, {class_id}, "<init>", "{ctor_sig}");
+    let ret: jobject = unsafe {{ (**env).NewObject.unwrap()(env, class, ctor{ctor_args}) }};
+{delete_refs}
+    if unsafe {{ (**env).ExceptionCheck.unwrap()(env) }} != 0 {{
+        // exception is pending, it will be thrown after return to Java
+        return ::std::ptr::null_mut();
+    }}
+    ret
+}}
+#[allow(dead_code, unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+fn {from_java}(env: *mut JNIEnv, obj: jobject) -> Option<{this_type}> {{
+    if obj.is_null() {{
+        jni_throw_null_pointer_exception(env, "{class_name}: null record");
+        return None;
+    }}
+{get_fields}
+    // result of conversation may borrow from local reference,
+    // like `&str`, so delete references after constructor call
+    let ret: {this_type} = {{
+{conv_fields}
+        {call}
+    }};
+{delete_field_refs}
+    Some(ret)
+}}
+impl JniInvalidValue for {this_type} {{
+    // callback can not report error to caller, so `null` returned by it is fatal
+    fn jni_invalid_value() -> Self {{
+        panic!("{class_name}: null record");
+    }}
+}}
+"#,
+        into_java = into_java,
+        from_java = from_java,
+        this_type = this_type,
+        get_components = get_components,
+        class_id = class_id,
+        class_name_for_jni = class_name_for_jni,
+        ctor_sig = ctor_sig,
+        ctor_args = ctor_args,
+        delete_refs = delete_refs,
+        get_fields = get_fields,
+        conv_fields = conv_fields,
+        delete_field_refs = delete_field_refs,
+        class_name = class.name,
+        call = ctor.generate_code_to_call_rust_func(),
+    );
+    debug!("generate_rust_conv_funcs: {}", code);
+    ctx.rust_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("java/jni internal record", code, err)),
+    );
+    Ok(())
+}
+
+/// Records can not have native methods and have no pointer to Rust object,
+/// so all methods are converted to private static methods of nested class,
+/// methods with `self` get record as the first argument.
+/// Public methods of record call them. Getters and constructor
+/// are replaced by record accessors and canonical constructor
+fn methods_to_static(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    components: &[RecordComponent],
+    use_null_annotation: bool,
+) -> Result<(Vec<ForeignMethod>, String)> {
+    let mut methods = Vec::with_capacity(class.methods.len());
+    let mut java_wrappers = String::new();
+    for method in &class.methods {
+        let mut static_method = method.clone();
+        static_method.variant = MethodVariant::StaticMethod;
+        static_method.access = MethodAccess::Private;
+        static_method.doc_comments = vec![];
+        match method.variant {
+            MethodVariant::Constructor => continue,
+            MethodVariant::StaticMethod => {}
+            MethodVariant::Method(_)
+                if is_clone(method)
+                    || components
+                        .iter()
+                        .any(|comp| std::ptr::eq(comp.getter, method)) =>
+            {
+                continue;
+            }
+            MethodVariant::Method(self_variant) => {
+                let self_span = match method.fn_decl.inputs[0] {
+                    FnArg::SelfArg(span, _) => span,
+                    FnArg::Default(ref arg) => arg.span,
+                };
+                let self_type = class.self_type_as_ty();
+                let self_arg_ty: syn::Type = match self_variant {
+                    SelfTypeVariant::Rptr => parse_quote! { & #self_type },
+                    SelfTypeVariant::Default | SelfTypeVariant::Mut => self_type,
+                    SelfTypeVariant::RptrMut => {
+                        return Err(DiagnosticError::new(
+                            class.src_id,
+                            method.rust_id.span(),
+                            format!(
+                                "class {}: method {} of {} can not change object, use `&self` or `self`",
+                                class.name,
+                                method.short_name(),
+                                JAVA_RECORD
+                            ),
+                        ));
+                    }
+                };
+                if method
+                    .arg_names_without_self()
+                    .any(|name| name == RECORD_SELF_ARG)
+                {
+                    return Err(DiagnosticError::new(
+                        class.src_id,
+                        method.rust_id.span(),
+                        format!(
+                            "In method {} there is argument with name {}, this name reserved for generated code",
+                            method.short_name(),
+                            RECORD_SELF_ARG
+                        ),
+                    ));
+                }
+                let self_arg_name = syn::Ident::new(RECORD_SELF_ARG, self_span);
+                static_method.fn_decl.inputs[0] = FnArg::Default(NamedArg {
+                    name: RECORD_SELF_ARG.into(),
+                    span: self_span,
+                    ty: self_arg_ty,
+                });
+                static_method.inline_block = method.inline_block.as_ref().map(|block| {
+                    parse_quote! {{
+                        let this = #self_arg_name;
+                        #block
+                    }}
+                });
+            }
+        }
+        write_java_wrapper(
+            ctx,
+            class.src_id,
+            method,
+            &mut java_wrappers,
+            use_null_annotation,
+        )?;
+        methods.push(static_method);
+    }
+    Ok((methods, java_wrappers))
+}
+
+/// Public Java method of record that calls static method with
+/// the same name from nested class, with `this` as the first argument
+/// if method is not static
+fn write_java_wrapper(
+    ctx: &mut JavaContext,
+    src_id: SourceId,
+    method: &ForeignMethod,
+    out: &mut String,
+    use_null_annotation: bool,
+) -> Result<()> {
+    let mut args = Vec::<JavaForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len());
+    for arg in &method.fn_decl.inputs {
+        let named_arg = match arg {
+            FnArg::SelfArg(..) => continue,
+            FnArg::Default(ref arg) => arg,
+        };
+        let arg_rust_ty = ctx.conv_map.find_or_alloc_rust_type(&named_arg.ty, src_id);
+        args.push(map_type(
+            ctx,
+            &arg_rust_ty,
+            Direction::Incoming,
+            (src_id, named_arg.ty.span()),
+        )?);
+    }
+    let (ret_type, may_return_error) = match method.fn_decl.output {
+        syn::ReturnType::Default => ("void".into(), false),
+        syn::ReturnType::Type(_, ref ty) => {
+            let ret_rust_ty = ctx.conv_map.find_or_alloc_rust_type(ty, src_id);
+            let output = map_type(ctx, &ret_rust_ty, Direction::Outgoing, (src_id, ty.span()))?;
+            (
+                output.base.name,
+                if_result_return_ok_err_types(&ret_rust_ty).is_some(),
+            )
+        }
+    };
+    let sign = JniForeignMethodSignature {
+        output: ForeignTypeInfo {
+            name: ret_type.clone(),
+            correspoding_rust_type: ctx.conv_map.ty_to_rust_type(&parse_type! { () }),
+        }
+        .into(),
+        input: args,
+    };
+    let args_with_types = java_code::args_with_java_types(
+        &sign,
+        method.arg_names_without_self(),
+        java_code::ArgsFormatFlags::EXTERNAL,
+        use_null_annotation,
+    );
+    let is_static = method.variant == MethodVariant::StaticMethod;
+    let mut args = if is_static {
+        String::new()
+    } else {
+        "this".to_string()
+    };
+    for name in method.arg_names_without_self() {
+        if !args.is_empty() {
+            args.push_str(", ");
+        }
+        args.push_str(name);
+    }
+    let method_access = match method.access {
+        MethodAccess::Private => "private",
+        MethodAccess::Public => "public",
+        MethodAccess::Protected => "protected",
+    };
+    write!(
+        out,
+        r#"{doc_comments}
+    {method_access} {static_}{ret_type} {method_name}({args_with_types}){exception_spec} {{
+        {ret}{native_class}.{method_name}({args});
+    }}"#,
+        doc_comments = java_code::doc_comments_to_java_comments(
+            &method.doc_comments,
+            false,
+            &ctx.exported_items
+        ),
+        method_access = method_access,
+        static_ = if is_static { "static " } else { "" },
+        ret_type = ret_type,
+        method_name = method.short_name(),
+        args_with_types = args_with_types,
+        exception_spec = if may_return_error {
+            " throws Exception"
+        } else {
+            ""
+        },
+        ret = if ret_type == "void" { "" } else { "return " },
+        native_class = JAVA_RECORD_NATIVE_CLASS,
+        args = args,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    Ok(())
+}
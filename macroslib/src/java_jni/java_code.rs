@@ -1,7 +1,10 @@
 use bitflags::bitflags;
 
 use super::{JniForeignMethodSignature, NullAnnotation};
-use crate::WRITE_TO_MEM_FAILED_MSG;
+use crate::{
+    doc_comments::{DocComment, ExportedItems},
+    WRITE_TO_MEM_FAILED_MSG,
+};
 
 bitflags! {
     pub(in crate::java_jni) struct ArgsFormatFlags: u8 {
@@ -64,10 +67,12 @@ pub(in crate::java_jni) fn args_with_java_types<'a, NI: Iterator<Item = &'a str>
 pub(in crate::java_jni) fn doc_comments_to_java_comments(
     doc_comments: &[String],
     class_comments: bool,
+    exported_items: &ExportedItems,
 ) -> String {
     use std::fmt::Write;
+    let lines = DocComment::parse(doc_comments).to_javadoc(exported_items);
     let mut comments = String::new();
-    for (i, comment) in doc_comments.iter().enumerate() {
+    for (i, comment) in lines.iter().enumerate() {
         if i != 0 {
             comments.push('\n');
         }
@@ -81,9 +86,9 @@ pub(in crate::java_jni) fn doc_comments_to_java_comments(
             }
         }
 
-        write!(&mut comments, " * {}", comment.trim()).unwrap();
+        write!(&mut comments, " * {}", comment).unwrap();
 
-        if i == doc_comments.len() - 1 {
+        if i == lines.len() - 1 {
             comments.push('\n');
             if !class_comments {
                 comments.push_str("    ");
@@ -94,9 +99,9 @@ pub(in crate::java_jni) fn doc_comments_to_java_comments(
     comments
 }
 
-pub(in crate::java_jni) fn get_null_annotation_imports(
+pub(in crate::java_jni) fn get_null_annotation_imports<'a>(
     null_annotation_package: Option<&str>,
-    methods_sign: &[JniForeignMethodSignature],
+    methods_sign: impl IntoIterator<Item = &'a JniForeignMethodSignature>,
 ) -> String {
     if let Some(null_annotation_package) = null_annotation_package {
         let mut has_non_null = false;
@@ -148,6 +153,14 @@ pub(in crate::java_jni) fn filter_null_annotation(type_name: &str) -> String {
 }
 
 pub(in crate::java_jni) fn is_primitive_type(type_name: &str) -> bool {
+    // skip marker comment, like in `/*u32*/int`
+    let type_name = match type_name
+        .strip_prefix("/*")
+        .and_then(|x| x.find("*/").map(|pos| &x[pos + 2..]))
+    {
+        Some(tail) => tail.trim_start(),
+        None => type_name,
+    };
     match type_name {
         "void" | "boolean" | "byte" | "short" | "int" | "long" | "float" | "double" => true,
         _ => false,
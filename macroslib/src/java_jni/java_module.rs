@@ -0,0 +1,191 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use super::JavaContext;
+use crate::{
+    error::{invalid_src_id_span, DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+const MODULE_INFO_FILE: &str = "module-info.java";
+const NATIVE_LIBRARY_LOADER_CLASS: &str = "NativeLibraryLoader";
+
+/// Generate `module-info.java` in the root of source tree
+pub(in crate::java_jni) fn generate_module_info(
+    ctx: &mut JavaContext,
+    module_name: &str,
+) -> Result<()> {
+    let root = source_root(&ctx.cfg.output_dir, &ctx.cfg.package_name).ok_or_else(|| {
+        DiagnosticError::new2(
+            invalid_src_id_span(),
+            format!(
+                "Can not generate {} for module {}: output directory {} does not correspond to package {}",
+                MODULE_INFO_FILE,
+                module_name,
+                ctx.cfg.output_dir.display(),
+                ctx.cfg.package_name
+            ),
+        )
+    })?;
+    let src_path = root.join(MODULE_INFO_FILE);
+    let mut file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
+    writeln!(
+        file,
+        "// Automatically generated by flapigen\nmodule {} {{",
+        module_name
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    for required in &ctx.cfg.java_module_requires {
+        writeln!(file, "    requires {};", required).expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    writeln!(file, "    exports {};\n}}", ctx.cfg.package_name).expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::new2(
+            invalid_src_id_span(),
+            format!("write to {} failed: {}", src_path.display(), err),
+        )
+    })?;
+    Ok(())
+}
+
+/// Generate class to load native library, it should be compatible with Java 8,
+/// to be usable from base part of multi-release JAR
+pub(in crate::java_jni) fn generate_native_library_loader(
+    ctx: &mut JavaContext,
+    library_name: &str,
+) -> Result<()> {
+    let src_path = ctx
+        .cfg
+        .output_dir
+        .join(format!("{}.java", NATIVE_LIBRARY_LOADER_CLASS));
+    let mut file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
+    write!(
+        file,
+        r#"// Automatically generated by flapigen
+package {package};
+
+/**
+ * Loads native library with Rust code.
+ * At first {{@code System.loadLibrary}} is used, if library is not found
+ * in {{@code java.library.path}}, it is extracted from resource
+ * {{@code native/<os>-<arch>/<library file>}} near this class.
+ */
+public final class {class_name} {{
+    private static final String LIBRARY_NAME = "{library_name}";
+    private static boolean loaded;
+
+    private {class_name}() {{}}
+
+    /** Load native library, does nothing if it is already loaded */
+    public static synchronized void load() {{
+        if (loaded) {{
+            return;
+        }}
+        try {{
+            System.loadLibrary(LIBRARY_NAME);
+        }} catch (UnsatisfiedLinkError err) {{
+            loadFromResource(err);
+        }}
+        loaded = true;
+    }}
+
+    private static void loadFromResource(UnsatisfiedLinkError loadLibraryErr) {{
+        String fileName = System.mapLibraryName(LIBRARY_NAME);
+        String resourceName = "native/" + platform() + "/" + fileName;
+        // Class.getResourceAsStream can read resources of own named module,
+        // and respects versioned entries of multi-release JAR
+        java.io.InputStream in = {class_name}.class.getResourceAsStream(resourceName);
+        if (in == null) {{
+            throw loadLibraryErr;
+        }}
+        try {{
+            java.io.File tmpDir = java.nio.file.Files.createTempDirectory(LIBRARY_NAME).toFile();
+            tmpDir.deleteOnExit();
+            java.io.File libFile = new java.io.File(tmpDir, fileName);
+            libFile.deleteOnExit();
+            try {{
+                java.nio.file.Files.copy(in, libFile.toPath());
+            }} finally {{
+                in.close();
+            }}
+            System.load(libFile.getAbsolutePath());
+        }} catch (java.io.IOException ex) {{
+            UnsatisfiedLinkError err = new UnsatisfiedLinkError("Can not extract " + resourceName + ": " + ex);
+            err.initCause(ex);
+            throw err;
+        }}
+    }}
+
+    /** Platform name in the form of Rust target, like linux-x86_64 */
+    private static String platform() {{
+        String os = System.getProperty("os.name").toLowerCase(java.util.Locale.ROOT);
+        if (os.startsWith("windows")) {{
+            os = "windows";
+        }} else if (os.startsWith("mac") || os.startsWith("darwin")) {{
+            os = "macos";
+        }} else {{
+            os = os.replace(" ", "");
+        }}
+        String arch = System.getProperty("os.arch").toLowerCase(java.util.Locale.ROOT);
+        if (arch.equals("amd64") || arch.equals("x86_64")) {{
+            arch = "x86_64";
+        }} else if (arch.equals("arm64") || arch.equals("aarch64")) {{
+            arch = "aarch64";
+        }} else if (arch.matches("i[3-6]86|x86")) {{
+            arch = "x86";
+        }}
+        return os + "-" + arch;
+    }}
+}}
+"#,
+        package = ctx.cfg.package_name,
+        class_name = NATIVE_LIBRARY_LOADER_CLASS,
+        library_name = library_name,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
+    file.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::new2(
+            invalid_src_id_span(),
+            format!("write to {} failed: {}", src_path.display(), err),
+        )
+    })?;
+    Ok(())
+}
+
+/// Directory that contains directories of package,
+/// for `output_dir` "src/com/example" and package "com.example" it is "src"
+fn source_root(output_dir: &Path, package_name: &str) -> Option<PathBuf> {
+    let mut root = output_dir.to_path_buf();
+    for part in package_name.split('.').rev() {
+        if root.file_name()? != part {
+            return None;
+        }
+        root.pop();
+    }
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_root() {
+        assert_eq!(
+            Some(Path::new("/tmp/src").to_path_buf()),
+            source_root(Path::new("/tmp/src/com/example"), "com.example")
+        );
+        assert_eq!(
+            Some(Path::new("").to_path_buf()),
+            source_root(Path::new("com/example"), "com.example")
+        );
+        assert_eq!(
+            None,
+            source_root(Path::new("/tmp/src/example"), "com.example")
+        );
+        assert_eq!(None, source_root(Path::new("example"), "com.example"));
+    }
+}
@@ -174,6 +174,14 @@ pub trait SwigForeignCLikeEnum {
     fn from_jint(_: jint) -> Self;
 }
 
+/// Value of flags created by `bitflags!` is passed as its bits
+#[allow(dead_code)]
+pub trait SwigForeignBitflags: Sized {
+    /// `None` if there are unknown bits
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
+
 #[allow(dead_code)]
 pub struct JavaString {
     string: jstring,
@@ -225,6 +233,37 @@ foreign_typemap!(
     };
 );
 
+/// Number of local references that callback call may create without
+/// reallocation of local frame
+const SWIG_JNI_CALLBACK_FRAME_CAPACITY: jint = 16;
+
+/// Frame of JNI local references for callback call, all local references
+/// created after `JniLocalFrame::new` are deleted when frame is dropped.
+/// Without it local references created during callback call from Rust thread
+/// live until thread detach, and JVM limits number of them (512 on Android).
+struct JniLocalFrame {
+    env: *mut JNIEnv,
+}
+
+impl JniLocalFrame {
+    /// Returns `None` if `PushLocalFrame` failed, in this case JVM
+    /// already has pending `OutOfMemoryError`
+    fn new(env: *mut JNIEnv, capacity: jint) -> Option<JniLocalFrame> {
+        let res = unsafe { (**env).PushLocalFrame.unwrap()(env, capacity) };
+        if res != 0 {
+            log::error!("PushLocalFrame({}) failed: {}", capacity, res);
+            return None;
+        }
+        Some(JniLocalFrame { env })
+    }
+}
+
+impl Drop for JniLocalFrame {
+    fn drop(&mut self) {
+        unsafe { (**self.env).PopLocalFrame.unwrap()(self.env, ::std::ptr::null_mut()) };
+    }
+}
+
 #[allow(dead_code)]
 struct JavaCallback {
     java_vm: *mut JavaVM,
@@ -241,11 +280,16 @@ struct JniEnvHolder<'a> {
     env: Option<*mut JNIEnv>,
     callback: &'a JavaCallback,
     need_detach: bool,
+    /// Local references created during callback call,
+    /// without it they live until thread detach or return to Java
+    local_frame: Option<JniLocalFrame>,
 }
 
 #[allow(dead_code)]
 impl<'a> Drop for JniEnvHolder<'a> {
     fn drop(&mut self) {
+        // frame should be popped while thread is still attached
+        drop(self.local_frame.take());
         if self.need_detach {
             let res = unsafe {
                 (**self.callback.java_vm).DetachCurrentThread.unwrap()(self.callback.java_vm)
@@ -257,6 +301,90 @@ impl<'a> Drop for JniEnvHolder<'a> {
     }
 }
 
+/// How to attach threads created by Rust to JVM,
+/// see `JavaConfig::use_thread_attach_policy`
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
+enum JniThreadAttachPolicy {
+    DetachAfterCall,
+    DetachOnThreadExit,
+    Daemon,
+}
+
+const SWIG_JNI_THREAD_ATTACH_POLICY: JniThreadAttachPolicy = JniThreadAttachPolicy::DetachAfterCall;
+/// Name of attached thread, if `None` name of Rust thread is used
+const SWIG_JNI_ATTACHED_THREAD_NAME: Option<&str> = None;
+/// Name of `java.lang.ThreadGroup` for attached threads
+const SWIG_JNI_ATTACHED_THREAD_GROUP: Option<&str> = None;
+
+/// Global reference to `java.lang.ThreadGroup` for attached threads
+static mut SWIG_JNI_THREAD_GROUP: jobject = ::std::ptr::null_mut();
+static SWIG_JNI_THREAD_GROUP_INIT: ::std::sync::Once = ::std::sync::Once::new();
+
+#[allow(dead_code)]
+fn jni_init_thread_group(env: *mut JNIEnv) {
+    let group_name = match SWIG_JNI_ATTACHED_THREAD_GROUP {
+        Some(x) => x,
+        None => return,
+    };
+    SWIG_JNI_THREAD_GROUP_INIT.call_once(|| {
+        swig_jni_find_class!(JAVA_LANG_THREAD_GROUP, "java/lang/ThreadGroup");
+        let ctor: jmethodID = swig_jni_get_method_id!(
+            JAVA_LANG_THREAD_GROUP_INIT,
+            JAVA_LANG_THREAD_GROUP,
+            "<init>",
+            "(Ljava/lang/String;)V"
+        );
+        let c_group_name = ::std::ffi::CString::new(group_name).unwrap();
+        unsafe {
+            let jname = (**env).NewStringUTF.unwrap()(env, c_group_name.as_ptr());
+            assert!(
+                !jname.is_null(),
+                "jni_init_thread_group: NewStringUTF failed"
+            );
+            let group = (**env).NewObject.unwrap()(env, JAVA_LANG_THREAD_GROUP, ctor, jname);
+            (**env).DeleteLocalRef.unwrap()(env, jname);
+            if group.is_null() || (**env).ExceptionCheck.unwrap()(env) != 0 {
+                log::error!(
+                    "jni_init_thread_group: can not create ThreadGroup {}",
+                    group_name
+                );
+                (**env).ExceptionClear.unwrap()(env);
+                return;
+            }
+            SWIG_JNI_THREAD_GROUP = (**env).NewGlobalRef.unwrap()(env, group);
+            (**env).DeleteLocalRef.unwrap()(env, group);
+        }
+    });
+}
+
+/// The same as `JavaVMAttachArgs`
+#[repr(C)]
+struct JniAttachArgs {
+    version: jint,
+    name: *const ::std::ffi::c_char,
+    group: jobject,
+}
+
+/// Detach thread from JVM on thread exit
+struct JniThreadDetachGuard {
+    java_vm: *mut JavaVM,
+}
+
+impl Drop for JniThreadDetachGuard {
+    fn drop(&mut self) {
+        let res = unsafe { (**self.java_vm).DetachCurrentThread.unwrap()(self.java_vm) };
+        if res != 0 {
+            log::error!("JniThreadDetachGuard: DetachCurrentThread failed: {}", res);
+        }
+    }
+}
+
+thread_local! {
+    static SWIG_JNI_THREAD_DETACH_GUARD: ::std::cell::RefCell<Option<JniThreadDetachGuard>> =
+        const { ::std::cell::RefCell::new(None) };
+}
+
 #[allow(dead_code)]
 impl JavaCallback {
     fn new(obj: jobject, env: *mut JNIEnv) -> JavaCallback {
@@ -265,6 +393,7 @@ impl JavaCallback {
         assert_eq!(0, ret, "GetJavaVm failed");
         let global_obj = unsafe { (**env).NewGlobalRef.unwrap()(env, obj) };
         assert!(!global_obj.is_null());
+        jni_init_thread_group(env);
         JavaCallback {
             java_vm,
             this: global_obj,
@@ -284,10 +413,12 @@ impl JavaCallback {
             )
         };
         if res == (JNI_OK as jint) {
+            let local_frame = JniLocalFrame::new(env, SWIG_JNI_CALLBACK_FRAME_CAPACITY);
             return JniEnvHolder {
-                env: Some(env),
+                env: local_frame.as_ref().map(|_| env),
                 callback: self,
                 need_detach: false,
+                local_frame,
             };
         }
         if res != (JNI_EDETACHED as jint) {
@@ -296,7 +427,8 @@ impl JavaCallback {
 
         // AttachCurrentThread for Android and other JNI
         // has different signatures, second argument has type
-        // *mut *mut JNIEnv instead of *mut *mut c_void
+        // *mut *mut JNIEnv instead of *mut *mut c_void,
+        // third argument has type *mut JavaVMAttachArgs instead of *mut c_void
         trait ConvertPtr<T> {
             fn convert_ptr(self) -> T;
         }
@@ -313,30 +445,71 @@ impl JavaCallback {
             }
         }
 
+        impl<T> ConvertPtr<*mut T> for *mut JniAttachArgs {
+            fn convert_ptr(self) -> *mut T {
+                self as *mut T
+            }
+        }
+
+        let current_thread = ::std::thread::current();
+        let thread_name = SWIG_JNI_ATTACHED_THREAD_NAME
+            .or_else(|| current_thread.name())
+            .and_then(|name| ::std::ffi::CString::new(name).ok());
+        let mut attach_args = JniAttachArgs {
+            version: SWIG_JNI_VERSION,
+            name: thread_name
+                .as_ref()
+                .map(|x| x.as_ptr())
+                .unwrap_or(::std::ptr::null()),
+            group: unsafe { SWIG_JNI_THREAD_GROUP },
+        };
+
         let res = unsafe {
-            (**self.java_vm).AttachCurrentThread.unwrap()(
-                self.java_vm,
-                (&mut env as *mut *mut JNIEnv).convert_ptr(),
-                ::std::ptr::null_mut(),
-            )
+            if SWIG_JNI_THREAD_ATTACH_POLICY == JniThreadAttachPolicy::Daemon {
+                (**self.java_vm).AttachCurrentThreadAsDaemon.unwrap()(
+                    self.java_vm,
+                    (&mut env as *mut *mut JNIEnv).convert_ptr(),
+                    (&mut attach_args as *mut JniAttachArgs).convert_ptr(),
+                )
+            } else {
+                (**self.java_vm).AttachCurrentThread.unwrap()(
+                    self.java_vm,
+                    (&mut env as *mut *mut JNIEnv).convert_ptr(),
+                    (&mut attach_args as *mut JniAttachArgs).convert_ptr(),
+                )
+            }
         };
         if res != 0 {
             log::error!(
                 "JavaCallback::get_jnienv: AttachCurrentThread failed: {}",
                 res
             );
-            JniEnvHolder {
+            return JniEnvHolder {
                 env: None,
                 callback: self,
                 need_detach: false,
+                local_frame: None,
+            };
+        }
+        assert!(!env.is_null());
+        let need_detach = match SWIG_JNI_THREAD_ATTACH_POLICY {
+            JniThreadAttachPolicy::DetachAfterCall => true,
+            JniThreadAttachPolicy::DetachOnThreadExit | JniThreadAttachPolicy::Daemon => {
+                let java_vm = self.java_vm;
+                // if thread local storage already destroyed, then detach right after call
+                SWIG_JNI_THREAD_DETACH_GUARD
+                    .try_with(|guard| {
+                        *guard.borrow_mut() = Some(JniThreadDetachGuard { java_vm });
+                    })
+                    .is_err()
             }
-        } else {
-            assert!(!env.is_null());
-            JniEnvHolder {
-                env: Some(env),
-                callback: self,
-                need_detach: true,
-            }
+        };
+        let local_frame = JniLocalFrame::new(env, SWIG_JNI_CALLBACK_FRAME_CAPACITY);
+        JniEnvHolder {
+            env: local_frame.as_ref().map(|_| env),
+            callback: self,
+            need_detach,
+            local_frame,
         }
     }
 }
@@ -373,6 +546,226 @@ fn jni_throw_exception(env: *mut JNIEnv, message: &str) {
     jni_throw(env, exception_class, message)
 }
 
+#[allow(dead_code)]
+fn jni_throw_arithmetic_exception(env: *mut JNIEnv, message: &str) {
+    let exception_class = swig_jni_find_class!(
+        JAVA_LANG_ARITHMETIC_EXCEPTION,
+        "java/lang/ArithmeticException"
+    );
+    jni_throw(env, exception_class, message)
+}
+
+#[allow(dead_code)]
+fn jni_throw_null_pointer_exception(env: *mut JNIEnv, message: &str) {
+    let exception_class = swig_jni_find_class!(
+        JAVA_LANG_NULL_POINTER_EXCEPTION,
+        "java/lang/NullPointerException"
+    );
+    jni_throw(env, exception_class, message)
+}
+
+/// Java exception thrown by callback method
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct JavaException {
+    /// Full name of exception's class, like `java.lang.IllegalStateException`
+    pub class_name: String,
+    /// Result of `Throwable.getMessage()`
+    pub message: Option<String>,
+    /// Stack trace, one frame per line
+    pub stack_trace: String,
+}
+
+#[allow(dead_code)]
+impl JavaException {
+    /// Take pending Java exception (if any) and clear it
+    fn take_pending(env: *mut JNIEnv) -> Option<JavaException> {
+        let throwable: jthrowable = unsafe {
+            if (**env).ExceptionCheck.unwrap()(env) == 0 {
+                return None;
+            }
+            let throwable = (**env).ExceptionOccurred.unwrap()(env);
+            (**env).ExceptionClear.unwrap()(env);
+            throwable
+        };
+        assert!(!throwable.is_null());
+        let ex = JavaException::from_throwable(env, throwable);
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, throwable) };
+        Some(ex)
+    }
+
+    fn from_throwable(env: *mut JNIEnv, throwable: jthrowable) -> JavaException {
+        swig_jni_find_class!(JAVA_LANG_THROWABLE, "java/lang/Throwable");
+        let get_message_id: jmethodID = swig_jni_get_method_id!(
+            JAVA_LANG_THROWABLE_GET_MESSAGE,
+            JAVA_LANG_THROWABLE,
+            "getMessage",
+            "()Ljava/lang/String;"
+        );
+        let get_stack_trace_id: jmethodID = swig_jni_get_method_id!(
+            JAVA_LANG_THROWABLE_GET_STACK_TRACE,
+            JAVA_LANG_THROWABLE,
+            "getStackTrace",
+            "()[Ljava/lang/StackTraceElement;"
+        );
+        swig_jni_find_class!(JAVA_LANG_CLASS, "java/lang/Class");
+        let get_name_id: jmethodID = swig_jni_get_method_id!(
+            JAVA_LANG_CLASS_GET_NAME,
+            JAVA_LANG_CLASS,
+            "getName",
+            "()Ljava/lang/String;"
+        );
+        swig_jni_find_class!(JAVA_LANG_STACK_TRACE_ELEMENT, "java/lang/StackTraceElement");
+        let to_string_id: jmethodID = swig_jni_get_method_id!(
+            JAVA_LANG_STACK_TRACE_ELEMENT_TO_STRING,
+            JAVA_LANG_STACK_TRACE_ELEMENT,
+            "toString",
+            "()Ljava/lang/String;"
+        );
+
+        unsafe {
+            let class = (**env).GetObjectClass.unwrap()(env, throwable);
+            let class_name = call_string_method(env, class, get_name_id).unwrap_or_default();
+            (**env).DeleteLocalRef.unwrap()(env, class);
+
+            let message = call_string_method(env, throwable, get_message_id);
+
+            let mut stack_trace = String::new();
+            let frames: jobjectArray =
+                (**env).CallObjectMethod.unwrap()(env, throwable, get_stack_trace_id);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                (**env).ExceptionClear.unwrap()(env);
+            } else if !frames.is_null() {
+                let n = (**env).GetArrayLength.unwrap()(env, frames);
+                for i in 0..n {
+                    let frame = (**env).GetObjectArrayElement.unwrap()(env, frames, i);
+                    if let Some(frame_str) = call_string_method(env, frame, to_string_id) {
+                        stack_trace.push_str("\tat ");
+                        stack_trace.push_str(&frame_str);
+                        stack_trace.push('\n');
+                    }
+                    (**env).DeleteLocalRef.unwrap()(env, frame);
+                }
+                (**env).DeleteLocalRef.unwrap()(env, frames);
+            }
+
+            JavaException {
+                class_name,
+                message,
+                stack_trace,
+            }
+        }
+    }
+}
+
+impl ::std::fmt::Display for JavaException {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match self.message {
+            Some(ref msg) => write!(f, "{}: {}", self.class_name, msg),
+            None => write!(f, "{}", self.class_name),
+        }
+    }
+}
+
+impl ::std::error::Error for JavaException {}
+
+/// Call method `() -> String`, exception (if any) is cleared
+#[allow(dead_code)]
+unsafe fn call_string_method(
+    env: *mut JNIEnv,
+    obj: jobject,
+    method_id: jmethodID,
+) -> Option<String> {
+    let jstr: jstring = (**env).CallObjectMethod.unwrap()(env, obj, method_id);
+    if (**env).ExceptionCheck.unwrap()(env) != 0 {
+        (**env).ExceptionClear.unwrap()(env);
+        return None;
+    }
+    let ret = if !jstr.is_null() {
+        let s = JavaString::new(env, jstr).to_str().to_string();
+        (**env).DeleteLocalRef.unwrap()(env, jstr);
+        Some(s)
+    } else {
+        None
+    };
+    ret
+}
+
+thread_local! {
+    /// Exception thrown by callback, it would be rethrown on return to Java
+    static SWIG_JNI_STASHED_EXCEPTION: ::std::cell::Cell<jthrowable> =
+        const { ::std::cell::Cell::new(::std::ptr::null_mut()) };
+    /// Number of `JniRethrowGuard` alive in current thread
+    static SWIG_JNI_RETHROW_GUARDS: ::std::cell::Cell<usize> = const { ::std::cell::Cell::new(0) };
+}
+
+/// Remember pending exception and clear it,
+/// so it is possible to continue calling JNI functions.
+/// If there is no native method called from Java in current thread,
+/// for example thread is created by Rust, there is nobody to rethrow exception,
+/// so it is logged and cleared
+#[allow(dead_code)]
+fn jni_stash_pending_exception(env: *mut JNIEnv) {
+    unsafe {
+        if SWIG_JNI_RETHROW_GUARDS.with(|x| x.get()) == 0 {
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                log::error!("jni_stash_pending_exception: no native method to rethrow exception");
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+            }
+            return;
+        }
+        let throwable = (**env).ExceptionOccurred.unwrap()(env);
+        if throwable.is_null() {
+            return;
+        }
+        (**env).ExceptionClear.unwrap()(env);
+        let global_throwable = (**env).NewGlobalRef.unwrap()(env, throwable);
+        (**env).DeleteLocalRef.unwrap()(env, throwable);
+        let prev = SWIG_JNI_STASHED_EXCEPTION.with(|x| x.replace(global_throwable));
+        if !prev.is_null() {
+            log::error!("jni_stash_pending_exception: previous exception lost");
+            (**env).DeleteGlobalRef.unwrap()(env, prev);
+        }
+    }
+}
+
+/// Throw exception remembered by `jni_stash_pending_exception`
+/// during the return from native method
+#[allow(dead_code)]
+struct JniRethrowGuard {
+    env: *mut JNIEnv,
+}
+
+#[allow(dead_code)]
+impl JniRethrowGuard {
+    fn new(env: *mut JNIEnv) -> JniRethrowGuard {
+        SWIG_JNI_RETHROW_GUARDS.with(|x| x.set(x.get() + 1));
+        JniRethrowGuard { env }
+    }
+}
+
+impl Drop for JniRethrowGuard {
+    fn drop(&mut self) {
+        SWIG_JNI_RETHROW_GUARDS.with(|x| x.set(x.get() - 1));
+        let throwable = SWIG_JNI_STASHED_EXCEPTION.with(|x| x.replace(::std::ptr::null_mut()));
+        if throwable.is_null() {
+            return;
+        }
+        let env = self.env;
+        unsafe {
+            if (**env).ExceptionCheck.unwrap()(env) == 0 {
+                if (**env).Throw.unwrap()(env, throwable) != 0 {
+                    log::error!("JniRethrowGuard: Throw failed");
+                }
+            } else {
+                log::error!("JniRethrowGuard: exception already pending, callback exception lost");
+            }
+            (**env).DeleteGlobalRef.unwrap()(env, throwable);
+        }
+    }
+}
+
 #[allow(dead_code)]
 fn object_to_jobject<T: SwigForeignClass>(env: *mut JNIEnv, obj: T) -> jobject {
     let jcls = <T>::jni_class();
@@ -393,14 +786,6 @@ fn object_to_jobject<T: SwigForeignClass>(env: *mut JNIEnv, obj: T) -> jobject {
     jobj
 }
 
-foreign_typemap!(
-    ($p:r_type) <T: SwigForeignClass> Vec<T> => internal_aliases::JForeignObjectsArray<T> {
-        $out = vec_of_objects_to_jobject_array(env, $p);
-    };
-    ($p:f_type, option = "NoNullAnnotations") => "swig_f_type!(T) []";
-    ($p:f_type, option = "NullAnnotations") => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
-);
-
 #[allow(dead_code)]
 fn jobject_array_to_vec_of_objects<T: SwigForeignClass + Clone>(
     env: *mut JNIEnv,
@@ -428,50 +813,43 @@ fn jobject_array_to_vec_of_objects<T: SwigForeignClass + Clone>(
 
     result
 }
-//ANCHOR: foreign_typemap_generic_example
-foreign_typemap!(
-    ($p:r_type) <T: SwigForeignClass + Clone> Vec<T> <= internal_aliases::JForeignObjectsArray<T> {
-        $out = jobject_array_to_vec_of_objects(env, $p);
-    };
-    ($p:f_type, option = "NoNullAnnotations") <= "swig_f_type!(T) []";
-    ($p:f_type, option = "NullAnnotations")
-                  <= "@NonNull swig_f_type!(T, NoNullAnnotations) []";
-);
-//ANCHOR_END: foreign_typemap_generic_example
-
+/// Create Java array from local references,
+/// local references are deleted after insertion into array
 #[allow(dead_code)]
-fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
+fn jobjects_to_jobject_array<I: ExactSizeIterator<Item = jobject>>(
     env: *mut JNIEnv,
-    mut arr: Vec<T>,
-) -> internal_aliases::JForeignObjectsArray<T> {
-    let jcls: jclass = <T>::jni_class();
-    assert!(!jcls.is_null());
-    let arr_len = <jsize as ::std::convert::TryFrom<usize>>::try_from(arr.len())
+    elem_class: jclass,
+    elems: I,
+) -> jobjectArray {
+    let len = <jsize as ::std::convert::TryFrom<usize>>::try_from(elems.len())
         .expect("invalid usize, in usize => to jsize conversation");
     let obj_arr: jobjectArray =
-        unsafe { (**env).NewObjectArray.unwrap()(env, arr_len, jcls, ::std::ptr::null_mut()) };
+        unsafe { (**env).NewObjectArray.unwrap()(env, len, elem_class, ::std::ptr::null_mut()) };
     assert!(!obj_arr.is_null());
-
-    let field_id = <T>::jni_class_pointer_field();
-    assert!(!field_id.is_null());
-
-    for (i, r_obj) in arr.drain(..).enumerate() {
-        let jobj: jobject = unsafe { (**env).AllocObject.unwrap()(env, jcls) };
-        assert!(!jobj.is_null());
-
-        let r_obj: jlong = <T>::box_object(r_obj);
+    for (i, elem) in elems.enumerate() {
         unsafe {
-            (**env).SetLongField.unwrap()(env, jobj, field_id, r_obj);
-            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                panic!("Can not mNativeObj field: catch exception");
-            }
-            (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
+            (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, elem);
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("SetObjectArrayElement({}) failed", i);
             }
-            (**env).DeleteLocalRef.unwrap()(env, jobj);
+            (**env).DeleteLocalRef.unwrap()(env, elem);
         }
     }
+    obj_arr
+}
+
+#[allow(dead_code)]
+fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
+    env: *mut JNIEnv,
+    arr: Vec<T>,
+) -> internal_aliases::JForeignObjectsArray<T> {
+    let jcls: jclass = <T>::jni_class();
+    assert!(!jcls.is_null());
+    let obj_arr = jobjects_to_jobject_array(
+        env,
+        jcls,
+        arr.into_iter().map(|r_obj| object_to_jobject(env, r_obj)),
+    );
     internal_aliases::JForeignObjectsArray {
         inner: obj_arr,
         _marker: ::std::marker::PhantomData,
@@ -499,6 +877,12 @@ impl JniInvalidValue for () {
     fn jni_invalid_value() {}
 }
 
+impl<T> JniInvalidValue for Option<T> {
+    fn jni_invalid_value() -> Self {
+        None
+    }
+}
+
 impl<T: SwigForeignClass> JniInvalidValue for internal_aliases::JForeignObjectsArray<T> {
     fn jni_invalid_value() -> Self {
         Self {
@@ -519,7 +903,7 @@ macro_rules! impl_jni_jni_invalid_value {
 }
 
 impl_jni_jni_invalid_value! {
-    jbyte jshort jint jlong jfloat jdouble
+    jboolean jchar jbyte jshort jint jlong jfloat jdouble
 }
 
 foreign_typemap!(
@@ -567,20 +951,6 @@ foreign_typemap!(
     ($p:f_type) <= "boolean";
 );
 
-foreign_typemap!(
-    ($p:r_type) SystemTime => jlong {
-        let since_unix_epoch = $p
-            .duration_since(::std::time::UNIX_EPOCH)
-            .expect("SystemTime to Unix time conv. error");
-        $out = <i64 as ::std::convert::TryFrom<u64>>::try_from(
-            since_unix_epoch.as_secs() * 1_000 + u64::from(since_unix_epoch.subsec_millis()),
-        )
-        .expect("SystemTime: milleseconds u64 to i64 convert error");
-    };
-    ($p:f_type, option = "NoNullAnnotations") => "java.util.Date" "$out = new java.util.Date($p);";
-    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.Date" "$out = new java.util.Date($p);";
-);
-
 foreign_typemap!(
     ($p:r_type) jbyte => i8 {
         $out = $p;
@@ -590,18 +960,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u8 => jshort {
-        $out = jshort::from($p);
-    };
-);
-foreign_typemap!(
-    ($p:r_type) u8 <= jshort {
-        $out = <u8 as ::std::convert::TryFrom<jshort>>::try_from($p)
-            .expect("invalid jshort, in jshort => u8 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) i16 => jshort {
         $out = $p;
@@ -611,16 +969,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u16 => jint {
-        $out = jint::from($p);
-    };
-    ($p:r_type) u16 <= jint {
-        $out = <u16 as ::std::convert::TryFrom<jint>>::try_from($p)
-            .expect("invalid jint, in jint => u16 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) jint => i32 {
         $out = $p;
@@ -630,16 +978,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u32 => jlong {
-        $out = jlong::from($p);
-    };
-    ($p:r_type) u32 <= jlong {
-        $out = <u32 as ::std::convert::TryFrom<jlong>>::try_from($p)
-            .expect("invalid jlong, in jlong => u32 conversation");
-    };
-);
-
 foreign_typemap!(
     ($p:r_type) i64 => jlong {
         $out = $p;
@@ -649,17 +987,6 @@ foreign_typemap!(
     };
 );
 
-foreign_typemap!(
-    ($p:r_type) u64 => jlong {
-        $out = <jlong as ::std::convert::TryFrom<u64>>::try_from($p)
-            .expect("invalid u64, in u64 => jlong conversation");
-    };
-    ($p:r_type) u64 <= jlong {
-        $out = <u64 as ::std::convert::TryFrom<jlong>>::try_from($p)
-            .expect("invalid jlong, in jlong => u64 conversation");
-    };
-);
-
 #[allow(dead_code)]
 pub fn u64_to_jlong_checked(x: u64) -> jlong {
     <jlong as ::std::convert::TryFrom<u64>>::try_from(x)
@@ -731,36 +1058,39 @@ foreign_typemap!(
 );
 
 #[allow(dead_code)]
-fn vec_string_to_jobject_array(mut arr: Vec<String>, env: *mut JNIEnv) -> jobjectArray {
+fn vec_string_to_jobject_array(arr: Vec<String>, env: *mut JNIEnv) -> jobjectArray {
     let jcls: jclass = swig_jni_find_class!(JAVA_LANG_STRING, "java/lang/String");
     assert!(!jcls.is_null());
-    let obj_arr: jobjectArray = unsafe {
-        (**env).NewObjectArray.unwrap()(env, arr.len() as jsize, jcls, ::std::ptr::null_mut())
-    };
-    assert!(!obj_arr.is_null());
-    for (i, r_str) in arr.drain(..).enumerate() {
-        let jstr: jstring = from_std_string_jstring(r_str, env);
-        assert!(!jstr.is_null());
+    jobjects_to_jobject_array(
+        env,
+        jcls,
+        arr.into_iter().map(|r_str| {
+            let jstr: jstring = from_std_string_jstring(r_str, env);
+            assert!(!jstr.is_null());
+            jstr
+        }),
+    )
+}
 
-        unsafe {
-            (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jstr);
-            if (**env).ExceptionCheck.unwrap()(env) != 0 {
-                panic!("SetObjectArrayElement({}) failed", i);
-            }
-            (**env).DeleteLocalRef.unwrap()(env, jstr);
+#[allow(dead_code)]
+fn jobject_array_to_vec_of_strings(env: *mut JNIEnv, arr: jobjectArray) -> Vec<String> {
+    let length = unsafe { (**env).GetArrayLength.unwrap()(env, arr) };
+    let len = <usize as ::std::convert::TryFrom<jsize>>::try_from(length)
+        .expect("invalid jsize, in jsize => usize conversation");
+    let mut result = Vec::with_capacity(len);
+    for i in 0..length {
+        let jstr: jstring = unsafe { (**env).GetObjectArrayElement.unwrap()(env, arr, i) };
+        if unsafe { (**env).ExceptionCheck.unwrap()(env) } != 0 {
+            panic!("Failed to retrieve element {} from this `jobjectArray'", i);
         }
+        let s = JavaString::new(env, jstr);
+        result.push(s.to_str().to_string());
+        drop(s);
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, jstr) };
     }
-    obj_arr
+    result
 }
 
-foreign_typemap!(
-    ($p:r_type) Vec<String> => internal_aliases::JStringObjectsArray {
-        $out = vec_string_to_jobject_array($p, env);
-    };
-    ($p:f_type, option = "NoNullAnnotations") => "java.lang.String []";
-    ($p:f_type, option = "NullAnnotations") => "@NonNull java.lang.String []";
-);
-
 macro_rules! define_array_handling_code {
     ($([jni_arr_type = $jni_arr_type:ident,
         rust_arr_wrapper = $rust_arr_wrapper:ident,
@@ -1704,3 +2034,21 @@ foreign_typemap!(
         $out = <swig_subst_type!(T)>::from_jint($p);
     };
 );
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignBitflags> T => jint {
+        $out = $p.as_i32();
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignBitflags> T <= jint {
+        $out = match <swig_subst_type!(T)>::from_i32($p) {
+            Some(x) => x,
+            None => {
+                jni_throw_exception(env, &format!("Unknown bits for flags: {}", $p));
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+);
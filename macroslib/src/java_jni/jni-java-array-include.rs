@@ -0,0 +1,52 @@
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass> Vec<T> => internal_aliases::JForeignObjectsArray<T> {
+        $out = vec_of_objects_to_jobject_array(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+);
+
+//ANCHOR: foreign_typemap_generic_example
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> Vec<T> <= internal_aliases::JForeignObjectsArray<T> {
+        $out = jobject_array_to_vec_of_objects(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations")
+                  <= "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+);
+//ANCHOR_END: foreign_typemap_generic_example
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> &[T] => internal_aliases::JForeignObjectsArray<T> {
+        $out = vec_of_objects_to_jobject_array(env, $p.to_vec());
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+);
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> &[T] <= internal_aliases::JForeignObjectsArray<T> {
+        let tmp_vec: Vec<swig_subst_type!(T)> = jobject_array_to_vec_of_objects(env, $p);
+        $out = tmp_vec.as_slice();
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "swig_f_type!(T) []";
+    ($p:f_type, option = "NullAnnotations")
+                  <= "@NonNull swig_f_type!(T, NoNullAnnotations) []";
+);
+
+foreign_typemap!(
+    ($p:r_type) Vec<String> => internal_aliases::JStringObjectsArray {
+        $out = vec_string_to_jobject_array($p, env);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.lang.String []";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.lang.String []";
+);
+
+foreign_typemap!(
+    ($p:r_type) Vec<String> <= internal_aliases::JStringObjectsArray {
+        $out = jobject_array_to_vec_of_strings(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.lang.String []";
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.lang.String []";
+);
@@ -0,0 +1,613 @@
+#[allow(dead_code)]
+mod java_time_aliases {
+    use super::*;
+    pub type JInstant = jobject;
+    pub type JDuration = jobject;
+    pub type JLocalDate = jobject;
+    pub type JLocalDateTime = jobject;
+    /// Java object that was checked by Java code before conversion to Rust
+    pub type JCheckedTimeObject = jobject;
+}
+
+foreign_typemap!(
+    (r_type) java_time_aliases::JCheckedTimeObject;
+    (f_type) "/*java.time*/Object";
+);
+
+/// Number of days from 0001-01-01 (CE) to 1970-01-01
+#[allow(dead_code)]
+const SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE: i64 = 719_163;
+#[allow(dead_code)]
+const SWIG_JNI_NANOS_PER_SEC: u32 = 1_000_000_000;
+#[allow(dead_code)]
+const SWIG_JNI_SECS_PER_DAY: i64 = 86_400;
+
+#[allow(dead_code)]
+fn jni_throw_date_time_exception(env: *mut JNIEnv, message: &str) {
+    let exception_class = swig_jni_find_class!(
+        JAVA_TIME_DATE_TIME_EXCEPTION,
+        "java/time/DateTimeException"
+    );
+    jni_throw(env, exception_class, message)
+}
+
+/// Throw `NullPointerException` if `x` is null, return true if it is not null
+#[allow(dead_code)]
+fn jni_check_time_object_not_null(env: *mut JNIEnv, x: jobject, class_name: &str) -> bool {
+    if !x.is_null() {
+        return true;
+    }
+    jni_throw_null_pointer_exception(env, &format!("{} should not be null", class_name));
+    false
+}
+
+/// Create `java.time.Instant`, in case of `DateTimeException`
+/// return null and leave exception pending
+#[allow(dead_code)]
+fn to_java_time_instant(env: *mut JNIEnv, secs: i64, nanos: u32) -> java_time_aliases::JInstant {
+    let class: jclass = swig_jni_find_class!(JAVA_TIME_INSTANT, "java/time/Instant");
+    assert!(!class.is_null());
+    let of_epoch_second_m: jmethodID = swig_jni_get_static_method_id!(
+        JAVA_TIME_INSTANT_OF_EPOCH_SECOND,
+        JAVA_TIME_INSTANT,
+        "ofEpochSecond",
+        "(JJ)Ljava/time/Instant;"
+    );
+    assert!(!of_epoch_second_m.is_null());
+    unsafe {
+        let ret = (**env).CallStaticObjectMethod.unwrap()(
+            env,
+            class,
+            of_epoch_second_m,
+            secs,
+            jlong::from(nanos),
+        );
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return ::std::ptr::null_mut();
+        }
+        assert!(!ret.is_null());
+        ret
+    }
+}
+
+/// Seconds since Unix epoch and nanoseconds of second of `java.time.Instant`,
+/// in case of exception return `None` and leave exception pending
+#[allow(dead_code)]
+fn from_java_time_instant_to_rust(
+    env: *mut JNIEnv,
+    x: java_time_aliases::JInstant,
+) -> Option<(i64, u32)> {
+    if !jni_check_time_object_not_null(env, x, "java.time.Instant") {
+        return None;
+    }
+    let get_epoch_second_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_INSTANT_GET_EPOCH_SECOND,
+        JAVA_TIME_INSTANT,
+        "getEpochSecond",
+        "()J"
+    );
+    assert!(!get_epoch_second_m.is_null());
+    let get_nano_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_INSTANT_GET_NANO,
+        JAVA_TIME_INSTANT,
+        "getNano",
+        "()I"
+    );
+    assert!(!get_nano_m.is_null());
+    unsafe {
+        let secs = (**env).CallLongMethod.unwrap()(env, x, get_epoch_second_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        let nanos = (**env).CallIntMethod.unwrap()(env, x, get_nano_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        Some((secs, nanos as u32))
+    }
+}
+
+/// Create `java.time.Duration`, if duration is too big for it
+/// throw `ArithmeticException` and return null
+#[allow(dead_code)]
+fn to_java_time_duration(
+    env: *mut JNIEnv,
+    x: ::std::time::Duration,
+) -> java_time_aliases::JDuration {
+    let secs = match <i64 as ::std::convert::TryFrom<u64>>::try_from(x.as_secs()) {
+        Ok(secs) => secs,
+        Err(_) => {
+            jni_throw_arithmetic_exception(
+                env,
+                &format!("Duration {:?} is too big for java.time.Duration", x),
+            );
+            return ::std::ptr::null_mut();
+        }
+    };
+    let class: jclass = swig_jni_find_class!(JAVA_TIME_DURATION, "java/time/Duration");
+    assert!(!class.is_null());
+    let of_seconds_m: jmethodID = swig_jni_get_static_method_id!(
+        JAVA_TIME_DURATION_OF_SECONDS,
+        JAVA_TIME_DURATION,
+        "ofSeconds",
+        "(JJ)Ljava/time/Duration;"
+    );
+    assert!(!of_seconds_m.is_null());
+    unsafe {
+        let ret = (**env).CallStaticObjectMethod.unwrap()(
+            env,
+            class,
+            of_seconds_m,
+            secs,
+            jlong::from(x.subsec_nanos()),
+        );
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return ::std::ptr::null_mut();
+        }
+        assert!(!ret.is_null());
+        ret
+    }
+}
+
+/// Convert `java.time.Duration` to `Duration`, if it is negative
+/// throw `ArithmeticException` and return `None`
+#[allow(dead_code)]
+fn from_java_time_duration_to_rust(
+    env: *mut JNIEnv,
+    x: java_time_aliases::JDuration,
+) -> Option<::std::time::Duration> {
+    if !jni_check_time_object_not_null(env, x, "java.time.Duration") {
+        return None;
+    }
+    let get_seconds_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_DURATION_GET_SECONDS,
+        JAVA_TIME_DURATION,
+        "getSeconds",
+        "()J"
+    );
+    assert!(!get_seconds_m.is_null());
+    let get_nano_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_DURATION_GET_NANO,
+        JAVA_TIME_DURATION,
+        "getNano",
+        "()I"
+    );
+    assert!(!get_nano_m.is_null());
+    let (secs, nanos) = unsafe {
+        let secs = (**env).CallLongMethod.unwrap()(env, x, get_seconds_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        let nanos = (**env).CallIntMethod.unwrap()(env, x, get_nano_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        (secs, nanos)
+    };
+    match <u64 as ::std::convert::TryFrom<jlong>>::try_from(secs) {
+        Ok(secs) => Some(::std::time::Duration::new(secs, nanos as u32)),
+        Err(_) => {
+            jni_throw_arithmetic_exception(
+                env,
+                "negative java.time.Duration can not be converted to std::time::Duration",
+            );
+            None
+        }
+    }
+}
+
+/// Convert `SystemTime` to `java.time.Instant` with nanoseconds precision
+#[allow(dead_code)]
+fn system_time_to_java_time_instant(
+    env: *mut JNIEnv,
+    x: ::std::time::SystemTime,
+) -> java_time_aliases::JInstant {
+    let (secs, nanos) = match x.duration_since(::std::time::UNIX_EPOCH) {
+        Ok(after_epoch) => (
+            <i64 as ::std::convert::TryFrom<u64>>::try_from(after_epoch.as_secs()).ok(),
+            after_epoch.subsec_nanos(),
+        ),
+        Err(err) => {
+            let before_epoch = err.duration();
+            let secs = <i64 as ::std::convert::TryFrom<u64>>::try_from(before_epoch.as_secs())
+                .ok()
+                .map(|secs| -secs);
+            match before_epoch.subsec_nanos() {
+                0 => (secs, 0),
+                nanos => (
+                    secs.and_then(|secs| secs.checked_sub(1)),
+                    SWIG_JNI_NANOS_PER_SEC - nanos,
+                ),
+            }
+        }
+    };
+    match secs {
+        Some(secs) => to_java_time_instant(env, secs, nanos),
+        None => {
+            jni_throw_arithmetic_exception(
+                env,
+                &format!("SystemTime {:?} is out of java.time.Instant range", x),
+            );
+            ::std::ptr::null_mut()
+        }
+    }
+}
+
+/// Convert `java.time.Instant` to `SystemTime` with nanoseconds precision,
+/// if it is out of `SystemTime` range throw `DateTimeException` and return `None`
+#[allow(dead_code)]
+fn java_time_instant_to_system_time(
+    env: *mut JNIEnv,
+    x: java_time_aliases::JInstant,
+) -> Option<::std::time::SystemTime> {
+    let (secs, nanos) = from_java_time_instant_to_rust(env, x)?;
+    let since_epoch = ::std::time::Duration::new(secs.unsigned_abs(), 0);
+    let ret = if secs >= 0 {
+        ::std::time::UNIX_EPOCH.checked_add(since_epoch)
+    } else {
+        ::std::time::UNIX_EPOCH.checked_sub(since_epoch)
+    };
+    let ret = ret.and_then(|t| t.checked_add(::std::time::Duration::new(0, nanos)));
+    if ret.is_none() {
+        jni_throw_date_time_exception(
+            env,
+            "java.time.Instant is out of SystemTime range on this platform",
+        );
+    }
+    ret
+}
+
+/// Create `java.time.LocalDate` from number of days since 1970-01-01
+#[allow(dead_code)]
+fn to_java_time_local_date(env: *mut JNIEnv, epoch_day: i64) -> java_time_aliases::JLocalDate {
+    let class: jclass = swig_jni_find_class!(JAVA_TIME_LOCAL_DATE, "java/time/LocalDate");
+    assert!(!class.is_null());
+    let of_epoch_day_m: jmethodID = swig_jni_get_static_method_id!(
+        JAVA_TIME_LOCAL_DATE_OF_EPOCH_DAY,
+        JAVA_TIME_LOCAL_DATE,
+        "ofEpochDay",
+        "(J)Ljava/time/LocalDate;"
+    );
+    assert!(!of_epoch_day_m.is_null());
+    unsafe {
+        let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, of_epoch_day_m, epoch_day);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return ::std::ptr::null_mut();
+        }
+        assert!(!ret.is_null());
+        ret
+    }
+}
+
+/// Number of days since 1970-01-01 of `java.time.LocalDate`,
+/// in case of exception return `None` and leave exception pending
+#[allow(dead_code)]
+fn from_java_time_local_date_to_rust(
+    env: *mut JNIEnv,
+    x: java_time_aliases::JLocalDate,
+) -> Option<i64> {
+    if !jni_check_time_object_not_null(env, x, "java.time.LocalDate") {
+        return None;
+    }
+    let to_epoch_day_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_LOCAL_DATE_TO_EPOCH_DAY,
+        JAVA_TIME_LOCAL_DATE,
+        "toEpochDay",
+        "()J"
+    );
+    assert!(!to_epoch_day_m.is_null());
+    unsafe {
+        let ret = (**env).CallLongMethod.unwrap()(env, x, to_epoch_day_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        Some(ret)
+    }
+}
+
+/// Create `java.time.LocalDateTime` from number of days since 1970-01-01
+/// and nanoseconds of day, in case of exception return null and leave exception pending
+#[allow(dead_code)]
+fn to_java_time_local_date_time(
+    env: *mut JNIEnv,
+    epoch_day: i64,
+    nano_of_day: i64,
+) -> java_time_aliases::JLocalDateTime {
+    let date = to_java_time_local_date(env, epoch_day);
+    if date.is_null() {
+        return ::std::ptr::null_mut();
+    }
+    let time_class: jclass = swig_jni_find_class!(JAVA_TIME_LOCAL_TIME, "java/time/LocalTime");
+    assert!(!time_class.is_null());
+    let of_nano_of_day_m: jmethodID = swig_jni_get_static_method_id!(
+        JAVA_TIME_LOCAL_TIME_OF_NANO_OF_DAY,
+        JAVA_TIME_LOCAL_TIME,
+        "ofNanoOfDay",
+        "(J)Ljava/time/LocalTime;"
+    );
+    assert!(!of_nano_of_day_m.is_null());
+    let class: jclass = swig_jni_find_class!(JAVA_TIME_LOCAL_DATE_TIME, "java/time/LocalDateTime");
+    assert!(!class.is_null());
+    let of_m: jmethodID = swig_jni_get_static_method_id!(
+        JAVA_TIME_LOCAL_DATE_TIME_OF,
+        JAVA_TIME_LOCAL_DATE_TIME,
+        "of",
+        "(Ljava/time/LocalDate;Ljava/time/LocalTime;)Ljava/time/LocalDateTime;"
+    );
+    assert!(!of_m.is_null());
+    unsafe {
+        let time =
+            (**env).CallStaticObjectMethod.unwrap()(env, time_class, of_nano_of_day_m, nano_of_day);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            (**env).DeleteLocalRef.unwrap()(env, date);
+            return ::std::ptr::null_mut();
+        }
+        let ret = (**env).CallStaticObjectMethod.unwrap()(env, class, of_m, date, time);
+        (**env).DeleteLocalRef.unwrap()(env, date);
+        (**env).DeleteLocalRef.unwrap()(env, time);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return ::std::ptr::null_mut();
+        }
+        assert!(!ret.is_null());
+        ret
+    }
+}
+
+/// Number of days since 1970-01-01 and nanoseconds of day of `java.time.LocalDateTime`,
+/// in case of exception return `None` and leave exception pending
+#[allow(dead_code)]
+fn from_java_time_local_date_time_to_rust(
+    env: *mut JNIEnv,
+    x: java_time_aliases::JLocalDateTime,
+) -> Option<(i64, i64)> {
+    if !jni_check_time_object_not_null(env, x, "java.time.LocalDateTime") {
+        return None;
+    }
+    let to_local_date_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_LOCAL_DATE_TIME_TO_LOCAL_DATE,
+        JAVA_TIME_LOCAL_DATE_TIME,
+        "toLocalDate",
+        "()Ljava/time/LocalDate;"
+    );
+    assert!(!to_local_date_m.is_null());
+    let to_local_time_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_LOCAL_DATE_TIME_TO_LOCAL_TIME,
+        JAVA_TIME_LOCAL_DATE_TIME,
+        "toLocalTime",
+        "()Ljava/time/LocalTime;"
+    );
+    assert!(!to_local_time_m.is_null());
+    let to_nano_of_day_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_TIME_LOCAL_TIME_TO_NANO_OF_DAY,
+        JAVA_TIME_LOCAL_TIME,
+        "toNanoOfDay",
+        "()J"
+    );
+    assert!(!to_nano_of_day_m.is_null());
+    let (date, nano_of_day) = unsafe {
+        let date = (**env).CallObjectMethod.unwrap()(env, x, to_local_date_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            return None;
+        }
+        let time = (**env).CallObjectMethod.unwrap()(env, x, to_local_time_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            (**env).DeleteLocalRef.unwrap()(env, date);
+            return None;
+        }
+        let nano_of_day = (**env).CallLongMethod.unwrap()(env, time, to_nano_of_day_m);
+        (**env).DeleteLocalRef.unwrap()(env, time);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            (**env).DeleteLocalRef.unwrap()(env, date);
+            return None;
+        }
+        (date, nano_of_day)
+    };
+    let epoch_day = from_java_time_local_date_to_rust(env, date);
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, date) };
+    Some((epoch_day?, nano_of_day))
+}
+
+foreign_typemap!(
+    ($p:r_type) Duration => java_time_aliases::JDuration {
+        $out = to_java_time_duration(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.time.Duration";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.time.Duration";
+    ($p:r_type) Duration <= java_time_aliases::JCheckedTimeObject {
+        $out = match from_java_time_duration_to_rust(env, $p) {
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.time.Duration"
+        r#"
+        if ($p.isNegative()) {
+            throw new ArithmeticException("negative " + $p + " can not be converted to std::time::Duration");
+        }
+        $out = $p;
+"#;
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.time.Duration"
+        r#"
+        if ($p.isNegative()) {
+            throw new ArithmeticException("negative " + $p + " can not be converted to std::time::Duration");
+        }
+        $out = $p;
+"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) SystemTime => java_time_aliases::JInstant {
+        $out = system_time_to_java_time_instant(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.time.Instant";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.time.Instant";
+    ($p:r_type) SystemTime <= java_time_aliases::JCheckedTimeObject {
+        $out = match java_time_instant_to_system_time(env, $p) {
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.time.Instant" "$out = $p;";
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.time.Instant" "$out = $p;";
+);
+
+foreign_typemap!(
+    ($p:r_type) NaiveDate => java_time_aliases::JLocalDate {
+        let days_from_ce = i64::from(::chrono::Datelike::num_days_from_ce(&$p));
+        $out = to_java_time_local_date(env, days_from_ce - SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.time.LocalDate";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.time.LocalDate";
+    ($p:r_type) NaiveDate <= java_time_aliases::JCheckedTimeObject {
+        let epoch_day = match from_java_time_local_date_to_rust(env, $p) {
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+        $out = match <i32 as ::std::convert::TryFrom<i64>>::try_from(
+            epoch_day + SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE,
+        )
+        .ok()
+        .and_then(::chrono::NaiveDate::from_num_days_from_ce_opt)
+        {
+            Some(x) => x,
+            None => {
+                jni_throw_date_time_exception(
+                    env,
+                    "java.time.LocalDate is out of chrono::NaiveDate range",
+                );
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.time.LocalDate"
+        r#"
+        if ($p.getYear() < -262144 || $p.getYear() > 262143) {
+            throw new java.time.DateTimeException($p + " is out of chrono::NaiveDate range");
+        }
+        $out = $p;
+"#;
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.time.LocalDate"
+        r#"
+        if ($p.getYear() < -262144 || $p.getYear() > 262143) {
+            throw new java.time.DateTimeException($p + " is out of chrono::NaiveDate range");
+        }
+        $out = $p;
+"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) NaiveDateTime => java_time_aliases::JLocalDateTime {
+        let days_from_ce = i64::from(::chrono::Datelike::num_days_from_ce(&$p.date()));
+        let time = $p.time();
+        let nano_of_day = i64::from(::chrono::Timelike::num_seconds_from_midnight(&time))
+            * i64::from(SWIG_JNI_NANOS_PER_SEC)
+            + i64::from(::chrono::Timelike::nanosecond(&time).min(SWIG_JNI_NANOS_PER_SEC - 1));
+        $out = to_java_time_local_date_time(
+            env,
+            days_from_ce - SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE,
+            nano_of_day,
+        );
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.time.LocalDateTime";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.time.LocalDateTime";
+    ($p:r_type) NaiveDateTime <= java_time_aliases::JCheckedTimeObject {
+        let (epoch_day, nano_of_day) = match from_java_time_local_date_time_to_rust(env, $p) {
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+        let nanos_per_sec = i64::from(SWIG_JNI_NANOS_PER_SEC);
+        let date = <i32 as ::std::convert::TryFrom<i64>>::try_from(
+            epoch_day + SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE,
+        )
+        .ok()
+        .and_then(::chrono::NaiveDate::from_num_days_from_ce_opt);
+        let time = ::chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+            (nano_of_day / nanos_per_sec) as u32,
+            (nano_of_day % nanos_per_sec) as u32,
+        );
+        $out = match (date, time) {
+            (Some(date), Some(time)) => ::chrono::NaiveDateTime::new(date, time),
+            _ => {
+                jni_throw_date_time_exception(
+                    env,
+                    "java.time.LocalDateTime is out of chrono::NaiveDateTime range",
+                );
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.time.LocalDateTime"
+        r#"
+        if ($p.getYear() < -262144 || $p.getYear() > 262143) {
+            throw new java.time.DateTimeException($p + " is out of chrono::NaiveDateTime range");
+        }
+        $out = $p;
+"#;
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.time.LocalDateTime"
+        r#"
+        if ($p.getYear() < -262144 || $p.getYear() > 262143) {
+            throw new java.time.DateTimeException($p + " is out of chrono::NaiveDateTime range");
+        }
+        $out = $p;
+"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) DateTime<Utc> => java_time_aliases::JInstant {
+        let naive = $p.naive_utc();
+        let time = naive.time();
+        let days_from_ce = i64::from(::chrono::Datelike::num_days_from_ce(&naive.date()));
+        let secs = (days_from_ce - SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE) * SWIG_JNI_SECS_PER_DAY
+            + i64::from(::chrono::Timelike::num_seconds_from_midnight(&time));
+        $out = to_java_time_instant(env, secs, ::chrono::Timelike::nanosecond(&time));
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*chrono::DateTime<Utc>*/")
+        => "/*chrono::DateTime<Utc>*/java.time.Instant";
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*chrono::DateTime<Utc>*/")
+        => "/*chrono::DateTime<Utc>*/@NonNull java.time.Instant";
+    ($p:r_type) DateTime<Utc> <= java_time_aliases::JCheckedTimeObject {
+        let (secs, nanos) = match from_java_time_instant_to_rust(env, $p) {
+            Some(x) => x,
+            None => return JniInvalidValue::jni_invalid_value(),
+        };
+        let days_from_ce = <i32 as ::std::convert::TryFrom<i64>>::try_from(
+            secs.div_euclid(SWIG_JNI_SECS_PER_DAY) + SWIG_JNI_UNIX_EPOCH_DAYS_FROM_CE,
+        );
+        let naive = match days_from_ce
+            .ok()
+            .and_then(::chrono::NaiveDate::from_num_days_from_ce_opt)
+            .and_then(|date| {
+                ::chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                    secs.rem_euclid(SWIG_JNI_SECS_PER_DAY) as u32,
+                    nanos,
+                )
+                .map(|time| ::chrono::NaiveDateTime::new(date, time))
+            }) {
+            Some(x) => x,
+            None => {
+                jni_throw_date_time_exception(
+                    env,
+                    "java.time.Instant is out of chrono::DateTime<Utc> range",
+                );
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+        $out = ::chrono::TimeZone::from_utc_datetime(&::chrono::Utc, &naive);
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*chrono::DateTime<Utc>*/")
+        <= "/*chrono::DateTime<Utc>*/java.time.Instant"
+        r#"
+        if ($p.getEpochSecond() < -8334632851200L || $p.getEpochSecond() > 8210298412799L) {
+            throw new java.time.DateTimeException($p + " is out of chrono::DateTime<Utc> range");
+        }
+        $out = $p;
+"#;
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*chrono::DateTime<Utc>*/")
+        <= "/*chrono::DateTime<Utc>*/@NonNull java.time.Instant"
+        r#"
+        if ($p.getEpochSecond() < -8334632851200L || $p.getEpochSecond() > 8210298412799L) {
+            throw new java.time.DateTimeException($p + " is out of chrono::DateTime<Utc> range");
+        }
+        $out = $p;
+"#;
+);
@@ -0,0 +1,13 @@
+foreign_typemap!(
+    ($p:r_type) SystemTime => jlong {
+        let since_unix_epoch = $p
+            .duration_since(::std::time::UNIX_EPOCH)
+            .expect("SystemTime to Unix time conv. error");
+        $out = <i64 as ::std::convert::TryFrom<u64>>::try_from(
+            since_unix_epoch.as_secs() * 1_000 + u64::from(since_unix_epoch.subsec_millis()),
+        )
+        .expect("SystemTime: milleseconds u64 to i64 convert error");
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.Date" "$out = new java.util.Date($p);";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.Date" "$out = new java.util.Date($p);";
+);
@@ -0,0 +1,192 @@
+/// Create `java.util.ArrayList` from local references,
+/// local references are deleted after insertion into list
+#[allow(dead_code)]
+fn jobjects_to_java_list<I: ExactSizeIterator<Item = jobject>>(
+    env: *mut JNIEnv,
+    elems: I,
+) -> jobject {
+    let len = <jsize as ::std::convert::TryFrom<usize>>::try_from(elems.len())
+        .expect("invalid usize, in usize => to jsize conversation");
+    let class: jclass = swig_jni_find_class!(JAVA_UTIL_ARRAY_LIST, "java/util/ArrayList");
+    assert!(!class.is_null());
+    let init_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_ARRAY_LIST_INIT,
+        JAVA_UTIL_ARRAY_LIST,
+        "<init>",
+        "(I)V"
+    );
+    assert!(!init_m.is_null());
+    let add_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_ARRAY_LIST_ADD,
+        JAVA_UTIL_ARRAY_LIST,
+        "add",
+        "(Ljava/lang/Object;)Z"
+    );
+    assert!(!add_m.is_null());
+    let list: jobject = unsafe { (**env).NewObject.unwrap()(env, class, init_m, len) };
+    assert!(!list.is_null(), "Can not create java.util.ArrayList");
+    for (i, elem) in elems.enumerate() {
+        unsafe {
+            (**env).CallBooleanMethod.unwrap()(env, list, add_m, elem);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("ArrayList.add({}) failed", i);
+            }
+            (**env).DeleteLocalRef.unwrap()(env, elem);
+        }
+    }
+    list
+}
+
+/// Length of `java.util.List`
+#[allow(dead_code)]
+fn java_list_len(env: *mut JNIEnv, list: jobject) -> jsize {
+    let class: jclass = swig_jni_find_class!(JAVA_UTIL_LIST, "java/util/List");
+    assert!(!class.is_null());
+    let size_m: jmethodID =
+        swig_jni_get_method_id!(JAVA_UTIL_LIST_SIZE, JAVA_UTIL_LIST, "size", "()I");
+    assert!(!size_m.is_null());
+    unsafe {
+        let len = (**env).CallIntMethod.unwrap()(env, list, size_m);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("List.size failed: catch exception");
+        }
+        len
+    }
+}
+
+/// Get element of `java.util.List`, result is local reference
+#[allow(dead_code)]
+fn java_list_get(env: *mut JNIEnv, list: jobject, i: jsize) -> jobject {
+    let get_m: jmethodID = swig_jni_get_method_id!(
+        JAVA_UTIL_LIST_GET,
+        JAVA_UTIL_LIST,
+        "get",
+        "(I)Ljava/lang/Object;"
+    );
+    assert!(!get_m.is_null());
+    unsafe {
+        let obj = (**env).CallObjectMethod.unwrap()(env, list, get_m, i);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("Failed to retrieve element {} from java.util.List", i);
+        }
+        obj
+    }
+}
+
+#[allow(dead_code)]
+fn vec_of_objects_to_java_list<T: SwigForeignClass>(
+    env: *mut JNIEnv,
+    arr: Vec<T>,
+) -> internal_aliases::JForeignObjectsArray<T> {
+    let list = jobjects_to_java_list(
+        env,
+        arr.into_iter().map(|r_obj| object_to_jobject(env, r_obj)),
+    );
+    internal_aliases::JForeignObjectsArray {
+        inner: list,
+        _marker: ::std::marker::PhantomData,
+    }
+}
+
+#[allow(dead_code)]
+fn java_list_to_vec_of_objects<T: SwigForeignClass + Clone>(
+    env: *mut JNIEnv,
+    list: internal_aliases::JForeignObjectsArray<T>,
+) -> Vec<T> {
+    let field_id = <T>::jni_class_pointer_field();
+    assert!(!field_id.is_null());
+    let length = java_list_len(env, list.inner);
+    let len = <usize as ::std::convert::TryFrom<jsize>>::try_from(length)
+        .expect("invalid jsize, in jsize => usize conversation");
+    let mut result = Vec::with_capacity(len);
+    for i in 0..length {
+        let native: &mut T = unsafe {
+            let obj = java_list_get(env, list.inner, i);
+            let ptr = (**env).GetLongField.unwrap()(env, obj, field_id);
+            let native = (jlong_to_pointer(ptr) as *mut T).as_mut().unwrap();
+            (**env).DeleteLocalRef.unwrap()(env, obj);
+            native
+        };
+        result.push(native.clone());
+    }
+    result
+}
+
+#[allow(dead_code)]
+fn vec_string_to_java_list(arr: Vec<String>, env: *mut JNIEnv) -> jobject {
+    jobjects_to_java_list(
+        env,
+        arr.into_iter().map(|r_str| {
+            let jstr: jstring = from_std_string_jstring(r_str, env);
+            assert!(!jstr.is_null());
+            jstr
+        }),
+    )
+}
+
+#[allow(dead_code)]
+fn java_list_to_vec_of_strings(env: *mut JNIEnv, list: jobject) -> Vec<String> {
+    let length = java_list_len(env, list);
+    let len = <usize as ::std::convert::TryFrom<jsize>>::try_from(length)
+        .expect("invalid jsize, in jsize => usize conversation");
+    let mut result = Vec::with_capacity(len);
+    for i in 0..length {
+        let jstr: jstring = java_list_get(env, list, i);
+        let s = JavaString::new(env, jstr);
+        result.push(s.to_str().to_string());
+        drop(s);
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, jstr) };
+    }
+    result
+}
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass> Vec<T> => internal_aliases::JForeignObjectsArray<T> {
+        $out = vec_of_objects_to_java_list(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.List<swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+);
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> Vec<T> <= internal_aliases::JForeignObjectsArray<T> {
+        $out = java_list_to_vec_of_objects(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.util.List<swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations")
+                  <= "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+);
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> &[T] => internal_aliases::JForeignObjectsArray<T> {
+        $out = vec_of_objects_to_java_list(env, $p.to_vec());
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.List<swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+);
+
+foreign_typemap!(
+    ($p:r_type) <T: SwigForeignClass + Clone> &[T] <= internal_aliases::JForeignObjectsArray<T> {
+        let tmp_vec: Vec<swig_subst_type!(T)> = java_list_to_vec_of_objects(env, $p);
+        $out = tmp_vec.as_slice();
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.util.List<swig_f_type!(T)>";
+    ($p:f_type, option = "NullAnnotations")
+                  <= "@NonNull java.util.List<swig_f_type!(T, NoNullAnnotations)>";
+);
+
+foreign_typemap!(
+    ($p:r_type) Vec<String> => internal_aliases::JStringObjectsArray {
+        $out = vec_string_to_java_list($p, env);
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.util.List<String>";
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.util.List<String>";
+);
+
+foreign_typemap!(
+    ($p:r_type) Vec<String> <= internal_aliases::JStringObjectsArray {
+        $out = java_list_to_vec_of_strings(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.util.List<String>";
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.util.List<String>";
+);
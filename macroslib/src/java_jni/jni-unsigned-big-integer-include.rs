@@ -0,0 +1,27 @@
+foreign_typemap!(
+    ($p:r_type) u64 => jlong {
+        $out = $p as jlong;
+    };
+    ($p:f_type, option = "NoNullAnnotations") => "java.math.BigInteger" r#"
+        $out = new java.math.BigInteger(Long.toUnsignedString($p));
+"#;
+    ($p:f_type, option = "NullAnnotations") => "@NonNull java.math.BigInteger" r#"
+        $out = new java.math.BigInteger(Long.toUnsignedString($p));
+"#;
+    ($p:r_type) u64 <= jlong {
+        // bits of `BigInteger.longValue()`, range is checked by Java code
+        $out = $p as u64;
+    };
+    ($p:f_type, option = "NoNullAnnotations") <= "java.math.BigInteger" r#"
+        if ($p.signum() < 0 || $p.bitLength() > 64) {
+            throw new ArithmeticException($p + " is out of range of u64");
+        }
+        $out = $p.longValue();
+"#;
+    ($p:f_type, option = "NullAnnotations") <= "@NonNull java.math.BigInteger" r#"
+        if ($p.signum() < 0 || $p.bitLength() > 64) {
+            throw new ArithmeticException($p + " is out of range of u64");
+        }
+        $out = $p.longValue();
+"#;
+);
@@ -0,0 +1,62 @@
+foreign_typemap!(
+    ($p:r_type) u8 => jshort {
+        $out = jshort::from($p);
+    };
+    ($p:r_type) u8 <= jshort {
+        $out = match <u8 as ::std::convert::TryFrom<jshort>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                jni_throw_arithmetic_exception(env, &format!("{} is out of range of u8", $p));
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*u8*/") <= "/*u8*/short" r#"
+        if ($p < 0 || $p > 0xFF) {
+            throw new ArithmeticException($p + " is out of range of u8");
+        }
+        $out = $p;
+"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) u16 => jint {
+        $out = jint::from($p);
+    };
+    ($p:r_type) u16 <= jint {
+        $out = match <u16 as ::std::convert::TryFrom<jint>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                jni_throw_arithmetic_exception(env, &format!("{} is out of range of u16", $p));
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*u16*/") <= "/*u16*/int" r#"
+        if ($p < 0 || $p > 0xFFFF) {
+            throw new ArithmeticException($p + " is out of range of u16");
+        }
+        $out = $p;
+"#;
+);
+
+foreign_typemap!(
+    ($p:r_type) u32 => jlong {
+        $out = jlong::from($p);
+    };
+    ($p:r_type) u32 <= jlong {
+        $out = match <u32 as ::std::convert::TryFrom<jlong>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                jni_throw_arithmetic_exception(env, &format!("{} is out of range of u32", $p));
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*u32*/") <= "/*u32*/long" r#"
+        if ($p < 0 || $p > 0xFFFFFFFFL) {
+            throw new ArithmeticException($p + " is out of range of u32");
+        }
+        $out = $p;
+"#;
+);
@@ -0,0 +1,26 @@
+foreign_typemap!(
+    ($p:r_type) u64 => jlong {
+        $out = $p as jlong;
+    };
+    ($p:f_type, unique_prefix = "/*u64*/") => "/*u64*/long" r#"
+        if ($p < 0) {
+            throw new ArithmeticException(Long.toUnsignedString($p) + " is out of range of long");
+        }
+        $out = $p;
+"#;
+    ($p:r_type) u64 <= jlong {
+        $out = match <u64 as ::std::convert::TryFrom<jlong>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                jni_throw_arithmetic_exception(env, &format!("{} is out of range of u64", $p));
+                return JniInvalidValue::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*u64*/") <= "/*u64*/long" r#"
+        if ($p < 0) {
+            throw new ArithmeticException($p + " is out of range of u64");
+        }
+        $out = $p;
+"#;
+);
@@ -0,0 +1,64 @@
+#[allow(dead_code)]
+mod unsigned_aliases {
+    use super::*;
+    pub type JU8 = jbyte;
+    pub type JU16 = jshort;
+    pub type JU32 = jint;
+    pub type JU64 = jlong;
+}
+
+foreign_typemap!(
+    (r_type) unsigned_aliases::JU8;
+    (f_type) "/*u8*/byte";
+);
+
+foreign_typemap!(
+    ($p:r_type) u8 => unsigned_aliases::JU8 {
+        $out = $p as jbyte;
+    };
+    ($p:r_type) u8 <= unsigned_aliases::JU8 {
+        $out = $p as u8;
+    };
+);
+
+foreign_typemap!(
+    (r_type) unsigned_aliases::JU16;
+    (f_type) "/*u16*/short";
+);
+
+foreign_typemap!(
+    ($p:r_type) u16 => unsigned_aliases::JU16 {
+        $out = $p as jshort;
+    };
+    ($p:r_type) u16 <= unsigned_aliases::JU16 {
+        $out = $p as u16;
+    };
+);
+
+foreign_typemap!(
+    (r_type) unsigned_aliases::JU32;
+    (f_type) "/*u32*/int";
+);
+
+foreign_typemap!(
+    ($p:r_type) u32 => unsigned_aliases::JU32 {
+        $out = $p as jint;
+    };
+    ($p:r_type) u32 <= unsigned_aliases::JU32 {
+        $out = $p as u32;
+    };
+);
+
+foreign_typemap!(
+    (r_type) unsigned_aliases::JU64;
+    (f_type) "/*u64*/long";
+);
+
+foreign_typemap!(
+    ($p:r_type) u64 => unsigned_aliases::JU64 {
+        $out = $p as jlong;
+    };
+    ($p:r_type) u64 <= unsigned_aliases::JU64 {
+        $out = $p as u64;
+    };
+);
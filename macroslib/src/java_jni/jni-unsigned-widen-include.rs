@@ -0,0 +1,42 @@
+foreign_typemap!(
+    ($p:r_type) u8 => jshort {
+        $out = jshort::from($p);
+    };
+);
+foreign_typemap!(
+    ($p:r_type) u8 <= jshort {
+        $out = <u8 as ::std::convert::TryFrom<jshort>>::try_from($p)
+            .expect("invalid jshort, in jshort => u8 conversation");
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) u16 => jint {
+        $out = jint::from($p);
+    };
+    ($p:r_type) u16 <= jint {
+        $out = <u16 as ::std::convert::TryFrom<jint>>::try_from($p)
+            .expect("invalid jint, in jint => u16 conversation");
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) u32 => jlong {
+        $out = jlong::from($p);
+    };
+    ($p:r_type) u32 <= jlong {
+        $out = <u32 as ::std::convert::TryFrom<jlong>>::try_from($p)
+            .expect("invalid jlong, in jlong => u32 conversation");
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) u64 => jlong {
+        $out = <jlong as ::std::convert::TryFrom<u64>>::try_from($p)
+            .expect("invalid u64, in u64 => jlong conversation");
+    };
+    ($p:r_type) u64 <= jlong {
+        $out = <u64 as ::std::convert::TryFrom<jlong>>::try_from($p)
+            .expect("invalid jlong, in jlong => u64 conversation");
+    };
+);
@@ -3,12 +3,15 @@ use smol_str::SmolStr;
 use std::{fmt::Write, rc::Rc};
 use syn::spanned::Spanned;
 
-use super::{JavaContext, INTERNAL_PTR_MARKER, JAVA_RUST_SELF_NAME};
+use super::{
+    JavaContext, BORROW_OWNER_TEMPLATE, INTERNAL_PTR_MARKER, JAVA_BORROW_OWNER_FIELD,
+    JAVA_CHECKED_SELF_GETTER, JAVA_RUST_SELF_NAME,
+};
 use crate::{
     error::{invalid_src_id_span, Result},
     source_registry::SourceId,
     typemap::{
-        ast::TypeName,
+        ast::{DisplayToTokens, TypeName},
         ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS, RustType},
         utils::{boxed_type, convert_to_heap_pointer, unpack_from_heap_pointer},
         RustTypeIdx, TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
@@ -288,14 +291,38 @@ fn register_main_foreign_types(
         })?;
     }
 
-    let mut java_code_in_val_to_long = format!(
+    let borrowed = ctx
+        .borrowed_classes
+        .contains(class.name.to_string().as_str());
+    let self_raw_ptr = if borrowed {
+        format!("{}()", JAVA_CHECKED_SELF_GETTER)
+    } else {
+        JAVA_RUST_SELF_NAME.to_string()
+    };
+    let mut java_code_in_val_to_long = String::new();
+    if borrowed && !class.copy_derived() && !class.smart_ptr_copy_derived() {
+        write!(
+            &mut java_code_in_val_to_long,
+            r#"
+        if ({from_var}.{owner_field} != null) {{
+            throw new IllegalStateException("{class_name}: borrowed object can not be passed by value");
+        }}"#,
+            from_var = FROM_VAR_TEMPLATE,
+            owner_field = JAVA_BORROW_OWNER_FIELD,
+            class_name = class.name,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    write!(
+        &mut java_code_in_val_to_long,
         r#"
         long {to_var} = {from_var}.{class_raw_ptr};
 "#,
         to_var = TO_VAR_TEMPLATE,
         from_var = FROM_VAR_TEMPLATE,
-        class_raw_ptr = JAVA_RUST_SELF_NAME,
-    );
+        class_raw_ptr = self_raw_ptr,
+    )
+    .expect(WRITE_TO_MEM_FAILED_MSG);
     if !class.copy_derived() && !class.smart_ptr_copy_derived() {
         writeln!(
             &mut java_code_in_val_to_long,
@@ -347,6 +374,17 @@ fn register_main_foreign_types(
     };
     ctx.conv_map.alloc_foreign_type(class_ftype)?;
 
+    let borrowed_out = if borrowed {
+        Some(register_borrowed_out_val(
+            ctx,
+            class,
+            this_type_ref,
+            this_type_mut_ref,
+        )?)
+    } else {
+        None
+    };
+
     let jlong_ty = ctx.conv_map.ty_to_rust_type(&parse_type! { jlong });
     debug!(
         "register_main_foreign_types: ftype for this_type_ref {}",
@@ -368,13 +406,22 @@ fn register_main_foreign_types(
                         "        long {out} = {from}.{self_raw_ptr};",
                         from = FROM_VAR_TEMPLATE,
                         out = TO_VAR_TEMPLATE,
-                        self_raw_ptr = JAVA_RUST_SELF_NAME,
+                        self_raw_ptr = self_raw_ptr,
                     ),
                     invalid_src_id_span(),
                 )),
             }),
         }),
-        into_from_rust: None,
+        into_from_rust: borrowed_out.clone().map(|(intermediate_ty, conv_code)| {
+            ForeignConversationRule {
+                rust_ty: this_type_ref,
+                intermediate: Some(ForeignConversationIntermediate {
+                    input_to_output: false,
+                    intermediate_ty,
+                    conv_code,
+                }),
+            }
+        }),
         name_prefix: Some("/*ref*/".into()),
     };
     ctx.conv_map.alloc_foreign_type(class_ftype_ref_in)?;
@@ -399,13 +446,20 @@ fn register_main_foreign_types(
                         "        long {out} = {from}.{self_raw_ptr};",
                         from = FROM_VAR_TEMPLATE,
                         out = TO_VAR_TEMPLATE,
-                        self_raw_ptr = JAVA_RUST_SELF_NAME,
+                        self_raw_ptr = self_raw_ptr,
                     ),
                     invalid_src_id_span(),
                 )),
             }),
         }),
-        into_from_rust: None,
+        into_from_rust: borrowed_out.map(|(intermediate_ty, conv_code)| ForeignConversationRule {
+            rust_ty: this_type_mut_ref,
+            intermediate: Some(ForeignConversationIntermediate {
+                input_to_output: false,
+                intermediate_ty,
+                conv_code,
+            }),
+        }),
         name_prefix: Some("/*mut ref*/".into()),
     };
     ctx.conv_map.alloc_foreign_type(class_ftype_mut_ref_in)?;
@@ -442,7 +496,7 @@ fn register_main_foreign_types(
                                 "        long {out} = {from}.{self_raw_ptr};",
                                 from = FROM_VAR_TEMPLATE,
                                 out = TO_VAR_TEMPLATE,
-                                self_raw_ptr = JAVA_RUST_SELF_NAME,
+                                self_raw_ptr = self_raw_ptr,
                             ),
                             invalid_src_id_span(),
                         )),
@@ -473,7 +527,7 @@ fn register_main_foreign_types(
                                 "        long {out} = {from}.{self_raw_ptr};",
                                 from = FROM_VAR_TEMPLATE,
                                 out = TO_VAR_TEMPLATE,
-                                self_raw_ptr = JAVA_RUST_SELF_NAME,
+                                self_raw_ptr = self_raw_ptr,
                             ),
                             invalid_src_id_span(),
                         )),
@@ -488,3 +542,67 @@ fn register_main_foreign_types(
 
     Ok(())
 }
+
+/// Register conversation of references to objects of class into Java objects,
+/// that do not own memory, but keep strong reference to the owner
+fn register_borrowed_out_val(
+    ctx: &mut JavaContext,
+    class: &ForeignClassInfo,
+    this_type_ref: RustTypeIdx,
+    this_type_mut_ref: RustTypeIdx,
+) -> Result<(RustTypeIdx, Rc<TypeConvCode>)> {
+    let span = ctx.conv_map[this_type_ref].ty.span();
+    let jlong_ty = parse_type_spanned_checked!(span, jlong);
+    let borrowed_prefix = format!("{}Borrowed", class.name);
+    let jlong_borrowed_rty =
+        ctx.conv_map
+            .find_or_alloc_rust_type_with_suffix(&jlong_ty, &borrowed_prefix, class.src_id);
+    let this_type_inner = match ctx.conv_map[this_type_ref].ty {
+        syn::Type::Reference(ref ref_ty) => DisplayToTokens(&*ref_ty.elem).to_string(),
+        _ => unreachable!("this_type_ref should be reference"),
+    };
+    for from_ty in &[this_type_ref, this_type_mut_ref] {
+        ctx.conv_map.add_conversation_rule(
+            *from_ty,
+            jlong_borrowed_rty.to_idx(),
+            TypeConvCode::new2(
+                format!(
+                    r#"
+    let {to_var}: jlong = {from_var} as *const {this_type_inner} as jlong;
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    this_type_inner = this_type_inner,
+                ),
+                invalid_src_id_span(),
+            )
+            .into(),
+        );
+    }
+    let name_prefix: SmolStr = format!("/*{}*/", borrowed_prefix).into();
+    ctx.conv_map.alloc_foreign_type(ForeignTypeS {
+        name: TypeName::new(
+            format!("{}long", name_prefix),
+            (class.src_id, class.name.span()),
+        ),
+        provides_by_module: vec![],
+        from_into_rust: None,
+        into_from_rust: Some(ForeignConversationRule {
+            rust_ty: jlong_borrowed_rty.to_idx(),
+            intermediate: None,
+        }),
+        name_prefix: Some(name_prefix),
+    })?;
+    let conv_code = Rc::new(TypeConvCode::new(
+        format!(
+            "        {class_name} {out} = new {class_name}({internal_ptr_marker}.BORROWED_PTR, {var}, {owner});",
+            class_name = class.name,
+            var = FROM_VAR_TEMPLATE,
+            out = TO_VAR_TEMPLATE,
+            internal_ptr_marker = INTERNAL_PTR_MARKER,
+            owner = BORROW_OWNER_TEMPLATE,
+        ),
+        invalid_src_id_span(),
+    ));
+    Ok((jlong_borrowed_rty.to_idx(), conv_code))
+}
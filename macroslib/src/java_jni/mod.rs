@@ -2,9 +2,12 @@ mod fclass;
 mod fenum;
 mod find_cache;
 mod finterface;
+mod frecord;
 mod java_code;
+mod java_module;
 mod map_class_self_type;
 mod map_type;
+mod proguard;
 mod rust_code;
 
 use log::debug;
@@ -16,13 +19,14 @@ use std::{fmt, io::Write, path::PathBuf};
 use syn::{spanned::Spanned, Type};
 
 use crate::{
+    doc_comments::{exported_items, ExportedItems},
     error::{invalid_src_id_span, DiagnosticError, Result},
     extension::{ClassExtHandlers, ExtHandlers, MethodExtHandlers},
     file_cache::FileWriteCache,
     typemap::{
         ast::{
             check_if_smart_pointer_return_inner_type, if_result_return_ok_err_types,
-            if_ty_result_return_ok_type, DisplayToTokens,
+            if_ty_result_return_ok_type, normalize_type, DisplayToTokens,
         },
         ty::RustType,
         utils::{
@@ -38,8 +42,17 @@ use crate::{
 use map_class_self_type::register_typemap_for_self_type;
 
 const INTERNAL_PTR_MARKER: &str = "InternalPointerMarker";
+const NATIVE_OBJECT_OWNER: &str = "NativeObjectOwner";
+/// Placeholder in Java conversation code for object that owns returned reference
+const BORROW_OWNER_TEMPLATE: &str = "{borrow_owner}";
 const JAVA_RUST_SELF_NAME: &str = "mNativeObj";
+/// Field with owner of borrowed object
+const JAVA_BORROW_OWNER_FIELD: &str = "mOwner";
+/// Getter of `mNativeObj` that checks that owner of borrowed object is alive
+const JAVA_CHECKED_SELF_GETTER: &str = "checkedNativeObj";
 const REACHABILITY_FENCE_CLASS: &str = "JNIReachabilityFence";
+/// Nested class of Java record with native methods, records can not have them
+const JAVA_RECORD_NATIVE_CLASS: &str = "Native";
 
 struct JavaContext<'a> {
     cfg: &'a JavaConfig,
@@ -50,6 +63,24 @@ struct JavaContext<'a> {
     java_type_to_jni_sig_map: FxHashMap<SmolStr, SmolStr>,
     class_ext_handlers: &'a ClassExtHandlers,
     method_ext_handlers: &'a MethodExtHandlers,
+    native_methods: Vec<JniNativeMethod>,
+    /// Classes references to which returned from methods of other classes
+    borrowed_classes: FxHashSet<SmolStr>,
+    /// Classes with methods that return references to `borrowed_classes`
+    lending_classes: FxHashSet<SmolStr>,
+    exported_items: ExportedItems,
+}
+
+/// Java native method and Rust function that implements it
+struct JniNativeMethod {
+    /// Java class name without package
+    class_name: SmolStr,
+    java_method_name: String,
+    /// Java types of arguments, as declared in Java code
+    input: Vec<SmolStr>,
+    /// Java type of result, as declared in Java code
+    output: SmolStr,
+    rust_func_name: String,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -125,7 +156,11 @@ impl JavaConfig {
             let this_type_for_method = if_ty_result_return_ok_type(constructor_ret_type)
                 .unwrap_or_else(|| constructor_ret_type.clone());
 
-            let mut traits = vec!["SwigForeignClass"];
+            let mut traits = if class.java_record_derived() {
+                vec![]
+            } else {
+                vec!["SwigForeignClass"]
+            };
             if class.clone_derived() {
                 traits.push("Clone");
             }
@@ -164,7 +199,11 @@ impl JavaConfig {
                     ));
                 }
             }
-            register_typemap_for_self_type(ctx, class, this_type, self_desc)?;
+            if class.java_record_derived() {
+                frecord::register_typemap_for_record(ctx, class, this_type, self_desc)?;
+            } else {
+                register_typemap_for_self_type(ctx, class, this_type, self_desc)?;
+            }
         }
 
         let _ = ctx
@@ -196,15 +235,30 @@ impl LanguageGenerator for JavaConfig {
             java_type_to_jni_sig_map: rust_code::predefined_java_type_to_jni_sig(),
             class_ext_handlers: ext_handlers.class_ext_handlers,
             method_ext_handlers: ext_handlers.method_ext_handlers,
+            native_methods: Vec::new(),
+            borrowed_classes: FxHashSet::default(),
+            lending_classes: FxHashSet::default(),
+            exported_items: exported_items(&items),
         };
+        find_borrowed_classes(&mut ctx, &items);
         init(&mut ctx, code)?;
         for item in &items {
             if let ItemToExpand::Class(ref fclass) = item {
                 self.register_class(&mut ctx, fclass)?;
             }
         }
+        let callback_interfaces: Vec<SmolStr> = items
+            .iter()
+            .filter_map(|item| match item {
+                ItemToExpand::Interface(finterface) => Some(finterface.name.to_string().into()),
+                ItemToExpand::Class(_) | ItemToExpand::Enum(_) | ItemToExpand::Lifecycle(_) => None,
+            })
+            .collect();
         for item in items {
             match item {
+                ItemToExpand::Class(fclass) if fclass.java_record_derived() => {
+                    frecord::generate(&mut ctx, &fclass)?;
+                }
                 ItemToExpand::Class(fclass) => {
                     fclass::generate(&mut ctx, &fclass)?;
                 }
@@ -214,8 +268,25 @@ impl LanguageGenerator for JavaConfig {
                 ItemToExpand::Interface(finterface) => {
                     finterface::generate_interface(&mut ctx, &finterface)?;
                 }
+                ItemToExpand::Lifecycle(lifecycle) => {
+                    rust_code::generate_lifecycle_hooks(&mut ctx, &lifecycle);
+                }
             }
         }
+        if self.register_natives {
+            rust_code::generate_register_natives_func(&mut ctx)?;
+        }
+        // names of native methods are not exported in this mode,
+        // so without keep rules obfuscation breaks `RegisterNatives`
+        if self.register_natives || self.proguard_rules {
+            proguard::generate_proguard_rules(&mut ctx, &callback_interfaces)?;
+        }
+        if let Some(module_name) = self.java_module.as_ref() {
+            java_module::generate_module_info(&mut ctx, module_name)?;
+        }
+        if let Some(library_name) = self.native_library_loader.as_ref() {
+            java_module::generate_native_library_loader(&mut ctx, library_name)?;
+        }
 
         if remove_not_generated_files {
             remove_files_if(&self.output_dir, |path| {
@@ -237,7 +308,7 @@ impl LanguageGenerator for JavaConfig {
         _pointer_target_width: usize,
         mut generated_code: Vec<u8>,
     ) -> Result<Vec<u8>> {
-        rust_code::generate_load_unload_jni_funcs(&mut generated_code)?;
+        rust_code::generate_load_unload_jni_funcs(&mut generated_code, self.register_natives)?;
         Ok(generated_code)
     }
 }
@@ -263,6 +334,56 @@ fn method_name(method: &ForeignMethod, f_method: &JniForeignMethodSignature) ->
     }
 }
 
+/// Find methods that return references to objects of other classes,
+/// such objects should keep their owner alive
+fn find_borrowed_classes(ctx: &mut JavaContext, items: &[ItemToExpand]) {
+    let mut borrowable = FxHashMap::<&'static str, SmolStr>::default();
+    for item in items {
+        if let ItemToExpand::Class(ref fclass) = item {
+            if let Some(self_desc) = fclass.self_desc.as_ref() {
+                let this_type = if_ty_result_return_ok_type(&self_desc.constructor_ret_type)
+                    .unwrap_or_else(|| self_desc.constructor_ret_type.clone());
+                borrowable.insert(
+                    normalize_type(&smart_pointer_inner_type(&this_type)),
+                    fclass.name.to_string().into(),
+                );
+            }
+        }
+    }
+    for item in items {
+        if let ItemToExpand::Class(ref fclass) = item {
+            for method in &fclass.methods {
+                if let (MethodVariant::Method(_), syn::ReturnType::Type(_, ref ret_ty)) =
+                    (method.variant, &method.fn_decl.output)
+                {
+                    if let Type::Reference(ref ret_ref) = **ret_ty {
+                        if let Some(class_name) = borrowable.get(normalize_type(&ret_ref.elem)) {
+                            ctx.borrowed_classes.insert(class_name.clone());
+                            ctx.lending_classes.insert(fclass.name.to_string().into());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Type that is pointed by `mNativeObj`, `T` for `Box<T>`, `Rc<T>`, `Arc<T>`
+fn smart_pointer_inner_type(ty: &Type) -> Type {
+    if let Type::Path(ref type_path) = ty {
+        if let Some(last) = type_path.path.segments.last() {
+            if last.ident == "Box" || last.ident == "Rc" || last.ident == "Arc" {
+                if let syn::PathArguments::AngleBracketed(ref args) = last.arguments {
+                    if let Some(syn::GenericArgument::Type(ref inner)) = args.args.first() {
+                        return inner.clone();
+                    }
+                }
+            }
+        }
+    }
+    ty.clone()
+}
+
 fn java_class_full_name(package_name: &str, class_name: &str) -> String {
     let mut ret: String = package_name.into();
     ret.push('.');
@@ -367,7 +488,8 @@ fn init(ctx: &mut JavaContext, _code: &[SourceCode]) -> Result<()> {
 package {package};
 
 /*package*/ enum {enum_name} {{
-    RAW_PTR;
+    RAW_PTR,
+    BORROWED_PTR;
 }}"#,
         package = ctx.cfg.package_name,
         enum_name = INTERNAL_PTR_MARKER,
@@ -379,6 +501,33 @@ package {package};
             format!("write to {} failed: {}", src_path.display(), err),
         )
     })?;
+    if !ctx.lending_classes.is_empty() {
+        let src_path = ctx
+            .cfg
+            .output_dir
+            .join(format!("{}.java", NATIVE_OBJECT_OWNER));
+        let mut src_file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
+        writeln!(
+            src_file,
+            r#"
+// Automatically generated by flapigen
+package {package};
+
+/*package*/ interface {interface_name} {{
+    /** Is memory of this object and memory of its owners (if any) still valid */
+    boolean isNativeObjectAlive();
+}}"#,
+            package = ctx.cfg.package_name,
+            interface_name = NATIVE_OBJECT_OWNER,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        src_file.update_file_if_necessary().map_err(|err| {
+            DiagnosticError::new2(
+                invalid_src_id_span(),
+                format!("write to {} failed: {}", src_path.display(), err),
+            )
+        })?;
+    }
     match ctx.cfg.reachability_fence {
         JavaReachabilityFence::Std => {}
         JavaReachabilityFence::GenerateFence(max_args) => {
@@ -444,13 +593,24 @@ package {package};
                     &f_method,
                     false,
                 )?;
+                ctx.native_methods.push(JniNativeMethod {
+                    class_name: REACHABILITY_FENCE_CLASS.into(),
+                    java_method_name,
+                    input: vec!["Object".into(); i],
+                    output: "void".into(),
+                    rust_func_name: jni_func_name.clone(),
+                });
                 let jni_func_name = syn::Ident::new(&jni_func_name, Span::call_site());
                 jni_args.push(quote!(_: jobject));
                 let jni_args = &jni_args;
+                let linkage = if ctx.cfg.register_natives {
+                    quote!(extern "C")
+                } else {
+                    quote!(#[no_mangle] pub extern "C")
+                };
                 ctx.rust_code.push(quote! {
                     #[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
-                    #[no_mangle]
-                    pub extern "C" fn #jni_func_name(_env: *mut JNIEnv, _: jclass, #(#jni_args),*) {
+                    #linkage fn #jni_func_name(_env: *mut JNIEnv, _: jclass, #(#jni_args),*) {
                     }
                 });
             }
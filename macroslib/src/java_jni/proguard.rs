@@ -0,0 +1,237 @@
+use proc_macro2::TokenStream;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use std::{collections::BTreeMap, io::Write};
+use syn::{visit::Visit, Macro};
+
+use super::{
+    find_cache::{JniClassItemWithId, JniFindClass},
+    java_class_full_name, JavaContext,
+};
+use crate::{
+    error::{invalid_src_id_span, DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    WRITE_TO_MEM_FAILED_MSG,
+};
+
+const PROGUARD_RULES_FILE: &str = "proguard-rules.pro";
+/// Classes from these packages are not processed by code shrinker,
+/// so there is no need to keep them
+const PLATFORM_PACKAGES: [&str; 3] = ["java/", "javax/", "android/"];
+
+/// Generate ProGuard/R8 rules to keep classes and members, that Rust code uses via JNI,
+/// so code shrinking does not break the names resolved at runtime
+pub(in crate::java_jni) fn generate_proguard_rules(
+    ctx: &mut JavaContext,
+    callback_interfaces: &[SmolStr],
+) -> Result<()> {
+    let mut keep_classes = BTreeMap::<String, Vec<String>>::new();
+    let mut add_member = |class_name: String, member: String| {
+        let members = keep_classes.entry(class_name).or_default();
+        if !members.contains(&member) {
+            members.push(member);
+        }
+    };
+
+    for m in &ctx.native_methods {
+        add_member(
+            java_class_full_name(&ctx.cfg.package_name, &m.class_name),
+            "native <methods>;".into(),
+        );
+    }
+
+    let mut visitor = JniLookupsVisitor::default();
+    for item in ctx.rust_code.iter() {
+        visit_rust_code(&mut visitor, item);
+    }
+    for (class_id, kind, item) in &visitor.members {
+        let class_path = match visitor.classes.get(class_id) {
+            Some(path) => path,
+            None => continue,
+        };
+        if PLATFORM_PACKAGES.iter().any(|p| class_path.starts_with(p)) {
+            continue;
+        }
+        let member = match kind {
+            MemberKind::Method => jni_method_to_proguard(&item.name.value(), &item.sig.value()),
+            MemberKind::Field => jni_field_to_proguard(&item.name.value(), &item.sig.value()),
+        }
+        .ok_or_else(|| {
+            DiagnosticError::new2(
+                invalid_src_id_span(),
+                format!(
+                    "Can not generate keep rule for {}, invalid JNI signature '{}' of '{}'",
+                    class_path,
+                    item.sig.value(),
+                    item.name.value()
+                ),
+            )
+        })?;
+        add_member(class_path.replace('/', "."), member);
+    }
+    for class_path in visitor.classes.values() {
+        if !PLATFORM_PACKAGES.iter().any(|p| class_path.starts_with(p)) {
+            keep_classes
+                .entry(class_path.replace('/', "."))
+                .or_default();
+        }
+    }
+
+    let src_path = ctx.cfg.output_dir.join(PROGUARD_RULES_FILE);
+    let mut file = FileWriteCache::new(&src_path, ctx.generated_foreign_files);
+    writeln!(file, "# Automatically generated by flapigen").expect(WRITE_TO_MEM_FAILED_MSG);
+    for (class_name, members) in &keep_classes {
+        writeln!(file, "-keep class {} {{", class_name).expect(WRITE_TO_MEM_FAILED_MSG);
+        for member in members {
+            writeln!(file, "    {}", member).expect(WRITE_TO_MEM_FAILED_MSG);
+        }
+        writeln!(file, "}}").expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    for interface in callback_interfaces {
+        writeln!(
+            file,
+            r#"-keep interface {full_name} {{
+    <methods>;
+}}
+-keepclassmembers class * implements {full_name} {{
+    <methods>;
+}}"#,
+            full_name = java_class_full_name(&ctx.cfg.package_name, interface),
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+    }
+    file.update_file_if_necessary().map_err(|err| {
+        DiagnosticError::new2(
+            invalid_src_id_span(),
+            format!("write to {} failed: {}", src_path.display(), err),
+        )
+    })?;
+    Ok(())
+}
+
+fn visit_rust_code(visitor: &mut JniLookupsVisitor, code: &TokenStream) {
+    if let Ok(file) = syn::parse2::<syn::File>(code.clone()) {
+        visitor.visit_file(&file);
+    }
+}
+
+enum MemberKind {
+    Method,
+    Field,
+}
+
+/// Collect classes, methods and fields that Rust code looks up by name
+/// via `swig_jni_find_class!`, `swig_jni_get_method_id!` and so on
+#[derive(Default)]
+struct JniLookupsVisitor {
+    classes: FxHashMap<String, String>,
+    members: Vec<(String, MemberKind, JniClassItemWithId)>,
+}
+
+impl<'ast> Visit<'ast> for JniLookupsVisitor {
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        let kind = if mac.path.is_ident("swig_jni_find_class") {
+            if let Ok(find_class) = syn::parse2::<JniFindClass>(mac.tokens.clone()) {
+                self.classes
+                    .insert(find_class.id.to_string(), find_class.path.value());
+            }
+            None
+        } else if mac.path.is_ident("swig_jni_get_method_id")
+            || mac.path.is_ident("swig_jni_get_static_method_id")
+        {
+            Some(MemberKind::Method)
+        } else if mac.path.is_ident("swig_jni_get_field_id")
+            || mac.path.is_ident("swig_jni_get_static_field_id")
+        {
+            Some(MemberKind::Field)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            if let Ok(item) = syn::parse2::<JniClassItemWithId>(mac.tokens.clone()) {
+                self.members.push((item.class_id.to_string(), kind, item));
+            }
+        }
+        syn::visit::visit_macro(self, mac)
+    }
+}
+
+fn jni_field_to_proguard(name: &str, sig: &str) -> Option<String> {
+    let (field_type, tail) = parse_jni_type(sig)?;
+    if !tail.is_empty() {
+        return None;
+    }
+    Some(format!("{} {};", field_type, name))
+}
+
+fn jni_method_to_proguard(name: &str, sig: &str) -> Option<String> {
+    let mut tail = sig.strip_prefix('(')?;
+    let mut args = Vec::new();
+    while !tail.starts_with(')') {
+        let (arg_type, new_tail) = parse_jni_type(tail)?;
+        args.push(arg_type);
+        tail = new_tail;
+    }
+    let (ret_type, tail) = parse_jni_type(&tail[1..])?;
+    if !tail.is_empty() {
+        return None;
+    }
+    if name == "<init>" {
+        Some(format!("<init>({});", args.join(", ")))
+    } else {
+        Some(format!("{} {}({});", ret_type, name, args.join(", ")))
+    }
+}
+
+/// Parse one type from JNI signature, return Java name of type and rest of signature
+fn parse_jni_type(sig: &str) -> Option<(String, &str)> {
+    let mut chars = sig.chars();
+    let java_type = match chars.next()? {
+        'Z' => "boolean",
+        'B' => "byte",
+        'C' => "char",
+        'S' => "short",
+        'I' => "int",
+        'J' => "long",
+        'F' => "float",
+        'D' => "double",
+        'V' => "void",
+        '[' => {
+            let (elem_type, tail) = parse_jni_type(&sig[1..])?;
+            return Some((format!("{}[]", elem_type), tail));
+        }
+        'L' => {
+            let end = sig.find(';')?;
+            return Some((sig[1..end].replace('/', "."), &sig[end + 1..]));
+        }
+        _ => return None,
+    };
+    Some((java_type.into(), chars.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jni_sig_to_proguard() {
+        assert_eq!(
+            Some("long mNativeObj;".to_string()),
+            jni_field_to_proguard("mNativeObj", "J")
+        );
+        assert_eq!(
+            Some("org.example.MyEnum ITEM1;".to_string()),
+            jni_field_to_proguard("ITEM1", "Lorg/example/MyEnum;")
+        );
+        assert_eq!(
+            Some("<init>(org.example.InternalPointerMarker, long);".to_string()),
+            jni_method_to_proguard("<init>", "(Lorg/example/InternalPointerMarker;J)V")
+        );
+        assert_eq!(
+            Some("java.lang.String[] f(int[][], boolean);".to_string()),
+            jni_method_to_proguard("f", "([[IZ)[Ljava/lang/String;")
+        );
+        assert_eq!(None, jni_method_to_proguard("f", "(I"));
+        assert_eq!(None, jni_field_to_proguard("x", "Ljava/lang/String"));
+    }
+}
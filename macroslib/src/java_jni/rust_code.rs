@@ -1,3 +1,4 @@
+use proc_macro2::Span;
 use quote::quote;
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
@@ -6,25 +7,28 @@ use syn::{parse_quote, visit::Visit};
 
 use super::{
     find_cache::{JniCacheMacroCalls, JniCacheMacroCallsVisitor},
+    java_class_full_name, java_class_name_to_jni,
     java_code::filter_null_annotation,
-    JavaContext, JniForeignMethodSignature,
+    JavaContext, JniForeignMethodSignature, JniNativeMethod,
 };
 use crate::{
     error::{invalid_src_id_span, panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
     typemap::ast::DisplayToTokens,
-    types::MethodVariant,
+    types::{ForeignLifecycle, MethodVariant},
     WRITE_TO_MEM_FAILED_MSG,
 };
 
 pub(in crate::java_jni) fn predefined_java_type_to_jni_sig() -> FxHashMap<SmolStr, SmolStr> {
     let mut m = FxHashMap::default();
     m.insert("String".into(), "Ljava.lang.String;".into());
-    m.insert("Byte".into(), "Ljava.lang.Byte".into());
-    m.insert("Short".into(), "Ljava.lang.Short".into());
-    m.insert("Integer".into(), "Ljava.lang.Integer".into());
-    m.insert("Long".into(), "Ljava.lang.Long".into());
-    m.insert("Float".into(), "Ljava.lang.Float".into());
-    m.insert("Double".into(), "Ljava.lang.Double".into());
+    m.insert("String []".into(), "[Ljava.lang.String;".into());
+    m.insert("Object".into(), "Ljava.lang.Object;".into());
+    m.insert("Byte".into(), "Ljava.lang.Byte;".into());
+    m.insert("Short".into(), "Ljava.lang.Short;".into());
+    m.insert("Integer".into(), "Ljava.lang.Integer;".into());
+    m.insert("Long".into(), "Ljava.lang.Long;".into());
+    m.insert("Float".into(), "Ljava.lang.Float;".into());
+    m.insert("Double".into(), "Ljava.lang.Double;".into());
     m.insert("boolean".into(), "Z".into());
     m.insert("byte".into(), "B".into());
     m.insert("char".into(), "C".into());
@@ -76,6 +80,7 @@ pub(in crate::java_jni) fn generate_jni_func_name(
                 '[' => output.push_str("_3"),
                 '_' => output.push_str("_1"),
                 ';' => output.push_str("_2"),
+                '$' => output.push_str("_00024"),
                 _ => output.push(c),
             }
         }
@@ -121,34 +126,202 @@ pub(in crate::java_jni) fn jni_method_signature(
     ctx: &JavaContext,
     method: &JniForeignMethodSignature,
 ) -> String {
-    let mut ret: String = "(".into();
-    for arg in &method.input {
-        let java_type: String = filter_null_annotation(arg.as_ref().name.as_str())
-            .trim()
-            .into();
-        let sig = java_type_to_jni_signature(ctx, &java_type).unwrap_or_else(|| {
+    let to_sig = |java_type: &str| {
+        java_type_name_to_jni_signature(ctx, java_type).unwrap_or_else(|| {
             panic!(
                 "Unknown type `{}`, can not generate JNI signature",
                 java_type
             )
-        });
-        let sig = sig.replace('.', "/");
-        ret.push_str(&sig);
+        })
+    };
+    let mut ret: String = "(".into();
+    for arg in &method.input {
+        ret.push_str(&to_sig(arg.as_ref().name.as_str()));
     }
     ret.push(')');
-    let sig =
-        java_type_to_jni_signature(ctx, method.output.base.name.as_str()).unwrap_or_else(|| {
-            panic!(
-                "Unknown type `{}`, can not generate JNI signature",
-                method.output.base.name
+    ret.push_str(&to_sig(method.output.base.name.as_str()));
+    ret
+}
+
+/// How to declare Rust function that implements Java native method
+pub(in crate::java_jni) fn jni_func_linkage(ctx: &JavaContext) -> &'static str {
+    if ctx.cfg.register_natives {
+        "extern \"C\""
+    } else {
+        "#[no_mangle]\npub extern \"C\""
+    }
+}
+
+/// Remember Java native method, to register it later via `RegisterNatives`
+pub(in crate::java_jni) fn add_native_method(
+    ctx: &mut JavaContext,
+    class_name: &str,
+    java_method_name: &str,
+    method_type: MethodVariant,
+    f_method: &JniForeignMethodSignature,
+    use_transition_types: bool,
+    rust_func_name: &str,
+) {
+    let java_type = |fti: &super::JavaForeignTypeInfo| -> SmolStr {
+        match fti.java_converter {
+            Some(ref conv) if use_transition_types => conv.java_transition_type.clone(),
+            _ => fti.base.name.clone(),
+        }
+    };
+    let mut input = Vec::with_capacity(f_method.input.len() + 1);
+    if let MethodVariant::Method(_) = method_type {
+        input.push("long".into());
+    }
+    input.extend(f_method.input.iter().map(java_type));
+    let output = match method_type {
+        MethodVariant::Constructor => "long".into(),
+        MethodVariant::Method(_) | MethodVariant::StaticMethod => java_type(&f_method.output),
+    };
+    ctx.native_methods.push(JniNativeMethod {
+        class_name: class_name.into(),
+        java_method_name: java_method_name.into(),
+        input,
+        output,
+        rust_func_name: rust_func_name.into(),
+    });
+}
+
+/// Convert Java type, as it written in Java code, to JNI signature
+pub(in crate::java_jni) fn java_type_name_to_jni_signature(
+    ctx: &JavaContext,
+    java_type: &str,
+) -> Option<String> {
+    let mut without_comments = String::with_capacity(java_type.len());
+    let mut tail = java_type;
+    while let Some(pos) = tail.find("/*") {
+        without_comments.push_str(&tail[..pos]);
+        tail = &tail[pos..];
+        let end = tail.find("*/")?;
+        tail = &tail[end + 2..];
+    }
+    without_comments.push_str(tail);
+    let java_type = filter_null_annotation(&without_comments);
+    let java_type = java_type.trim();
+    if let Some(elem_type) = java_type.strip_suffix("[]") {
+        let elem_sig = java_type_name_to_jni_signature(ctx, elem_type)?;
+        return Some(format!("[{}", elem_sig));
+    }
+    // type erasure
+    let java_type = match java_type.find('<') {
+        Some(pos) => java_type[..pos].trim(),
+        None => java_type,
+    };
+    if let Some(sig) = java_type_to_jni_signature(ctx, java_type) {
+        Some(sig.replace('.', "/"))
+    } else if java_type.contains('.') {
+        Some(format!("L{};", java_class_name_to_jni(java_type)))
+    } else {
+        None
+    }
+}
+
+fn native_method_jni_signature(ctx: &JavaContext, method: &JniNativeMethod) -> Result<String> {
+    let to_sig = |java_type: &str| {
+        java_type_name_to_jni_signature(ctx, java_type).ok_or_else(|| {
+            DiagnosticError::new2(
+                invalid_src_id_span(),
+                format!(
+                    "Can not register native method {}.{}: unknown java type '{}'",
+                    method.class_name, method.java_method_name, java_type
+                ),
             )
+        })
+    };
+    let mut ret: String = "(".into();
+    for arg in &method.input {
+        ret.push_str(&to_sig(arg)?);
+    }
+    ret.push(')');
+    ret.push_str(&to_sig(&method.output)?);
+    Ok(ret)
+}
+
+/// Generate `swig_jni_register_natives` that binds all Java native methods
+/// to Rust functions, it should be called from `JNI_OnLoad`
+pub(in crate::java_jni) fn generate_register_natives_func(ctx: &mut JavaContext) -> Result<()> {
+    let mut classes = Vec::<(&str, Vec<&JniNativeMethod>)>::new();
+    for m in &ctx.native_methods {
+        match classes.iter_mut().find(|x| x.0 == m.class_name) {
+            Some((_, methods)) => methods.push(m),
+            None => classes.push((m.class_name.as_str(), vec![m])),
+        }
+    }
+
+    let mut register_calls = Vec::with_capacity(classes.len());
+    for (class_name, methods) in classes {
+        let class_path =
+            java_class_name_to_jni(&java_class_full_name(&ctx.cfg.package_name, class_name));
+        let mut natives = Vec::with_capacity(methods.len());
+        for m in methods {
+            let method_name = &m.java_method_name;
+            let method_sig = native_method_jni_signature(ctx, m)?;
+            let func_name = syn::Ident::new(&m.rust_func_name, Span::call_site());
+            natives.push(quote! {
+                JNINativeMethod {
+                    name: swig_c_str!(#method_name) as *mut ::std::os::raw::c_char,
+                    signature: swig_c_str!(#method_sig) as *mut ::std::os::raw::c_char,
+                    fnPtr: #func_name as *mut ::std::os::raw::c_void,
+                }
+            });
+        }
+        register_calls.push(quote! {
+            unsafe {
+                let class = (**env).FindClass.unwrap()(env, swig_c_str!(#class_path));
+                assert!(!class.is_null(), concat!("FindClass failed for ", #class_path));
+                let methods = [#(#natives),*];
+                let res = (**env).RegisterNatives.unwrap()(
+                    env,
+                    class,
+                    methods.as_ptr(),
+                    methods.len() as jint,
+                );
+                assert!(res == (JNI_OK as jint), concat!("RegisterNatives failed for ", #class_path));
+                (**env).DeleteLocalRef.unwrap()(env, class);
+            }
         });
-    ret.push_str(sig);
-    ret
+    }
+    ctx.rust_code.push(quote! {
+        fn swig_jni_register_natives(env: *mut JNIEnv) {
+            #(#register_calls)*
+        }
+    });
+    Ok(())
+}
+
+/// Names of functions that call `foreign_lifecycle!` hooks
+const ON_LOAD_HOOK: &str = "swig_jni_on_load";
+const ON_UNLOAD_HOOK: &str = "swig_jni_on_unload";
+
+/// Generate functions that call user's hooks, they are called from `JNI_OnLoad`
+/// after caching of classes and from `JNI_OnUnload` before release of cache
+pub(in crate::java_jni) fn generate_lifecycle_hooks(
+    ctx: &mut JavaContext,
+    lifecycle: &ForeignLifecycle,
+) {
+    let hooks = [
+        (ON_LOAD_HOOK, lifecycle.on_load.as_ref()),
+        (ON_UNLOAD_HOOK, lifecycle.on_unload.as_ref()),
+    ];
+    for (hook_name, hook) in &hooks {
+        if let Some(hook) = hook {
+            let hook_name = syn::Ident::new(hook_name, Span::call_site());
+            ctx.rust_code.push(quote! {
+                fn #hook_name() {
+                    #hook();
+                }
+            });
+        }
+    }
 }
 
 pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
     generated_code: &mut Vec<u8>,
+    register_natives: bool,
 ) -> Result<()> {
     let code = str::from_utf8(&generated_code).map_err(|err| {
         DiagnosticError::new2(
@@ -169,6 +342,23 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
         panic_on_syn_error("generated code", code.to_string(), visitor.errors.remove(0));
     }
 
+    let has_hook = |hook_name: &str| {
+        file.items.iter().any(|item| match item {
+            syn::Item::Fn(f) => f.sig.ident == hook_name,
+            _ => false,
+        })
+    };
+    let on_load_call = if has_hook(ON_LOAD_HOOK) {
+        quote!(swig_jni_on_load();)
+    } else {
+        quote!()
+    };
+    let on_unload_call = if has_hook(ON_UNLOAD_HOOK) {
+        quote!(swig_jni_on_unload();)
+    } else {
+        quote!()
+    };
+
     let mut addon_code = Vec::with_capacity(3);
 
     addon_code.append(&mut jni_cache_macro_calls.global_vars());
@@ -274,10 +464,16 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
         });
     }
 
+    let register_natives_call = if register_natives {
+        quote!(swig_jni_register_natives(env);)
+    } else {
+        quote!()
+    };
+
     let jni_load_func: syn::Item = parse_quote! {
         #[no_mangle]
         pub extern "system" fn JNI_OnLoad(java_vm: *mut JavaVM, _reserved: *mut ::std::ffi::c_void) -> jint {
-            println!("JNI_OnLoad begin");
+            log::debug!("JNI_OnLoad begin");
             assert!(!java_vm.is_null());
             let mut env: *mut JNIEnv = ::std::ptr::null_mut();
             let res = unsafe {
@@ -292,6 +488,8 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
             }
             assert!(!env.is_null());
             #(#find_calls)*
+            #register_natives_call
+            #on_load_call
 
             SWIG_JNI_VERSION
         }
@@ -300,7 +498,7 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
     let jni_unload_func: syn::Item = parse_quote! {
         #[no_mangle]
         pub extern "system" fn JNI_OnUnload(java_vm: *mut JavaVM, _reserved: *mut ::std::ffi::c_void) {
-            println!("JNI_OnUnLoad begin");
+            log::debug!("JNI_OnUnload begin");
             assert!(!java_vm.is_null());
             let mut env: *mut JNIEnv = ::std::ptr::null_mut();
             let res = unsafe {
@@ -314,6 +512,7 @@ pub(in crate::java_jni) fn generate_load_unload_jni_funcs(
                 panic!("JNI GetEnv in JNI_OnLoad failed, return code {}", res);
             }
             assert!(!env.is_null());
+            #on_unload_call
             #(#free_find_calls)*
         }
     };
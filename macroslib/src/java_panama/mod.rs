@@ -0,0 +1,886 @@
+//! Java backend on top of Foreign Function and Memory API (`java.lang.foreign`).
+//! Rust code exports plain C ABI functions, and Java code calls them
+//! via `MethodHandle`s created by `Linker`, so no JNI code is involved.
+
+use log::debug;
+use proc_macro2::TokenStream;
+use quote::quote;
+use rustc_hash::FxHashSet;
+use std::{io::Write, path::PathBuf, rc::Rc};
+use syn::{parse_str, Ident};
+
+use crate::{
+    c_abi::{
+        classes,
+        map_type::{self, ArgName, CAbiArgInfo, CAbiForeignMethodSignature},
+        CAbiContext,
+    },
+    doc_comments::{exported_items, DocComment, ExportedItems},
+    error::{invalid_src_id_span, DiagnosticError, Result, ResultSynDiagnostic},
+    file_cache::FileWriteCache,
+    typemap::{
+        ast::TypeName,
+        ty::{ForeignConversationIntermediate, ForeignConversationRule, ForeignTypeS},
+        utils::{remove_files_if, rust_code_for_bitflags, rust_code_for_bitflags_item_bits},
+        TypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    types::{
+        ForeignClassInfo, ForeignEnumInfo, ForeignLifecycle, ForeignMethod, ItemToExpand,
+        MethodAccess, MethodVariant,
+    },
+    ExtHandlers, JavaPanamaConfig, LanguageGenerator, SourceCode, TypeMap, WRITE_TO_MEM_FAILED_MSG,
+};
+
+/// Package private class with common code, like loading of native library
+const RUST_LIB_CLASS: &str = "RustLib";
+const ON_LOAD_FUNC: &str = "panama_lifecycle_on_load";
+const JAVA_IMPORTS: &str = r#"import java.lang.foreign.Arena;
+import java.lang.foreign.FunctionDescriptor;
+import java.lang.foreign.MemorySegment;
+import java.lang.foreign.ValueLayout;
+import java.lang.invoke.MethodHandle;
+import java.lang.ref.Reference;
+
+"#;
+
+struct JavaPanamaGenerator<'a> {
+    cfg: &'a JavaPanamaConfig,
+    ctx: CAbiContext<'a>,
+    generated_foreign_files: FxHashSet<PathBuf>,
+    has_on_load_hook: bool,
+    exported_items: ExportedItems,
+}
+
+impl<'a> JavaPanamaGenerator<'a> {
+    fn generate(
+        mut self,
+        items: Vec<ItemToExpand>,
+    ) -> Result<(Vec<TokenStream>, FxHashSet<PathBuf>)> {
+        for item in &items {
+            match item {
+                ItemToExpand::Class(fclass) => {
+                    classes::register_class(self.ctx.conv_map, fclass, "MemorySegment")?
+                }
+                ItemToExpand::Enum(fenum) => self.generate_enum(fenum)?,
+                ItemToExpand::Lifecycle(lifecycle) => self.generate_lifecycle(lifecycle)?,
+                ItemToExpand::Interface(finterface) => {
+                    return Err(DiagnosticError::new2(
+                        finterface.src_id_span(),
+                        "foreign_callback is not supported yet for Java Panama",
+                    ));
+                }
+            }
+        }
+        for item in &items {
+            if let ItemToExpand::Class(fclass) = item {
+                self.generate_class(fclass)?;
+            }
+        }
+        self.generate_rust_lib_class()?;
+        for (module_name, java_code) in std::mem::take(&mut self.ctx.additional_foreign_code) {
+            self.write_java_file(&module_name, JAVA_IMPORTS, &java_code)?;
+        }
+        Ok((self.ctx.rust_code, self.generated_foreign_files))
+    }
+
+    fn generate_enum(&mut self, fenum: &ForeignEnumInfo) -> Result<()> {
+        if (fenum.items.len() as u64) >= (i32::MAX as u64) {
+            return Err(DiagnosticError::new(
+                fenum.src_id,
+                fenum.span(),
+                "Too many items in enum",
+            ));
+        }
+        let enum_name = &fenum.name;
+        if fenum.is_bitflags {
+            return self.generate_bitflags(fenum);
+        }
+        let mut java_code = format!(
+            "{doc_comments}public enum {enum_name} {{\n",
+            doc_comments =
+                doc_comments_to_java_comments(&fenum.doc_comments, "", &self.exported_items),
+            enum_name = enum_name,
+        );
+        for (i, item) in fenum.items.iter().enumerate() {
+            java_code.push_str(&doc_comments_to_java_comments(
+                &item.doc_comments,
+                "    ",
+                &self.exported_items,
+            ));
+            java_code.push_str(&format!(
+                "    {item_name}({index}){separator}\n",
+                item_name = item.name,
+                index = item.value,
+                separator = if i == fenum.items.len() - 1 { ';' } else { ',' },
+            ));
+        }
+        java_code.push_str(&format!(
+            r#"
+    private final int value;
+    {enum_name}(int value) {{
+        this.value = value;
+    }}
+    public final int getValue() {{ return value; }}
+    /*package*/ static {enum_name} fromInt(int x) {{
+        switch (x) {{
+"#,
+            enum_name = enum_name
+        ));
+        for item in &fenum.items {
+            java_code.push_str(&format!(
+                "            case {index}: return {item_name};\n",
+                index = item.value,
+                item_name = item.name
+            ));
+        }
+        java_code.push_str(&format!(
+            r#"            default: throw new Error("Invalid value for enum {enum_name}: " + x);
+        }}
+    }}
+}}
+"#,
+            enum_name = enum_name
+        ));
+        self.write_java_file(&enum_name.to_string(), "", &java_code)?;
+
+        self.register_enum_type(
+            fenum,
+            enum_name.to_string(),
+            format!("{}.fromInt({})", enum_name, FROM_VAR_TEMPLATE),
+            format!("{}.getValue()", FROM_VAR_TEMPLATE),
+            format!(
+                r#"let {to_var} = match <{enum_name} as SwigForeignEnum>::from_i32({from_var}) {{
+    Some(x) => x,
+    None => {{
+        panama_set_last_error(&format!("{{}} not expected for {enum_name}", {from_var}));
+        return PanamaInvalidValue::panama_invalid_value();
+    }}
+}};"#,
+                to_var = TO_VAR_TEMPLATE,
+                enum_name = enum_name,
+                from_var = FROM_VAR_TEMPLATE,
+            ),
+        )?;
+
+        let (arms_to_i32, arms_from_i32): (Vec<_>, Vec<_>) = fenum
+            .items
+            .iter()
+            .map(|item| {
+                let item_name = &item.rust_name;
+                let idx = item.value as i32;
+                (
+                    quote! { #item_name => #idx },
+                    quote! { #idx => Some(#item_name) },
+                )
+            })
+            .unzip();
+        self.ctx.rust_code.push(quote! {
+            impl SwigForeignEnum for #enum_name {
+                fn as_i32(&self) -> i32 {
+                    match *self {
+                        #(#arms_to_i32),*
+                    }
+                }
+                fn from_i32(x: i32) -> Option<Self> {
+                    match x {
+                        #(#arms_from_i32),*
+                        ,
+                        _ => None,
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Bits of flags are passed as `i32`, and on Java side they are `EnumSet` of enum items,
+    /// bits of items are asked from Rust during class initialization
+    fn generate_bitflags(&mut self, fenum: &ForeignEnumInfo) -> Result<()> {
+        let flags_name = &fenum.name;
+        let item_bits_func = format!("{}_item_bits", flags_name);
+        let mut java_code = format!(
+            "{doc_comments}public enum {flags_name} {{\n",
+            doc_comments =
+                doc_comments_to_java_comments(&fenum.doc_comments, "", &self.exported_items),
+            flags_name = flags_name,
+        );
+        for (i, item) in fenum.items.iter().enumerate() {
+            java_code.push_str(&doc_comments_to_java_comments(
+                &item.doc_comments,
+                "    ",
+                &self.exported_items,
+            ));
+            java_code.push_str(&format!(
+                "    {item_name}{separator}\n",
+                item_name = item.name,
+                separator = if i == fenum.items.len() - 1 { ';' } else { ',' },
+            ));
+        }
+        if fenum.items.is_empty() {
+            java_code.push_str("    ;\n");
+        }
+        java_code.push_str(&format!(
+            r#"
+    private static final MethodHandle {item_bits_func} = {rust_lib}.downcall("{item_bits_func}",
+        FunctionDescriptor.of(ValueLayout.JAVA_INT, ValueLayout.JAVA_INT));
+    private static final int[] BITS;
+    private static final int ALL_BITS;
+    static {{
+        {flags_name}[] flags = values();
+        BITS = new int[flags.length];
+        int allBits = 0;
+        try {{
+            for (int i = 0; i < flags.length; ++i) {{
+                BITS[i] = (int) {item_bits_func}.invokeExact(i);
+                allBits |= BITS[i];
+            }}
+        }} catch (Throwable ex) {{
+            throw {rust_lib}.rethrow(ex);
+        }}
+        ALL_BITS = allBits;
+    }}
+
+    /** Bits of this flag, the same as Rust uses */
+    public final int bits() {{ return BITS[ordinal()]; }}
+
+    /*package*/ static int toBits(java.util.Set<{flags_name}> flags) {{
+        int bits = 0;
+        for ({flags_name} flag : flags) {{
+            bits |= flag.bits();
+        }}
+        return bits;
+    }}
+    /*package*/ static java.util.EnumSet<{flags_name}> fromBits(int bits) {{
+        if ((bits & ~ALL_BITS) != 0) {{
+            throw new IllegalArgumentException("Invalid bits for {flags_name}: " + bits);
+        }}
+        java.util.EnumSet<{flags_name}> flags = java.util.EnumSet.noneOf({flags_name}.class);
+        for ({flags_name} flag : values()) {{
+            if (flag.bits() != 0 && (bits & flag.bits()) == flag.bits()) {{
+                flags.add(flag);
+            }}
+        }}
+        return flags;
+    }}
+}}
+"#,
+            flags_name = flags_name,
+            item_bits_func = item_bits_func,
+            rust_lib = RUST_LIB_CLASS,
+        ));
+        self.write_java_file(&flags_name.to_string(), JAVA_IMPORTS, &java_code)?;
+        self.register_enum_type(
+            fenum,
+            format!("java.util.EnumSet<{}>", flags_name),
+            format!("{}.fromBits({})", flags_name, FROM_VAR_TEMPLATE),
+            format!("{}.toBits({})", flags_name, FROM_VAR_TEMPLATE),
+            format!(
+                r#"let {to_var} = match <{flags_name} as SwigForeignBitflags>::from_i32({from_var}) {{
+    Some(x) => x,
+    None => {{
+        panama_set_last_error(&format!("{{}} not expected for {flags_name}", {from_var}));
+        return PanamaInvalidValue::panama_invalid_value();
+    }}
+}};"#,
+                to_var = TO_VAR_TEMPLATE,
+                flags_name = flags_name,
+                from_var = FROM_VAR_TEMPLATE,
+            ),
+        )?;
+        self.ctx.rust_code.push(rust_code_for_bitflags(fenum));
+        self.ctx
+            .rust_code
+            .push(rust_code_for_bitflags_item_bits(fenum, &item_bits_func));
+        Ok(())
+    }
+
+    /// Register Java type for enum, `i32` is used to pass it through C ABI,
+    /// `from_i32_code` converts `i32` to Rust type
+    fn register_enum_type(
+        &mut self,
+        fenum: &ForeignEnumInfo,
+        foreign_name: String,
+        into_conv: String,
+        from_conv: String,
+        from_i32_code: String,
+    ) -> Result<()> {
+        let enum_name = &fenum.name;
+        let span = fenum.span();
+        let enum_rty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type(&parse_type_spanned_checked!(span, #enum_name), fenum.src_id);
+        let i32_rty = self
+            .ctx
+            .conv_map
+            .find_or_alloc_rust_type_no_src_id(&parse_type! { i32 });
+        let enum_conv = |conv_code: String| {
+            Some(ForeignConversationRule {
+                rust_ty: enum_rty.to_idx(),
+                intermediate: Some(ForeignConversationIntermediate {
+                    input_to_output: false,
+                    intermediate_ty: i32_rty.to_idx(),
+                    conv_code: Rc::new(TypeConvCode::new(conv_code, invalid_src_id_span())),
+                }),
+            })
+        };
+        self.ctx.conv_map.alloc_foreign_type(ForeignTypeS {
+            name: TypeName::new(foreign_name, (fenum.src_id, fenum.name.span())),
+            provides_by_module: vec![],
+            into_from_rust: enum_conv(into_conv),
+            from_into_rust: enum_conv(from_conv),
+            name_prefix: None,
+        })?;
+        self.ctx.conv_map.add_conversation_rule(
+            i32_rty.to_idx(),
+            enum_rty.to_idx(),
+            TypeConvCode::new2(from_i32_code, invalid_src_id_span()).into(),
+        );
+        self.ctx.conv_map.add_conversation_rule(
+            enum_rty.to_idx(),
+            i32_rty.to_idx(),
+            TypeConvCode::new2(
+                format!("let {} = {}.as_i32();", TO_VAR_TEMPLATE, FROM_VAR_TEMPLATE),
+                invalid_src_id_span(),
+            )
+            .into(),
+        );
+        Ok(())
+    }
+
+    fn generate_lifecycle(&mut self, lifecycle: &ForeignLifecycle) -> Result<()> {
+        if lifecycle.on_unload.is_some() {
+            return Err(DiagnosticError::new2(
+                lifecycle.src_id_span(),
+                "on_unload is not supported for Java Panama",
+            ));
+        }
+        if let Some(on_load) = lifecycle.on_load.as_ref() {
+            let func_name = Ident::new(ON_LOAD_FUNC, proc_macro2::Span::call_site());
+            self.ctx.rust_code.push(quote! {
+                #[no_mangle]
+                pub extern "C" fn #func_name() {
+                    #on_load();
+                }
+            });
+            self.has_on_load_hook = true;
+        }
+        Ok(())
+    }
+
+    fn generate_class(&mut self, class: &ForeignClassInfo) -> Result<()> {
+        let class_name = class.name.to_string();
+        let mut java_code = format!(
+            "{doc_comments}public final class {class_name}",
+            doc_comments =
+                doc_comments_to_java_comments(&class.doc_comments, "", &self.exported_items),
+            class_name = class_name,
+        );
+        if let Some(self_desc) = class.self_desc.as_ref() {
+            let storage_type = self
+                .ctx
+                .conv_map
+                .find_or_alloc_rust_type(&self_desc.constructor_ret_type, class.src_id);
+            let intermediate_ptr_ty =
+                classes::SmartPointerType::new(&storage_type, self.ctx.conv_map, class.src_id)
+                    .intermediate_ptr_ty(&self_desc.constructor_ret_type);
+            let destructor_name = format!("{}_delete", class_name);
+            let destructor_ident = Ident::new(&destructor_name, class.name.span());
+            self.ctx.rust_code.push(quote! {
+                #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+                #[no_mangle]
+                pub unsafe extern "C" fn #destructor_ident(this: #intermediate_ptr_ty) {
+                    ::std::mem::drop(Box::from_raw(this))
+                }
+            });
+            java_code.push_str(&format!(
+                r#" implements AutoCloseable {{
+    /*package*/ final MemorySegment nativePtr;
+    private final java.lang.ref.Cleaner.Cleanable cleanable;
+
+    /*package*/ {class_name}(MemorySegment rawPtr) {{
+        Arena arena = Arena.ofShared();
+        this.nativePtr = rawPtr.reinterpret(arena, {class_name}::delete);
+        this.cleanable = {rust_lib}.CLEANER.register(this, arena::close);
+    }}
+
+    /**
+     * Free Rust object, usage of this object after call throws IllegalStateException.
+     * If it is not called, object is freed after garbage collection.
+     */
+    @Override
+    public void close() {{
+        cleanable.clean();
+    }}
+
+    private static final MethodHandle {destructor_name} = {rust_lib}.downcall("{destructor_name}",
+        FunctionDescriptor.ofVoid(ValueLayout.ADDRESS));
+
+    private static void delete(MemorySegment ptr) {{
+        try {{
+            {destructor_name}.invokeExact(ptr);
+        }} catch (Throwable ex) {{
+            throw {rust_lib}.rethrow(ex);
+        }}
+    }}
+"#,
+                class_name = class_name,
+                destructor_name = destructor_name,
+                rust_lib = RUST_LIB_CLASS,
+            ));
+        } else {
+            java_code.push_str(&format!(
+                " {{\n    private {class_name}() {{}}\n",
+                class_name = class_name
+            ));
+        }
+
+        for method in &class.methods {
+            if method.is_dummy_constructor() {
+                continue;
+            }
+            let f_method = map_type::make_foreign_method_signature(&mut self.ctx, class, method)?;
+            self.write_rust_glue_code(class, &f_method)?;
+            java_code.push_str(&generate_java_method(
+                class,
+                method,
+                &f_method,
+                &self.exported_items,
+            )?);
+        }
+        if !class.foreign_code.is_empty() {
+            java_code.push_str(&class.foreign_code);
+            if !class.foreign_code.ends_with('\n') {
+                java_code.push('\n');
+            }
+        }
+        java_code.push_str("}\n");
+        self.write_java_file(&class_name, JAVA_IMPORTS, &java_code)
+    }
+
+    fn write_rust_glue_code(
+        &mut self,
+        class: &ForeignClassInfo,
+        f_method: &CAbiForeignMethodSignature,
+    ) -> Result<()> {
+        let mut convert_input_code = String::new();
+        for arg in &f_method.input {
+            let (mut deps, conv_code) = arg.rust_conversion_code(self.ctx.conv_map)?;
+            self.ctx.rust_code.append(&mut deps);
+            convert_input_code.push_str(&conv_code);
+        }
+        let func_args = f_method
+            .input
+            .iter()
+            .map(|arg| {
+                format!(
+                    "{}: {}",
+                    arg.arg_name.rust_variable_name(),
+                    arg.type_info.rust_intermediate_type
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (mut deps, convert_output_code) =
+            f_method.output.rust_conversion_code(self.ctx.conv_map)?;
+        self.ctx.rust_code.append(&mut deps);
+
+        let rust_code = format!(
+            r#"
+    #[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+    #[no_mangle]
+    pub extern "C" fn {func_name}({func_args}) -> {ret_type} {{
+        {convert_input_code}
+        let mut {ret_name}: {rust_ret_type} = {call};
+        {convert_output_code}
+        {ret_name}
+    }}
+"#,
+            func_name = c_func_name(class, f_method),
+            func_args = func_args,
+            ret_type = f_method.output.type_info.rust_intermediate_type,
+            rust_ret_type = f_method.output.type_info.rust_type,
+            convert_input_code = convert_input_code,
+            ret_name = f_method.output.arg_name.rust_variable_name(),
+            call = f_method.rust_function_call,
+            convert_output_code = convert_output_code,
+        );
+        debug!("write_rust_glue_code: {}", rust_code);
+        self.ctx
+            .rust_code
+            .push(parse_str(&rust_code).with_syn_src_id(class.src_id)?);
+        Ok(())
+    }
+
+    fn generate_rust_lib_class(&mut self) -> Result<()> {
+        let on_load_call = if self.has_on_load_hook {
+            format!(
+                r#"
+    static {{
+        try {{
+            downcall("{on_load_func}", FunctionDescriptor.ofVoid()).invokeExact();
+        }} catch (Throwable ex) {{
+            throw rethrow(ex);
+        }}
+    }}
+"#,
+                on_load_func = ON_LOAD_FUNC
+            )
+        } else {
+            String::new()
+        };
+        let java_code = format!(
+            r#"/*package*/ final class {class_name} {{
+    static {{
+        System.loadLibrary("{native_lib_name}");
+    }}
+    private static final java.lang.foreign.Linker LINKER = java.lang.foreign.Linker.nativeLinker();
+    private static final java.lang.foreign.SymbolLookup SYMBOLS = java.lang.foreign.SymbolLookup.loaderLookup();
+    /*package*/ static final java.lang.ref.Cleaner CLEANER = java.lang.ref.Cleaner.create();
+    private static final MethodHandle C_STRING_DELETE = downcall("panama_c_string_delete",
+        FunctionDescriptor.ofVoid(ValueLayout.ADDRESS));
+    private static final MethodHandle TAKE_LAST_ERROR = downcall("panama_take_last_error",
+        FunctionDescriptor.of(ValueLayout.ADDRESS));
+{on_load_call}
+    private {class_name}() {{}}
+
+    /*package*/ static MethodHandle downcall(String name, FunctionDescriptor descriptor) {{
+        MemorySegment symbol = SYMBOLS.find(name)
+            .orElseThrow(() -> new UnsatisfiedLinkError("Can not find symbol " + name));
+        return LINKER.downcallHandle(symbol, descriptor);
+    }}
+
+    /*package*/ static String stringFromRust(MemorySegment cStr) {{
+        String str = cStr.reinterpret(Long.MAX_VALUE).getString(0);
+        try {{
+            C_STRING_DELETE.invokeExact(cStr);
+        }} catch (Throwable ex) {{
+            throw rethrow(ex);
+        }}
+        return str;
+    }}
+
+    /**
+     * Throws RuntimeException if last native call in this thread
+     * reported error, for example integer value out of range
+     */
+    /*package*/ static void checkError() {{
+        MemorySegment error;
+        try {{
+            error = (MemorySegment) TAKE_LAST_ERROR.invokeExact();
+        }} catch (Throwable ex) {{
+            throw rethrow(ex);
+        }}
+        if (!error.equals(MemorySegment.NULL)) {{
+            throw new RuntimeException(stringFromRust(error));
+        }}
+    }}
+
+    /*package*/ static RuntimeException rethrow(Throwable ex) {{
+        if (ex instanceof RuntimeException) {{
+            return (RuntimeException) ex;
+        }}
+        if (ex instanceof Error) {{
+            throw (Error) ex;
+        }}
+        return new RuntimeException(ex);
+    }}
+}}
+"#,
+            class_name = RUST_LIB_CLASS,
+            native_lib_name = self.cfg.native_lib_name,
+            on_load_call = on_load_call,
+        );
+        self.write_java_file(RUST_LIB_CLASS, JAVA_IMPORTS, &java_code)
+    }
+
+    fn write_java_file(&mut self, class_name: &str, imports: &str, java_code: &str) -> Result<()> {
+        let path = self.cfg.output_dir.join(format!("{}.java", class_name));
+        let mut file = FileWriteCache::new(&path, &mut self.generated_foreign_files);
+        write!(
+            file,
+            "// Automatically generated by flapigen\npackage {package_name};\n\n{imports}{code}",
+            package_name = self.cfg.package_name,
+            imports = imports,
+            code = java_code,
+        )
+        .expect(WRITE_TO_MEM_FAILED_MSG);
+        file.update_file_if_necessary()
+            .map_err(DiagnosticError::map_any_err_to_our_err)
+    }
+}
+
+impl ArgName {
+    fn java_variable_name(&self) -> &str {
+        match self {
+            ArgName::SelfArg => "this",
+            ArgName::Return => "ret",
+            ArgName::Named(name) => name,
+        }
+    }
+}
+
+fn c_func_name(class: &ForeignClassInfo, f_method: &CAbiForeignMethodSignature) -> String {
+    format!("{}_{}", class.name, f_method.name)
+}
+
+/// Name of Java type without comments, like `long` for `/* u32 */ long`
+fn java_type_without_comments(java_type: &str) -> &str {
+    match java_type.rfind("*/") {
+        Some(pos) => java_type[pos + 2..].trim(),
+        None => java_type.trim(),
+    }
+}
+
+fn java_type_to_value_layout(
+    java_type: &str,
+    span: crate::error::SourceIdSpan,
+) -> Result<&'static str> {
+    match java_type_without_comments(java_type) {
+        "byte" => Ok("ValueLayout.JAVA_BYTE"),
+        "short" => Ok("ValueLayout.JAVA_SHORT"),
+        "int" => Ok("ValueLayout.JAVA_INT"),
+        "long" => Ok("ValueLayout.JAVA_LONG"),
+        "float" => Ok("ValueLayout.JAVA_FLOAT"),
+        "double" => Ok("ValueLayout.JAVA_DOUBLE"),
+        "MemorySegment" => Ok("ValueLayout.ADDRESS"),
+        other => Err(DiagnosticError::new2(
+            span,
+            format!(
+                "Java type '{}' can not be passed to/from native code, \
+                 intermediate type should be primitive or MemorySegment",
+                other
+            ),
+        )),
+    }
+}
+
+fn generate_java_method(
+    class: &ForeignClassInfo,
+    method: &ForeignMethod,
+    f_method: &CAbiForeignMethodSignature,
+    exported_items: &ExportedItems,
+) -> Result<String> {
+    let c_func_name = c_func_name(class, f_method);
+    let output = &f_method.output;
+    let ret_java_type = java_type_without_comments(&output.type_info.foreign_intermediate_type);
+    let returns_void = ret_java_type == "void";
+    let mut arg_layouts = Vec::with_capacity(f_method.input.len());
+    for arg in &f_method.input {
+        arg_layouts.push(java_type_to_value_layout(
+            &arg.type_info.foreign_intermediate_type,
+            arg.span,
+        )?);
+    }
+    let descriptor = if returns_void {
+        format!("FunctionDescriptor.ofVoid({})", arg_layouts.join(", "))
+    } else {
+        let mut layouts = vec![java_type_to_value_layout(ret_java_type, output.span)?];
+        layouts.extend(arg_layouts);
+        format!("FunctionDescriptor.of({})", layouts.join(", "))
+    };
+
+    let mut code = format!(
+        r#"
+    private static final MethodHandle {c_func_name} = {rust_lib}.downcall("{c_func_name}",
+        {descriptor});
+"#,
+        c_func_name = c_func_name,
+        rust_lib = RUST_LIB_CLASS,
+        descriptor = descriptor,
+    );
+
+    let mut java_args = Vec::with_capacity(f_method.input.len());
+    let mut input_conv = String::new();
+    let mut call_args = Vec::with_capacity(f_method.input.len());
+    // objects whose `Arena` may be closed by `Cleaner` during the native call
+    let mut keep_alive = Vec::new();
+    for arg in &f_method.input {
+        if arg.arg_name == ArgName::SelfArg {
+            call_args.push("this.nativePtr".to_string());
+            keep_alive.push("this");
+            continue;
+        }
+        let name = arg.arg_name.java_variable_name();
+        java_args.push(format!("{} {}", arg.type_info.foreign_type, name));
+        match java_conversion_code(arg, name) {
+            Some(conv_code) => {
+                let conv_name = format!("__{}", name);
+                input_conv.push_str(&format!(
+                    "            {} {} = {};\n",
+                    java_type_without_comments(&arg.type_info.foreign_intermediate_type),
+                    conv_name,
+                    conv_code
+                ));
+                call_args.push(conv_name);
+                if conv_code.ends_with(".nativePtr") {
+                    keep_alive.push(name);
+                }
+            }
+            None => call_args.push(name.to_string()),
+        }
+    }
+    let call = format!("{}.invokeExact({})", c_func_name, call_args.join(", "));
+    let is_constructor = f_method.variant == MethodVariant::Constructor;
+    let check_error = format!("            {}.checkError();\n", RUST_LIB_CLASS);
+    let call_and_ret = if returns_void {
+        format!("            {};\n{}", call, check_error)
+    } else {
+        let (ret_type, ret_conv) = if is_constructor {
+            ("MemorySegment", "__ret".to_string())
+        } else {
+            (ret_java_type, java_ret_conversion(output, "__ret"))
+        };
+        format!(
+            "            {ret_type} __ret = ({ret_type}) {call};\n{check_error}            return {ret_conv};\n",
+            ret_type = ret_type,
+            call = call,
+            check_error = check_error,
+            ret_conv = ret_conv,
+        )
+    };
+    let body = if input_conv.contains("__arena") {
+        format!(
+            "        try (Arena __arena = Arena.ofConfined()) {{\n{}{}",
+            input_conv, call_and_ret
+        )
+    } else {
+        format!("        try {{\n{}{}", input_conv, call_and_ret)
+    };
+    let mut body = format!(
+        "{}        }} catch (Throwable __ex) {{\n            throw {}.rethrow(__ex);\n        }}",
+        body, RUST_LIB_CLASS
+    );
+    if keep_alive.is_empty() {
+        body.push('\n');
+    } else {
+        body.push_str(" finally {\n");
+        for obj in &keep_alive {
+            body.push_str(&format!(
+                "            Reference.reachabilityFence({});\n",
+                obj
+            ));
+        }
+        body.push_str("        }\n");
+    }
+
+    let access = match method.access {
+        MethodAccess::Private => "private",
+        MethodAccess::Protected => "protected",
+        MethodAccess::Public => "public",
+    };
+    let doc_comments = doc_comments_to_java_comments(&method.doc_comments, "    ", exported_items);
+    let args = java_args.join(", ");
+    match f_method.variant {
+        MethodVariant::Constructor => {
+            let arg_names = f_method
+                .input
+                .iter()
+                .map(|arg| arg.arg_name.java_variable_name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            code.push_str(&format!(
+                r#"
+{doc_comments}    {access} {class_name}({args}) {{
+        this(do_{method_name}({arg_names}));
+    }}
+    private static MemorySegment do_{method_name}({args}) {{
+{body}    }}
+"#,
+                doc_comments = doc_comments,
+                access = access,
+                class_name = class.name,
+                method_name = f_method.name,
+                args = args,
+                arg_names = arg_names,
+                body = body,
+            ));
+        }
+        MethodVariant::Method(_) | MethodVariant::StaticMethod => {
+            let static_modifier = if f_method.variant == MethodVariant::StaticMethod {
+                " static"
+            } else {
+                ""
+            };
+            code.push_str(&format!(
+                r#"
+{doc_comments}    {access}{static_modifier} {ret_type} {method_name}({args}) {{
+{body}    }}
+"#,
+                doc_comments = doc_comments,
+                access = access,
+                static_modifier = static_modifier,
+                ret_type = output.type_info.foreign_type,
+                method_name = f_method.name,
+                args = args,
+                body = body,
+            ));
+        }
+    }
+    Ok(code)
+}
+
+fn java_ret_conversion(output: &CAbiArgInfo, var_name: &str) -> String {
+    java_conversion_code(output, var_name).unwrap_or_else(|| var_name.to_string())
+}
+
+/// Java expression to convert `var_name` from/to intermediate type,
+/// `None` if no conversion required
+fn java_conversion_code(arg: &CAbiArgInfo, var_name: &str) -> Option<String> {
+    let conv_code = &arg.type_info.foreign_conversion_code;
+    if conv_code.is_empty() || conv_code == FROM_VAR_TEMPLATE {
+        None
+    } else {
+        Some(conv_code.replace(FROM_VAR_TEMPLATE, var_name))
+    }
+}
+
+fn doc_comments_to_java_comments(
+    doc_comments: &[String],
+    indent: &str,
+    exported_items: &ExportedItems,
+) -> String {
+    let lines = DocComment::parse(doc_comments).to_javadoc(exported_items);
+    if lines.is_empty() {
+        return String::new();
+    }
+    let mut comments = format!("{}/**\n", indent);
+    for comment in lines {
+        comments.push_str(&format!("{} * {}\n", indent, comment));
+    }
+    comments.push_str(&format!("{} */\n", indent));
+    comments
+}
+
+impl LanguageGenerator for JavaPanamaConfig {
+    fn expand_items(
+        &self,
+        conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        _code: &[SourceCode],
+        items: Vec<ItemToExpand>,
+        remove_not_generated_files: bool,
+        _ext_handlers: ExtHandlers,
+    ) -> Result<Vec<TokenStream>> {
+        let generator = JavaPanamaGenerator {
+            cfg: self,
+            ctx: CAbiContext::new(conv_map),
+            generated_foreign_files: FxHashSet::default(),
+            has_on_load_hook: false,
+            exported_items: exported_items(&items),
+        };
+        let (ret, generated_foreign_files) = generator.generate(items)?;
+        if remove_not_generated_files {
+            remove_files_if(&self.output_dir, |path| {
+                if let Some(ext) = path.extension() {
+                    if ext == "java" && !generated_foreign_files.contains(path) {
+                        return true;
+                    }
+                }
+                false
+            })
+            .map_err(DiagnosticError::map_any_err_to_our_err)?;
+        }
+        Ok(ret)
+    }
+}
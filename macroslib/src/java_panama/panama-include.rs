@@ -0,0 +1,314 @@
+thread_local! {
+    static PANAMA_LAST_ERROR: ::std::cell::RefCell<Option<::std::ffi::CString>> =
+        ::std::cell::RefCell::new(None);
+}
+
+/// Remember error for current thread, Java side throws it
+/// as `RuntimeException` after return from native function
+#[allow(dead_code)]
+fn panama_set_last_error(msg: &str) {
+    let msg = ::std::ffi::CString::new(msg.replace('\0', "\\0")).unwrap_or_default();
+    PANAMA_LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(msg));
+}
+
+/// Returns error of last call for current thread and reset it,
+/// or null if there is no error. Result should be freed via `panama_c_string_delete`
+#[no_mangle]
+extern "C" fn panama_take_last_error() -> *mut ::std::os::raw::c_char {
+    match PANAMA_LAST_ERROR.with(|last_error| last_error.borrow_mut().take()) {
+        Some(msg) => msg.into_raw(),
+        None => ::std::ptr::null_mut(),
+    }
+}
+
+/// Value to return from native function in case of error,
+/// Java side ignores it and throws exception
+#[allow(dead_code)]
+trait PanamaInvalidValue {
+    fn panama_invalid_value() -> Self;
+}
+
+impl<T> PanamaInvalidValue for *const T {
+    fn panama_invalid_value() -> Self {
+        ::std::ptr::null()
+    }
+}
+
+impl<T> PanamaInvalidValue for *mut T {
+    fn panama_invalid_value() -> Self {
+        ::std::ptr::null_mut()
+    }
+}
+
+impl PanamaInvalidValue for () {
+    fn panama_invalid_value() -> Self {}
+}
+
+macro_rules! impl_panama_invalid_value {
+    ($($t:ty)*) => ($(
+        impl PanamaInvalidValue for $t {
+            fn panama_invalid_value() -> Self {
+                <$t>::default()
+            }
+        }
+    )*)
+}
+
+impl_panama_invalid_value! { i8 i16 i32 i64 f32 f64 }
+
+foreign_typemap!(
+    (r_type) ();
+    (f_type) "void";
+);
+
+foreign_typemap!(
+    (r_type) i8;
+    (f_type) "byte";
+);
+
+foreign_typemap!(
+    (r_type) i16;
+    (f_type) "short";
+);
+
+foreign_typemap!(
+    (r_type) i32;
+    (f_type) "int";
+);
+
+foreign_typemap!(
+    (r_type) i64;
+    (f_type) "long";
+);
+
+foreign_typemap!(
+    (r_type) f32;
+    (f_type) "float";
+);
+
+foreign_typemap!(
+    (r_type) f64;
+    (f_type) "double";
+);
+
+foreign_typemap!(
+    ($p:r_type) u8 => i16 {
+        $out = i16::from($p);
+    };
+    ($p:r_type) u8 <= i16 {
+        $out = match <u8 as ::std::convert::TryFrom<i16>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid short, in short => u8 conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* u8 */ short" "$p";
+    ($p:f_type) <= "/* u8 */ short" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) u16 => i32 {
+        $out = i32::from($p);
+    };
+    ($p:r_type) u16 <= i32 {
+        $out = match <u16 as ::std::convert::TryFrom<i32>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid int, in int => u16 conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* u16 */ int" "$p";
+    ($p:f_type) <= "/* u16 */ int" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) u32 => i64 {
+        $out = i64::from($p);
+    };
+    ($p:r_type) u32 <= i64 {
+        $out = match <u32 as ::std::convert::TryFrom<i64>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid long, in long => u32 conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* u32 */ long" "$p";
+    ($p:f_type) <= "/* u32 */ long" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) u64 => i64 {
+        $out = match <i64 as ::std::convert::TryFrom<u64>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid u64, in u64 => long conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:r_type) u64 <= i64 {
+        $out = match <u64 as ::std::convert::TryFrom<i64>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid long, in long => u64 conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* u64 */ long" "$p";
+    ($p:f_type) <= "/* u64 */ long" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) usize => i64 {
+        $out = match <i64 as ::std::convert::TryFrom<usize>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid usize, in usize => long conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:r_type) usize <= i64 {
+        $out = match <usize as ::std::convert::TryFrom<i64>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid long, in long => usize conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* usize */ long" "$p";
+    ($p:f_type) <= "/* usize */ long" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) isize => i64 {
+        $out = $p as i64;
+    };
+    ($p:r_type) isize <= i64 {
+        $out = match <isize as ::std::convert::TryFrom<i64>>::try_from($p) {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error(&format!(
+                    "invalid long, in long => isize conversation: {}",
+                    $p
+                ));
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "/* isize */ long" "$p";
+    ($p:f_type) <= "/* isize */ long" "$p";
+);
+
+foreign_typemap!(
+    ($p:r_type) bool => i8 {
+        $out = if $p { 1 } else { 0 };
+    };
+    ($p:f_type) => "boolean" "($p != 0)";
+    ($p:r_type) bool <= i8 {
+        $out = $p != 0;
+    };
+    ($p:f_type) <= "boolean" "(byte) ($p ? 1 : 0)";
+);
+
+#[no_mangle]
+unsafe extern "C" fn panama_c_string_delete(c_str: *mut ::std::os::raw::c_char) {
+    if !c_str.is_null() {
+        ::std::mem::drop(::std::ffi::CString::from_raw(c_str));
+    }
+}
+
+foreign_typemap!(
+    (r_type) *const ::std::os::raw::c_char;
+    (f_type) "/* const char * */ MemorySegment";
+);
+
+foreign_typemap!(
+    (r_type) *mut ::std::os::raw::c_char;
+    (f_type) "/* char * */ MemorySegment";
+);
+
+foreign_typemap!(
+    ($p:r_type) String => *mut ::std::os::raw::c_char {
+        $out = match ::std::ffi::CString::new($p) {
+            Ok(x) => x.into_raw(),
+            Err(_) => {
+                panama_set_last_error("String with nul byte can not be passed to Java");
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) => "String" "RustLib.stringFromRust($p)";
+);
+
+foreign_typemap!(
+    ($p:r_type) &str <= *const ::std::os::raw::c_char {
+        if $p.is_null() {
+            panama_set_last_error("null string from Java");
+            return PanamaInvalidValue::panama_invalid_value();
+        }
+        $out = match unsafe { ::std::ffi::CStr::from_ptr($p) }.to_str() {
+            Ok(x) => x,
+            Err(_) => {
+                panama_set_last_error("invalid utf-8 in string from Java");
+                return PanamaInvalidValue::panama_invalid_value();
+            }
+        };
+    };
+    ($p:f_type) <= "String" "__arena.allocateFrom($p)";
+);
+
+foreign_typemap!(
+    ($p:r_type) &str => String {
+        $out = $p.to_owned();
+    };
+);
+
+foreign_typemap!(
+    ($p:r_type) String => &str {
+        $out = & $p;
+    };
+);
+
+#[allow(dead_code)]
+pub trait SwigForeignEnum: Sized {
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
+
+/// Value of flags created by `bitflags!` is passed as its bits
+#[allow(dead_code)]
+pub trait SwigForeignBitflags: Sized {
+    /// `None` if there are unknown bits
+    fn from_i32(x: i32) -> Option<Self>;
+    fn as_i32(&self) -> i32;
+}
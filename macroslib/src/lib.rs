@@ -30,13 +30,17 @@ macro_rules! parse_type_spanned_checked {
     }}
 }
 
+mod c_abi;
+mod c_api;
 mod code_parse;
 mod cpp;
+mod doc_comments;
 mod dotnet;
 mod error;
 mod extension;
 pub mod file_cache;
 mod java_jni;
+mod java_panama;
 mod namegen;
 mod python;
 mod source_registry;
@@ -73,12 +77,14 @@ pub(crate) static COPY_TRAIT: &str = "Copy";
 pub(crate) static CLONE_TRAIT: &str = "Clone";
 pub(crate) static PLAIN_CLASS: &str = "PlainClass";
 pub(crate) static CAMEL_CASE_ALIASES: &str = "camelCaseAliases";
-pub(crate) static KNOWN_CLASS_DERIVES: [&str; 5] = [
+pub(crate) static JAVA_RECORD: &str = "JavaRecord";
+pub(crate) static KNOWN_CLASS_DERIVES: [&str; 6] = [
     CLONE_TRAIT,
     COPY_TRAIT,
     SMART_PTR_COPY_TRAIT,
     PLAIN_CLASS,
     CAMEL_CASE_ALIASES,
+    JAVA_RECORD,
 ];
 
 pub use extension::MethodInfo;
@@ -103,6 +109,8 @@ pub enum LanguageConfig {
     CppConfig(CppConfig),
     PythonConfig(PythonConfig),
     DotNetConfig(DotNetConfig),
+    JavaPanamaConfig(JavaPanamaConfig),
+    CConfig(CConfig),
 }
 
 /// Configuration for Java binding generation
@@ -113,6 +121,18 @@ pub struct JavaConfig {
     null_annotation_package: Option<String>,
     optional_package: String,
     reachability_fence: JavaReachabilityFence,
+    callback_exception_policy: JavaCallbackExceptionPolicy,
+    thread_attach_policy: JavaThreadAttachPolicy,
+    attached_thread_name: Option<String>,
+    attached_thread_group: Option<String>,
+    register_natives: bool,
+    proguard_rules: bool,
+    use_java_util_list: bool,
+    use_java_time: bool,
+    unsigned_integers: JavaUnsignedIntegers,
+    java_module: Option<String>,
+    java_module_requires: Vec<String>,
+    native_library_loader: Option<String>,
 }
 
 impl JavaConfig {
@@ -127,6 +147,18 @@ impl JavaConfig {
             null_annotation_package: None,
             optional_package: "java.util".to_string(),
             reachability_fence: JavaReachabilityFence::GenerateFence(8),
+            callback_exception_policy: JavaCallbackExceptionPolicy::Log,
+            thread_attach_policy: JavaThreadAttachPolicy::DetachAfterCall,
+            attached_thread_name: None,
+            attached_thread_group: None,
+            register_natives: false,
+            proguard_rules: false,
+            use_java_util_list: false,
+            use_java_time: false,
+            unsigned_integers: JavaUnsignedIntegers::Widen,
+            java_module: None,
+            java_module_requires: Vec::new(),
+            native_library_loader: None,
         }
     }
     /// Use @NonNull for types where appropriate
@@ -174,6 +206,93 @@ impl JavaConfig {
         self.reachability_fence = reachability_fence;
         self
     }
+    /// What to do if Java code throws exception inside callback method
+    /// that returns not `Result`. Callback methods that return `Result<T, E>`
+    /// always convert exception to `Err(E::from(JavaException))`.
+    /// Default value is `JavaCallbackExceptionPolicy::Log`
+    pub fn use_callback_exception_policy(
+        mut self,
+        policy: JavaCallbackExceptionPolicy,
+    ) -> JavaConfig {
+        self.callback_exception_policy = policy;
+        self
+    }
+    /// How to attach to JVM threads created by Rust code, when they call callbacks.
+    /// Default value is `JavaThreadAttachPolicy::DetachAfterCall`
+    pub fn use_thread_attach_policy(mut self, policy: JavaThreadAttachPolicy) -> JavaConfig {
+        self.thread_attach_policy = policy;
+        self
+    }
+    /// Name of Java thread for threads attached to JVM.
+    /// By default name of Rust thread is used
+    pub fn attached_thread_name(mut self, name: String) -> JavaConfig {
+        self.attached_thread_name = Some(name);
+        self
+    }
+    /// Put threads attached to JVM into `java.lang.ThreadGroup` with such name
+    pub fn attached_thread_group(mut self, group_name: String) -> JavaConfig {
+        self.attached_thread_group = Some(group_name);
+        self
+    }
+    /// Register native methods via `RegisterNatives` inside `JNI_OnLoad`,
+    /// instead of export of `Java_package_Class_method` symbols.
+    /// `proguard-rules.pro` is generated in this mode, see `generate_proguard_rules`
+    pub fn use_register_natives(mut self, register_natives: bool) -> JavaConfig {
+        self.register_natives = register_natives;
+        self
+    }
+    /// Generate `proguard-rules.pro` in `output_dir` with keep rules for classes
+    /// and members, that Rust code uses via JNI. Default value is `false`,
+    /// but rules are always generated if `use_register_natives` is enabled
+    pub fn generate_proguard_rules(mut self, proguard_rules: bool) -> JavaConfig {
+        self.proguard_rules = proguard_rules;
+        self
+    }
+    /// Map `Vec<T>`, `&[T]` of foreign classes and `Vec<String>` to `java.util.List<T>`
+    /// instead of Java arrays. Rust code returns `java.util.ArrayList`
+    pub fn use_java_util_list(mut self, use_java_util_list: bool) -> JavaConfig {
+        self.use_java_util_list = use_java_util_list;
+        self
+    }
+    /// Map `std::time::Duration` to `java.time.Duration`, `SystemTime` to `java.time.Instant`,
+    /// and `chrono::NaiveDate`, `chrono::NaiveDateTime`, `chrono::DateTime<Utc>`
+    /// to `java.time.LocalDate`, `java.time.LocalDateTime` and `java.time.Instant`,
+    /// with nanoseconds precision. Values out of range of target type cause exception.
+    /// Default is to map `SystemTime` to `java.util.Date` with milliseconds precision
+    pub fn use_java_time(mut self, use_java_time: bool) -> JavaConfig {
+        self.use_java_time = use_java_time;
+        self
+    }
+    /// How to map Rust's unsigned integers to Java types.
+    /// Default value is `JavaUnsignedIntegers::Widen`
+    pub fn use_unsigned_integers(mut self, mapping: JavaUnsignedIntegers) -> JavaConfig {
+        self.unsigned_integers = mapping;
+        self
+    }
+    /// Generate `module-info.java` for Java Platform Module System,
+    /// with such module name and `exports` of package with generated classes.
+    /// The file is placed into the root of source tree, so `output_dir`
+    /// should ends with directories that correspond to `package_name`
+    pub fn java_module(mut self, module_name: String) -> JavaConfig {
+        self.java_module = Some(module_name);
+        self
+    }
+    /// Add `requires` directive to generated `module-info.java`,
+    /// for example for module with null annotations.
+    /// Modifiers also can be passed, like "static org.jetbrains.annotations"
+    pub fn java_module_requires(mut self, module_name: String) -> JavaConfig {
+        self.java_module_requires.push(module_name);
+        self
+    }
+    /// Generate `NativeLibraryLoader` class, that loads native library with such name
+    /// via `System.loadLibrary`, and if it is not found in `java.library.path`,
+    /// extracts it from `native/<os>-<arch>/` resource near `NativeLibraryLoader.class`.
+    /// It uses only Java 8 API, and resources lookup works inside named module,
+    /// so it can be used from multi-release JAR
+    pub fn native_library_loader(mut self, library_name: String) -> JavaConfig {
+        self.native_library_loader = Some(library_name);
+        self
+    }
 }
 
 /// What reachability fence to use
@@ -190,6 +309,66 @@ pub enum JavaReachabilityFence {
     GenerateFence(usize),
 }
 
+/// How to handle exception thrown by Java implementation of callback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaCallbackExceptionPolicy {
+    /// Log exception via `log::error!`, print it with `ExceptionDescribe`
+    /// and clear it
+    Log,
+    /// Panic with exception's class name and message
+    Panic,
+    /// Clear exception and throw it again on return from
+    /// the next native method.
+    /// Callback called from thread without native method
+    /// on the stack, for example thread created by Rust,
+    /// has nobody to rethrow exception, so it is handled like `Log`
+    Rethrow,
+}
+
+/// How to attach threads created by Rust to JVM
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaThreadAttachPolicy {
+    /// `AttachCurrentThread` before callback call and
+    /// `DetachCurrentThread` after it
+    DetachAfterCall,
+    /// `AttachCurrentThread` on the first callback call,
+    /// `DetachCurrentThread` on thread exit
+    DetachOnThreadExit,
+    /// `AttachCurrentThreadAsDaemon` on the first callback call,
+    /// `DetachCurrentThread` on thread exit
+    Daemon,
+}
+
+impl From<JavaThreadAttachPolicy> for &'static str {
+    fn from(x: JavaThreadAttachPolicy) -> Self {
+        match x {
+            JavaThreadAttachPolicy::DetachAfterCall => "DetachAfterCall",
+            JavaThreadAttachPolicy::DetachOnThreadExit => "DetachOnThreadExit",
+            JavaThreadAttachPolicy::Daemon => "Daemon",
+        }
+    }
+}
+
+/// How to map Rust's unsigned integers to Java types
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JavaUnsignedIntegers {
+    /// `u8` to `short`, `u16` to `int`, `u32` and `u64` to `long`,
+    /// values out of range of target type cause panic
+    Widen,
+    /// The same types as `Widen`, but generated Java code checks ranges
+    /// and throws `ArithmeticException` for values out of range.
+    /// Because of checks are in Java code, callbacks can not return unsigned integers
+    /// and can not accept `u64`
+    WidenChecked,
+    /// The same as `WidenChecked`, but `u64` is mapped to `java.math.BigInteger`
+    WidenBigInteger,
+    /// `u8` to `byte`, `u16` to `short`, `u32` to `int` and `u64` to `long`,
+    /// bits are reinterpreted as signed value of the same width.
+    /// Types are marked with comment, like `/*u32*/int`, so Java code can use
+    /// `Integer.toUnsignedLong` and similar methods, and Kotlin code `toUInt()`
+    SameWidth,
+}
+
 /// Configuration for C++ binding generation
 pub struct CppConfig {
     output_dir: PathBuf,
@@ -197,12 +376,23 @@ pub struct CppConfig {
     cpp_optional: CppOptional,
     cpp_variant: CppVariant,
     cpp_str_view: CppStrView,
+    cpp_slice: CppSlice,
     /// Generate `enum class` in instead of plain old `enum` in C++ code.
     /// Can be necessary, if variant names between different Rust enums are not unique.
     use_enum_class: bool,
     /// Create separate *_impl.hpp files with methods implementations.
     /// Can be necessary for the project with circular dependencies between classes.
     separate_impl_headers: bool,
+    /// Prefix for names of generated C functions and types
+    c_symbol_prefix: String,
+    /// Map `Result<T, E>` to `T` and throw exception in case of `Err`
+    use_exceptions: bool,
+    /// Name of CMake package to generate `<name>Config.cmake` for
+    cmake_package_name: Option<String>,
+    /// Name of header to merge all generated headers into
+    single_header_name: Option<String>,
+    /// Put implementations of methods into `.cpp` files
+    separate_impl_sources: bool,
 }
 
 /// To which `C++` type map `std::option::Option`
@@ -225,11 +415,17 @@ impl From<CppOptional> for &'static str {
 
 /// To which `C++` type map `std::result::Result`
 #[derive(Clone, Copy, EnumIter)]
+#[non_exhaustive]
 pub enum CppVariant {
     /// `std::variant` from C++17 standard
     Std17,
     /// `boost::variant`
     Boost,
+    /// `std::expected` from C++23 standard
+    Std23Expected,
+    /// `tl::expected` from <https://github.com/TartanLlama/expected>,
+    /// can be used before C++23
+    TlExpected,
 }
 
 impl From<CppVariant> for &'static str {
@@ -237,17 +433,22 @@ impl From<CppVariant> for &'static str {
         match x {
             CppVariant::Std17 => "CppVariant::Std17",
             CppVariant::Boost => "CppVariant::Boost",
+            CppVariant::Std23Expected => "CppVariant::Std23Expected",
+            CppVariant::TlExpected => "CppVariant::TlExpected",
         }
     }
 }
 
 /// To whcih `C++` type map `&str`
 #[derive(Clone, Copy, EnumIter)]
+#[non_exhaustive]
 pub enum CppStrView {
     /// `std::string_view` from C++17 standard
     Std17,
     /// `boost::string_view`
     Boost,
+    /// `std::u8string_view` from C++20 standard
+    Std20U8,
 }
 
 impl From<CppStrView> for &'static str {
@@ -255,6 +456,26 @@ impl From<CppStrView> for &'static str {
         match x {
             CppStrView::Std17 => "CppStrView::Std17",
             CppStrView::Boost => "CppStrView::Boost",
+            CppStrView::Std20U8 => "CppStrView::Std20U8",
+        }
+    }
+}
+
+/// To which `C++` type map `&[T]` and `&mut [T]`, where `T` is `repr(C)` type
+#[derive(Clone, Copy, EnumIter)]
+#[non_exhaustive]
+pub enum CppSlice {
+    /// `RustSlice` template generated by flapigen
+    RustSlice,
+    /// `std::span` from C++20 standard
+    Std20,
+}
+
+impl From<CppSlice> for &'static str {
+    fn from(x: CppSlice) -> Self {
+        match x {
+            CppSlice::RustSlice => "CppSlice::RustSlice",
+            CppSlice::Std20 => "CppSlice::Std20",
         }
     }
 }
@@ -271,8 +492,14 @@ impl CppConfig {
             cpp_optional: CppOptional::Std17,
             cpp_variant: CppVariant::Std17,
             cpp_str_view: CppStrView::Std17,
+            cpp_slice: CppSlice::RustSlice,
             use_enum_class: false,
             separate_impl_headers: false,
+            c_symbol_prefix: String::new(),
+            use_exceptions: false,
+            cmake_package_name: None,
+            single_header_name: None,
+            separate_impl_sources: false,
         }
     }
     pub fn cpp_optional(self, cpp_optional: CppOptional) -> CppConfig {
@@ -293,6 +520,9 @@ impl CppConfig {
             ..self
         }
     }
+    pub fn cpp_slice(self, cpp_slice: CppSlice) -> CppConfig {
+        CppConfig { cpp_slice, ..self }
+    }
     /// Use boost for that fit: Result -> boost::variant,
     /// Option -> boost::optional, &str -> boost::string_view
     pub fn use_boost(self) -> CppConfig {
@@ -319,6 +549,71 @@ impl CppConfig {
             ..self
         }
     }
+    /// Prefix for names of all generated C functions and types,
+    /// like `Foo_new`, `FooOpaque` or `CRustString`.
+    /// Can be necessary, if several libraries generated by flapigen
+    /// should be linked into one program.
+    /// Note that own `foreign_typemap!` that use C types of flapigen,
+    /// like `CRustStrView`, should use names with prefix.
+    pub fn c_symbol_prefix(self, c_symbol_prefix: String) -> CppConfig {
+        CppConfig {
+            c_symbol_prefix,
+            ..self
+        }
+    }
+    /// Use namespace name as prefix for names of C functions and types,
+    /// for example `my_lib_` for `my_lib` namespace, see `c_symbol_prefix`
+    pub fn c_symbol_prefix_from_namespace(self) -> CppConfig {
+        let c_symbol_prefix = format!("{}_", self.namespace_name.replace("::", "_"));
+        self.c_symbol_prefix(c_symbol_prefix)
+    }
+    /// Map `Result<T, E>` returned from Rust to `T` in C++ code,
+    /// and throw `RustError<E>` (derived from `std::runtime_error`) in case of `Err`.
+    /// `what()` of exception returns `Display` representation of error,
+    /// so `E` should implement `std::fmt::Display`.
+    /// Methods that can not throw are still marked as `noexcept`.
+    pub fn use_exceptions(self, use_exceptions: bool) -> CppConfig {
+        CppConfig {
+            use_exceptions,
+            ..self
+        }
+    }
+    /// Generate `<package_name>Config.cmake` in `output_dir` with imported
+    /// target `<package_name>::<package_name>`. The target has include directory
+    /// pointing to `output_dir`, C++ standard required by the chosen types
+    /// and dependency on boost if it is used.
+    /// Add `output_dir` to `CMAKE_PREFIX_PATH` (or set `<package_name>_DIR`)
+    /// and use `find_package(<package_name> CONFIG REQUIRED)` to consume it.
+    /// If `<package_name>_RUST_LIBRARY` is set, the target links with it.
+    pub fn cmake_package(self, package_name: String) -> CppConfig {
+        CppConfig {
+            cmake_package_name: Some(package_name),
+            ..self
+        }
+    }
+    /// Merge all generated C and C++ headers, including helpers,
+    /// into one self-contained header `output_dir/header_name`,
+    /// so it is simple to copy it into other project.
+    /// Headers are merged in order of their includes, and methods' implementations
+    /// are placed after all classes declarations (like with `separate_impl_headers`),
+    /// so circular dependencies between classes are supported.
+    pub fn single_header(self, header_name: String) -> CppConfig {
+        CppConfig {
+            single_header_name: Some(header_name),
+            ..self
+        }
+    }
+    /// Put implementations of classes' methods into `<ClassName>.cpp` files,
+    /// instead of inline functions in headers, so they are compiled only once.
+    /// Headers contain only declarations and `extern template` for explicitly
+    /// instantiated methods. The `.cpp` files should be compiled and linked
+    /// with the rest of C++ code, they are added to target sources by `cmake_package`.
+    pub fn separate_impl_sources(self, separate_impl_sources: bool) -> CppConfig {
+        CppConfig {
+            separate_impl_sources,
+            ..self
+        }
+    }
 }
 
 /// Configuration for Python binding generation
@@ -367,6 +662,65 @@ impl DotNetConfig {
     }
 }
 
+/// Configuration for Java binding generation via Foreign Function and Memory API
+/// (`java.lang.foreign`, Java 22+), no JNI code is generated
+pub struct JavaPanamaConfig {
+    output_dir: PathBuf,
+    package_name: String,
+    native_lib_name: String,
+}
+
+impl JavaPanamaConfig {
+    /// Create `JavaPanamaConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated java files
+    /// * `package_name` - package name for generated java files
+    /// * `native_lib_name` - name of library with Rust code,
+    ///   it is loaded via `System.loadLibrary`
+    pub fn new(
+        output_dir: PathBuf,
+        package_name: String,
+        native_lib_name: String,
+    ) -> JavaPanamaConfig {
+        JavaPanamaConfig {
+            output_dir,
+            package_name,
+            native_lib_name,
+        }
+    }
+}
+
+/// Configuration for plain C99 API generation, without C++ wrappers.
+/// Generated headers are self-contained and can be used from C code directly.
+pub struct CConfig {
+    output_dir: PathBuf,
+    library_name: String,
+    symbol_prefix: String,
+}
+
+impl CConfig {
+    /// Create `CConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated C headers
+    /// * `library_name` - name of library, umbrella header is named `<library_name>.h`,
+    ///   and `<library_name>_` is used as default symbol prefix
+    pub fn new(output_dir: PathBuf, library_name: String) -> CConfig {
+        CConfig {
+            output_dir,
+            symbol_prefix: format!("{}_", library_name),
+            library_name,
+        }
+    }
+    /// Prefix for names of all exported functions, types and headers,
+    /// `<library_name>_` by default
+    pub fn symbol_prefix(self, symbol_prefix: String) -> CConfig {
+        CConfig {
+            symbol_prefix,
+            ..self
+        }
+    }
+}
+
 /// `Generator` is a main point of `flapigen`.
 /// It expands rust macroses and generates not rust code.
 /// It designed to use inside `build.rs`.
@@ -393,11 +747,13 @@ struct SourceCode {
 static FOREIGNER_CLASS_DEPRECATED: &str = "foreigner_class";
 static FOREIGN_CLASS: &str = "foreign_class";
 static FOREIGN_ENUM: &str = "foreign_enum";
+static FOREIGN_BITFLAGS: &str = "foreign_bitflags";
 static FOREIGN_INTERFACE_DEPRECATED: &str = "foreign_interface";
 static FOREIGN_CALLBACK: &str = "foreign_callback";
 static FOREIGNER_CODE_DEPRECATED: &str = "foreigner_code";
 static FOREIGN_CODE: &str = "foreign_code";
 static FOREIGN_TYPEMAP: &str = "foreign_typemap";
+static FOREIGN_LIFECYCLE: &str = "foreign_lifecycle";
 
 impl Generator {
     pub fn new(config: LanguageConfig) -> Generator {
@@ -407,25 +763,110 @@ impl Generator {
         let mut src_reg = SourceRegistry::default();
         match config {
             LanguageConfig::JavaConfig(ref java_cfg) => {
-                conv_map_source.push(
-                    src_reg.register(SourceCode {
-                        id_of_code: "jni-include.rs".into(),
-                        code: include_str!("java_jni/jni-include.rs")
-                            .replace(
-                                "java.util.Optional",
-                                &format!("{}.Optional", java_cfg.optional_package),
-                            )
-                            .replace(
-                                "java/util/Optional",
-                                &format!(
-                                    "{}/Optional",
-                                    java_cfg.optional_package.replace('.', "/")
-                                ),
-                            ),
-                    }),
-                );
+                let jni_include_code = include_str!("java_jni/jni-include.rs")
+                    .replace(
+                        "java.util.Optional",
+                        &format!("{}.Optional", java_cfg.optional_package),
+                    )
+                    .replace(
+                        "java/util/Optional",
+                        &format!("{}/Optional", java_cfg.optional_package.replace('.', "/")),
+                    )
+                    .replace(
+                        "JniThreadAttachPolicy = JniThreadAttachPolicy::DetachAfterCall;",
+                        &format!(
+                            "JniThreadAttachPolicy = JniThreadAttachPolicy::{};",
+                            <&'static str>::from(java_cfg.thread_attach_policy)
+                        ),
+                    )
+                    .replace(
+                        "SWIG_JNI_ATTACHED_THREAD_NAME: Option<&str> = None;",
+                        &format!(
+                            "SWIG_JNI_ATTACHED_THREAD_NAME: Option<&str> = {:?};",
+                            java_cfg.attached_thread_name
+                        ),
+                    )
+                    .replace(
+                        "SWIG_JNI_ATTACHED_THREAD_GROUP: Option<&str> = None;",
+                        &format!(
+                            "SWIG_JNI_ATTACHED_THREAD_GROUP: Option<&str> = {:?};",
+                            java_cfg.attached_thread_group
+                        ),
+                    );
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "jni-include.rs".into(),
+                    code: jni_include_code,
+                }));
+                conv_map_source.push(src_reg.register(if java_cfg.use_java_util_list {
+                    SourceCode {
+                        id_of_code: "jni-java-util-list-include.rs".into(),
+                        code: include_str!("java_jni/jni-java-util-list-include.rs").into(),
+                    }
+                } else {
+                    SourceCode {
+                        id_of_code: "jni-java-array-include.rs".into(),
+                        code: include_str!("java_jni/jni-java-array-include.rs").into(),
+                    }
+                }));
+                let unsigned_includes: &[(&str, &str)] = match java_cfg.unsigned_integers {
+                    JavaUnsignedIntegers::Widen => &[(
+                        "jni-unsigned-widen-include.rs",
+                        include_str!("java_jni/jni-unsigned-widen-include.rs"),
+                    )],
+                    JavaUnsignedIntegers::WidenChecked => &[
+                        (
+                            "jni-unsigned-checked-include.rs",
+                            include_str!("java_jni/jni-unsigned-checked-include.rs"),
+                        ),
+                        (
+                            "jni-unsigned-checked-u64-include.rs",
+                            include_str!("java_jni/jni-unsigned-checked-u64-include.rs"),
+                        ),
+                    ],
+                    JavaUnsignedIntegers::WidenBigInteger => &[
+                        (
+                            "jni-unsigned-checked-include.rs",
+                            include_str!("java_jni/jni-unsigned-checked-include.rs"),
+                        ),
+                        (
+                            "jni-unsigned-big-integer-include.rs",
+                            include_str!("java_jni/jni-unsigned-big-integer-include.rs"),
+                        ),
+                    ],
+                    JavaUnsignedIntegers::SameWidth => &[(
+                        "jni-unsigned-same-width-include.rs",
+                        include_str!("java_jni/jni-unsigned-same-width-include.rs"),
+                    )],
+                };
+                for (id_of_code, code) in unsigned_includes {
+                    conv_map_source.push(src_reg.register(SourceCode {
+                        id_of_code: (*id_of_code).into(),
+                        code: (*code).into(),
+                    }));
+                }
+                conv_map_source.push(src_reg.register(if java_cfg.use_java_time {
+                    SourceCode {
+                        id_of_code: "jni-java-time-include.rs".into(),
+                        code: include_str!("java_jni/jni-java-time-include.rs").into(),
+                    }
+                } else {
+                    SourceCode {
+                        id_of_code: "jni-java-util-date-include.rs".into(),
+                        code: include_str!("java_jni/jni-java-util-date-include.rs").into(),
+                    }
+                }));
             }
-            LanguageConfig::CppConfig(..) => {
+            LanguageConfig::CppConfig(ref cpp_cfg) => {
+                if cpp_cfg.use_exceptions {
+                    conv_map_source.push(src_reg.register(SourceCode {
+                        id_of_code: "cpp-exceptions-include.rs".into(),
+                        code: include_str!("cpp/cpp-exceptions-include.rs").into(),
+                    }));
+                    foreign_lang_helpers.push(SourceCode {
+                        id_of_code: "rust_error.hpp".into(),
+                        code: include_str!("cpp/rust_error.hpp").into(),
+                    });
+                }
                 conv_map_source.push(src_reg.register(SourceCode {
                     id_of_code: "cpp-include.rs".into(),
                     code: include_str!("cpp/cpp-include.rs").into(),
@@ -434,6 +875,10 @@ impl Generator {
                     id_of_code: "rust_vec_impl.hpp".into(),
                     code: include_str!("cpp/rust_vec_impl.hpp").into(),
                 });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_map_impl.hpp".into(),
+                    code: include_str!("cpp/rust_map_impl.hpp").into(),
+                });
                 foreign_lang_helpers.push(SourceCode {
                     id_of_code: "rust_foreign_vec_impl.hpp".into(),
                     code: include_str!("cpp/rust_foreign_vec_impl.hpp").into(),
@@ -463,6 +908,18 @@ impl Generator {
                     code: include_str!("dotnet/dotnet-include.rs").into(),
                 }));
             }
+            LanguageConfig::JavaPanamaConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "panama-include.rs".into(),
+                    code: include_str!("java_panama/panama-include.rs").into(),
+                }));
+            }
+            LanguageConfig::CConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "c-include.rs".into(),
+                    code: include_str!("c_api/c-include.rs").into(),
+                }));
+            }
         }
         Generator {
             init_done: false,
@@ -680,9 +1137,11 @@ impl Generator {
                         FOREIGNER_CLASS_DEPRECATED,
                         FOREIGN_CLASS,
                         FOREIGN_ENUM,
+                        FOREIGN_BITFLAGS,
                         FOREIGN_INTERFACE_DEPRECATED,
                         FOREIGN_CALLBACK,
                         FOREIGN_TYPEMAP,
+                        FOREIGN_LIFECYCLE,
                     ]
                     .iter()
                     .any(|x| item_macro.mac.path.is_ident(x));
@@ -720,6 +1179,9 @@ impl Generator {
                     } else if item_macro.mac.path.is_ident(FOREIGN_ENUM) {
                         let fenum = code_parse::parse_foreign_enum(*src_id, tts)?;
                         items_to_expand.push(ItemToExpand::Enum(fenum));
+                    } else if item_macro.mac.path.is_ident(FOREIGN_BITFLAGS) {
+                        let fenum = code_parse::parse_foreign_bitflags(*src_id, tts)?;
+                        items_to_expand.push(ItemToExpand::Enum(fenum));
                     } else if item_macro.mac.path.is_ident(FOREIGN_INTERFACE_DEPRECATED)
                         || item_macro.mac.path.is_ident(FOREIGN_CALLBACK)
                     {
@@ -733,6 +1195,19 @@ impl Generator {
                         items_to_expand.push(ItemToExpand::Interface(finterface));
                     } else if item_macro.mac.path.is_ident(FOREIGN_TYPEMAP) {
                         self.conv_map.parse_foreign_typemap_macro(*src_id, tts)?;
+                    } else if item_macro.mac.path.is_ident(FOREIGN_LIFECYCLE) {
+                        let lifecycle = code_parse::parse_foreign_lifecycle(*src_id, tts)?;
+                        if let Some(prev) = items_to_expand.iter().find_map(|x| match x {
+                            ItemToExpand::Lifecycle(prev) => Some(prev),
+                            _ => None,
+                        }) {
+                            return Err(DiagnosticError::new2(
+                                lifecycle.src_id_span(),
+                                format!("only one {} is allowed", FOREIGN_LIFECYCLE),
+                            )
+                            .add_span_note(prev.src_id_span(), "previous definition here"));
+                        }
+                        items_to_expand.push(ItemToExpand::Lifecycle(lifecycle));
                     } else {
                         unreachable!();
                     }
@@ -810,6 +1285,8 @@ impl Generator {
             LanguageConfig::CppConfig(ref cpp_cfg) => cpp_cfg,
             LanguageConfig::PythonConfig(ref python_cfg) => python_cfg,
             LanguageConfig::DotNetConfig(ref dot_net_config) => dot_net_config,
+            LanguageConfig::JavaPanamaConfig(ref panama_cfg) => panama_cfg,
+            LanguageConfig::CConfig(ref c_cfg) => c_cfg,
         }
     }
 }
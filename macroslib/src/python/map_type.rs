@@ -0,0 +1,197 @@
+//! Conversion of types via rules from `TypeMap`.
+//! For Python foreign type is Rust type that `cpython` can convert from/to Python object
+//! (`i64`, `String`, `cpython::PyObject` and so on), so code of `f_type` rules is Rust code
+//! too, `py: cpython::Python` is available there.
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Type;
+
+use crate::{
+    error::{DiagnosticError, Result, SourceIdSpan},
+    typemap::{
+        ty::{ForeignTypeS, RustType},
+        MapToForeignFlag, RustTypeIdx, TypeConvCode, TypeConvCodeSubstParam, FROM_VAR_TEMPLATE,
+        TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
+    },
+    types::ForeignClassInfo,
+    TypeMap,
+};
+
+/// Returns type of argument for `py_class!` method
+/// and expression to convert it to `rust_type`
+pub(in crate::python) fn map_arg_type(
+    conv_map: &mut TypeMap,
+    rust_type: &RustType,
+    arg_name: &str,
+    span: SourceIdSpan,
+    flag: MapToForeignFlag,
+    code_deps: &mut Vec<TokenStream>,
+) -> Result<Option<(Type, TokenStream)>> {
+    let ftype = match find_foreign_type(conv_map, rust_type, Direction::Incoming, flag, span) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let rule = match ftype.from_into_rust.as_ref() {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let py_type = parse_py_type(&ftype, span)?;
+    let mut code = String::new();
+    let (from_rty, from_var) = match rule.intermediate.as_ref() {
+        Some(intermediate) => {
+            let tmp_var = format!("{}_tmp", arg_name);
+            let tmp_type = conv_map[intermediate.intermediate_ty]
+                .typename()
+                .to_string();
+            code.push_str(&generate_f_type_code(
+                &intermediate.conv_code,
+                arg_name,
+                &tmp_var,
+                &tmp_type,
+            )?);
+            (intermediate.intermediate_ty, tmp_var)
+        }
+        None if rule.rust_ty == rust_type.to_idx() => {
+            let arg_name: TokenStream = parse_code(arg_name, span)?;
+            return Ok(Some((py_type, arg_name)));
+        }
+        None => (rule.rust_ty, arg_name.to_string()),
+    };
+    let out_var = format!("{}_rust", arg_name);
+    let (mut deps, conv_code) =
+        conv_map.convert_rust_types(from_rty, rust_type.to_idx(), &from_var, &out_var, "", span)?;
+    code_deps.append(&mut deps);
+    code.push_str(&conv_code);
+    code.push_str(&out_var);
+    let code: TokenStream = parse_code(&code, span)?;
+    Ok(Some((py_type, quote! { { #code } })))
+}
+
+/// Returns type of result for `py_class!` method
+/// and expression to convert result of `rust_call` to it
+pub(in crate::python) fn map_return_type(
+    conv_map: &mut TypeMap,
+    rust_type: &RustType,
+    rust_call: &TokenStream,
+    span: SourceIdSpan,
+    flag: MapToForeignFlag,
+    code_deps: &mut Vec<TokenStream>,
+) -> Result<Option<(Type, TokenStream)>> {
+    let ftype = match find_foreign_type(conv_map, rust_type, Direction::Outgoing, flag, span) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let rule = match ftype.into_from_rust.as_ref() {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let py_type = parse_py_type(&ftype, span)?;
+    if rule.intermediate.is_none() && rule.rust_ty == rust_type.to_idx() {
+        return Ok(Some((py_type, rust_call.clone())));
+    }
+    let to_rty: RustTypeIdx = match rule.intermediate.as_ref() {
+        Some(intermediate) => intermediate.intermediate_ty,
+        None => rule.rust_ty,
+    };
+    let ret_var = "ret_rust";
+    let out_var = "ret_tmp";
+    let (mut deps, conv_code) =
+        conv_map.convert_rust_types(rust_type.to_idx(), to_rty, ret_var, out_var, "", span)?;
+    code_deps.append(&mut deps);
+    let mut code = conv_code;
+    match rule.intermediate.as_ref() {
+        Some(intermediate) => {
+            let py_var = "ret_py";
+            code.push_str(&generate_f_type_code(
+                &intermediate.conv_code,
+                out_var,
+                py_var,
+                &ftype.typename(),
+            )?);
+            code.push_str(py_var);
+        }
+        None => code.push_str(out_var),
+    }
+    let code: TokenStream = parse_code(&code, span)?;
+    Ok(Some((
+        py_type,
+        quote! {
+            {
+                let ret_rust = #rust_call;
+                #code
+            }
+        },
+    )))
+}
+
+fn find_foreign_type(
+    conv_map: &mut TypeMap,
+    rust_type: &RustType,
+    direction: Direction,
+    flag: MapToForeignFlag,
+    span: SourceIdSpan,
+) -> Option<ForeignTypeS> {
+    conv_map
+        .map_through_conversation_to_foreign(
+            rust_type.to_idx(),
+            direction,
+            flag,
+            span,
+            calc_this_type_for_method,
+        )
+        .map(|idx| conv_map[idx].clone())
+}
+
+/// Python classes are not described in `TypeMap`,
+/// so there are no generic rules for them
+fn calc_this_type_for_method(_: &TypeMap, _: &ForeignClassInfo) -> Option<Type> {
+    None
+}
+
+/// Code of `f_type` rule is Rust code, the first `$out` in it declares variable
+fn generate_f_type_code(
+    conv_code: &TypeConvCode,
+    from_var: &str,
+    to_var: &str,
+    to_type: &str,
+) -> Result<String> {
+    let mut code = conv_code.generate_code_with_subst_func(|param| match param {
+        TypeConvCodeSubstParam::Name(name) => {
+            if name == FROM_VAR_TEMPLATE {
+                Some(from_var.into())
+            } else if name == TO_VAR_TYPE_TEMPLATE {
+                Some(format!("let mut {}: {}", to_var, to_type).into())
+            } else if name == TO_VAR_TEMPLATE {
+                Some(to_var.into())
+            } else {
+                None
+            }
+        }
+        TypeConvCodeSubstParam::Tmp(name) => Some(format!("{}_{}", to_var, name).into()),
+    })?;
+    code.push('\n');
+    Ok(code)
+}
+
+fn parse_py_type(ftype: &ForeignTypeS, span: SourceIdSpan) -> Result<Type> {
+    syn::parse_str::<Type>(&ftype.typename()).map_err(|err| {
+        DiagnosticError::new2(
+            span,
+            format!(
+                "foreign type '{}' should be Rust type, that cpython can convert: {}",
+                ftype.name, err
+            ),
+        )
+    })
+}
+
+fn parse_code(code: &str, span: SourceIdSpan) -> Result<TokenStream> {
+    syn::parse_str::<TokenStream>(code).map_err(|err| {
+        DiagnosticError::new2(
+            span,
+            format!("can not parse conversion code '{}': {}", code, err),
+        )
+    })
+}
@@ -1,16 +1,20 @@
+mod map_type;
+
 use crate::typemap::ty::RustType;
 use crate::{
+    doc_comments::{exported_items, DocComment, ExportedItems},
     error::Result,
     extension::{ClassExtHandlers, MethodExtHandlers},
     source_registry::SourceId,
     typemap::{
         ast::{GenericTypeConv, TypeName},
         ty::ForeignTypeS,
-        TypeConvCode,
+        utils::rust_code_for_bitflags_width_check,
+        MapToForeignFlag, TypeConvCode,
     },
     types::{
-        ForeignClassInfo, ForeignEnumInfo, ForeignInterface, ForeignMethod, ItemToExpand,
-        MethodVariant, SelfTypeVariant,
+        ForeignClassInfo, ForeignEnumInfo, ForeignInterface, ForeignLifecycle, ForeignMethod,
+        ItemToExpand, MethodVariant, SelfTypeVariant,
     },
     DiagnosticError, LanguageGenerator, PythonConfig, SourceCode, TypeMap,
 };
@@ -24,6 +28,7 @@ use syn::parse_quote;
 use syn::{Ident, Type};
 
 const ENUM_TRAIT_NAME: &str = "SwigForeignEnum";
+const BITFLAGS_TRAIT_NAME: &str = "SwigForeignBitflags";
 
 impl LanguageGenerator for PythonConfig {
     fn expand_items(
@@ -40,6 +45,7 @@ impl LanguageGenerator for PythonConfig {
                 self.register_class(conv_map, fclass)?;
             }
         }
+        let exported_items = exported_items(&items);
         let mut code = Vec::with_capacity(items.len());
         let mut module_initialization = Vec::with_capacity(items.len());
         for item in items {
@@ -49,11 +55,15 @@ impl LanguageGenerator for PythonConfig {
                     &fclass,
                     ext_handlers.class_ext_handlers,
                     ext_handlers.method_ext_handlers,
+                    &exported_items,
                 )?,
-                ItemToExpand::Enum(fenum) => self.generate_enum(conv_map, &fenum)?,
+                ItemToExpand::Enum(fenum) => {
+                    self.generate_enum(conv_map, &fenum, &exported_items)?
+                }
                 ItemToExpand::Interface(finterface) => {
                     self.generate_interface(conv_map, &finterface)?
                 }
+                ItemToExpand::Lifecycle(lifecycle) => self.generate_lifecycle(&lifecycle)?,
             };
             code.push(class_code);
             module_initialization.push(initialization);
@@ -78,6 +88,7 @@ impl PythonConfig {
         class: &ForeignClassInfo,
         class_ext_handlers: &ClassExtHandlers,
         method_ext_handlers: &MethodExtHandlers,
+        exported_items: &ExportedItems,
     ) -> Result<(TokenStream, TokenStream)> {
         if !class_ext_handlers.is_empty() || !method_ext_handlers.is_empty() {
             return Err(DiagnosticError::new(
@@ -95,12 +106,14 @@ impl PythonConfig {
             parse::<Ident>(&py_wrapper_mod_name(&class_name.to_string()), class.src_id)?;
         let (rust_instance_field, rust_instance_getter) =
             generate_rust_instance_field_and_methods(class, conv_map)?;
+        let mut code_deps = Vec::new();
         let methods_code = class
             .methods
             .iter()
-            .map(|m| generate_method_code(class, m, conv_map))
+            .map(|m| generate_method_code(class, m, conv_map, &mut code_deps, exported_items))
             .collect::<Result<Vec<_>>>()?;
-        let mut doc_comments = class.doc_comments.clone();
+        let mut doc_comments =
+            DocComment::parse(&class.doc_comments).to_python_docstring(exported_items);
         if let Some(constructor) = class
             .methods
             .iter()
@@ -110,10 +123,14 @@ impl PythonConfig {
             // including __new__ and __init__.
             // The convention is, to document the constructor in class's docstring.
             doc_comments.push("".to_owned());
-            doc_comments.extend_from_slice(&constructor.doc_comments);
+            doc_comments.extend(
+                DocComment::parse(&constructor.doc_comments).to_python_docstring(exported_items),
+            );
         }
         let docstring = doc_comments.as_slice().join("\n");
         let class_code = quote! {
+            #( #code_deps )*
+
             mod #wrapper_mod_name {
                 use super::*;
                 #[allow(unused)]
@@ -141,12 +158,22 @@ impl PythonConfig {
         &self,
         conv_map: &mut TypeMap,
         enum_info: &ForeignEnumInfo,
+        exported_items: &ExportedItems,
     ) -> Result<(TokenStream, TokenStream)> {
         let enum_name = &enum_info.name;
         let wrapper_mod_name = parse::<Ident>(
             &py_wrapper_mod_name(&enum_name.to_string()),
             enum_info.src_id,
         )?;
+        if enum_info.is_bitflags {
+            return generate_bitflags(
+                conv_map,
+                enum_info,
+                &wrapper_mod_name,
+                &self.module_name,
+                exported_items,
+            );
+        }
         let foreign_variants = enum_info.items.iter().map(|item| &item.name);
         let rust_variants = enum_info
             .items
@@ -155,20 +182,37 @@ impl PythonConfig {
             .collect::<Vec<_>>();
         let rust_variants_ref_1 = &rust_variants;
         let rust_variants_ref_2 = &rust_variants;
+        let values = enum_info
+            .items
+            .iter()
+            .map(|item| item.value)
+            .collect::<Vec<_>>();
+        let values_ref_1 = &values;
+        let values_ref_2 = &values;
         let enum_name_str = enum_name.to_string();
-        let docstring = enum_info.doc_comments.as_slice().join("\n");
+        let docstring = DocComment::parse(&enum_info.doc_comments)
+            .to_python_docstring(exported_items)
+            .join("\n");
         let class_code = quote! {
             mod #wrapper_mod_name {
                 py_class!(pub class #enum_name |py| {
                     static __doc__  = #docstring;
-                    #( static #foreign_variants = super::#rust_variants_ref_1 as u32; )*
+                    #( static #foreign_variants = #values_ref_1; )*
                 });
 
                 pub fn from_u32(py: cpython::Python, value: u32) -> cpython::PyResult<super::#enum_name> {
-                    #( if value == super::#rust_variants_ref_1 as u32 { return Ok(super::#rust_variants_ref_2); } )*
-                    Err(cpython::PyErr::new::<cpython::exc::ValueError, _>(
-                        py, format!("{} is not valid value for enum {}", value, #enum_name_str)
-                    ))
+                    match value {
+                        #( #values_ref_1 => Ok(super::#rust_variants_ref_1), )*
+                        _ => Err(cpython::PyErr::new::<cpython::exc::ValueError, _>(
+                            py, format!("{} is not valid value for enum {}", value, #enum_name_str)
+                        )),
+                    }
+                }
+
+                pub fn to_u32(value: super::#enum_name) -> u32 {
+                    match value {
+                        #( super::#rust_variants_ref_2 => #values_ref_2, )*
+                    }
                 }
             }
         };
@@ -208,6 +252,22 @@ impl PythonConfig {
         unimplemented!("Interfaces are currently unsupported for Python.")
     }
 
+    /// Python never unloads extension modules, so only `on_load` is supported,
+    /// it is called during module initialization
+    fn generate_lifecycle(
+        &self,
+        lifecycle: &ForeignLifecycle,
+    ) -> Result<(TokenStream, TokenStream)> {
+        if lifecycle.on_unload.is_some() {
+            return Err(DiagnosticError::new2(
+                lifecycle.src_id_span(),
+                "on_unload is not supported for Python",
+            ));
+        }
+        let on_load = &lifecycle.on_load;
+        Ok((TokenStream::new(), quote! { #on_load(); }))
+    }
+
     fn generate_module_initialization(
         &self,
         module_initialization_code: &[TokenStream],
@@ -234,6 +294,98 @@ impl PythonConfig {
     }
 }
 
+/// Flags are exported as subclass of `enum.IntFlag` with bits of items,
+/// Python passes them as `int` and gets back instance of this subclass.
+fn generate_bitflags(
+    conv_map: &mut TypeMap,
+    flags_info: &ForeignEnumInfo,
+    wrapper_mod_name: &Ident,
+    module_name: &str,
+    exported_items: &ExportedItems,
+) -> Result<(TokenStream, TokenStream)> {
+    let flags_name = &flags_info.name;
+    let foreign_items = flags_info.items.iter().map(|item| item.name.to_string());
+    let rust_items = flags_info
+        .items
+        .iter()
+        .map(|item| &item.rust_name)
+        .collect::<Vec<_>>();
+    let rust_items_ref_1 = &rust_items;
+    let rust_items_ref_2 = &rust_items;
+    let flags_name_str = flags_name.to_string();
+    let docstring = DocComment::parse(&flags_info.doc_comments)
+        .to_python_docstring(exported_items)
+        .join("\n");
+    let width_check = rust_code_for_bitflags_width_check(flags_info);
+    let class_code = quote! {
+        #width_check
+        mod #wrapper_mod_name {
+            use cpython::ObjectProtocol;
+
+            pub fn create_class(py: cpython::Python) -> cpython::PyResult<cpython::PyObject> {
+                let items: Vec<(&str, u32)> = vec![
+                    #( (#foreign_items, super::#rust_items_ref_1.bits() as u32) ),*
+                ];
+                let class = py
+                    .import("enum")?
+                    .get(py, "IntFlag")?
+                    .call(py, (#flags_name_str, items), None)?;
+                class.setattr(py, "__doc__", #docstring)?;
+                Ok(class)
+            }
+
+            pub fn to_py(py: cpython::Python, flags: super::#flags_name) -> cpython::PyResult<cpython::PyObject> {
+                py.import(#module_name)?
+                    .get(py, #flags_name_str)?
+                    .call(py, (flags.bits() as u32,), None)
+            }
+
+            pub fn from_u32(py: cpython::Python, value: u32) -> cpython::PyResult<super::#flags_name> {
+                let mut flags = super::#flags_name::empty();
+                let mut unknown_bits = value;
+                #(
+                    let item_bits = super::#rust_items_ref_1.bits() as u32;
+                    if (value & item_bits) == item_bits {
+                        flags.insert(super::#rust_items_ref_2);
+                        unknown_bits &= !item_bits;
+                    }
+                )*
+                if unknown_bits != 0 {
+                    return Err(cpython::PyErr::new::<cpython::exc::ValueError, _>(
+                        py, format!("{} is not valid value for flags {}", value, #flags_name_str)
+                    ));
+                }
+                Ok(flags)
+            }
+        }
+    };
+    let flags_ti: Type =
+        ast::parse_ty_with_given_span(&flags_name.to_string(), flags_info.name.span())
+            .map_err(|err| DiagnosticError::from_syn_err(flags_info.src_id, err))?;
+    conv_map.find_or_alloc_rust_type_that_implements(
+        &flags_ti,
+        &[BITFLAGS_TRAIT_NAME],
+        flags_info.src_id,
+    );
+    conv_map.alloc_foreign_type(ForeignTypeS {
+        name: TypeName::new(
+            flags_info.name.to_string(),
+            (flags_info.src_id, flags_info.name.span()),
+        ),
+        provides_by_module: vec![],
+        into_from_rust: None,
+        from_into_rust: None,
+        name_prefix: None,
+    })?;
+
+    let module_initialization_code = quote! {
+        {
+            m.add(py, #flags_name_str, #wrapper_mod_name::create_class(py)?)?;
+        }
+    };
+    Ok((class_code, module_initialization_code))
+}
+
 fn generate_rust_instance_field_and_methods(
     class: &ForeignClassInfo,
     conv_map: &mut TypeMap,
@@ -290,6 +442,8 @@ fn generate_method_code(
     class: &ForeignClassInfo,
     method: &ForeignMethod,
     conv_map: &mut TypeMap,
+    code_deps: &mut Vec<TokenStream>,
+    exported_items: &ExportedItems,
 ) -> Result<TokenStream> {
     if method.is_dummy_constructor() {
         return Ok(TokenStream::new());
@@ -315,6 +469,7 @@ fn generate_method_code(
                 method.span(),
                 class.src_id,
                 conv_map,
+                code_deps,
                 &named_arg.name,
                 true,
             )?;
@@ -323,7 +478,7 @@ fn generate_method_code(
         .collect::<Result<Vec<_>>>()?
         .into_iter()
         .unzip();
-    if let Some(self_convertion) = self_type_conversion(class, method, conv_map)? {
+    if let Some(self_convertion) = self_type_conversion(class, method, conv_map, code_deps)? {
         args_convertions.insert(0, self_convertion);
     }
     let mut args_list_tokens = args_list
@@ -351,13 +506,17 @@ fn generate_method_code(
         method.span(),
         class.src_id,
         conv_map,
+        code_deps,
         quote! {
             #method_rust_path(#( #args_convertions ),*)
         },
     )?;
     let docstring = if !method_name.to_string().starts_with("__") {
         parse::<TokenStream>(
-            &("/// ".to_owned() + &method.doc_comments.as_slice().join("\n/// ")),
+            &("/// ".to_owned()
+                + &DocComment::parse(&method.doc_comments)
+                    .to_python_docstring(exported_items)
+                    .join("\n/// ")),
             class.src_id,
         )?
     } else {
@@ -409,6 +568,7 @@ fn self_type_conversion(
     class: &ForeignClassInfo,
     method: &ForeignMethod,
     conv_map: &mut TypeMap,
+    code_deps: &mut Vec<TokenStream>,
 ) -> Result<Option<TokenStream>> {
     if let MethodVariant::Method(self_variant) = method.variant {
         let self_type = &class
@@ -433,6 +593,7 @@ fn self_type_conversion(
                 method.span(),
                 class.src_id,
                 conv_map,
+                code_deps,
                 "self",
                 true,
             )?
@@ -458,12 +619,20 @@ fn generate_conversion_for_argument(
     method_span: Span,
     src_id: SourceId,
     conv_map: &mut TypeMap,
+    code_deps: &mut Vec<TokenStream>,
     arg_name: &str,
     reference_allowed: bool,
 ) -> Result<(Type, TokenStream)> {
     let arg_name_ident: TokenStream = parse(arg_name, src_id)?;
-    if is_cpython_supported_type(rust_type) {
-        Ok((rust_type.ty.clone(), arg_name_ident))
+    if let Some((ty, conversion)) = map_type::map_arg_type(
+        conv_map,
+        rust_type,
+        arg_name,
+        (src_id, method_span),
+        MapToForeignFlag::FastSearch,
+        code_deps,
+    )? {
+        Ok((ty, conversion))
     } else if let Some((ty, conversion)) = if_exported_class_generate_argument_conversion(
         rust_type,
         conv_map,
@@ -476,6 +645,9 @@ fn generate_conversion_for_argument(
     } else if rust_type
         .implements
         .contains_path(&parse(ENUM_TRAIT_NAME, src_id)?)
+        || rust_type
+            .implements
+            .contains_path(&parse(BITFLAGS_TRAIT_NAME, src_id)?)
     {
         let enum_py_mod: Ident = parse(&py_wrapper_mod_name(&rust_type.normalized_name), src_id)?;
         Ok((
@@ -490,6 +662,7 @@ fn generate_conversion_for_argument(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             "inner",
             false,
         )?;
@@ -508,6 +681,7 @@ fn generate_conversion_for_argument(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             "inner",
             false,
         )?;
@@ -523,6 +697,7 @@ fn generate_conversion_for_argument(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             "inner",
             false,
         )?;
@@ -545,6 +720,7 @@ fn generate_conversion_for_argument(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             arg_name,
             false,
         )?;
@@ -554,6 +730,15 @@ fn generate_conversion_for_argument(
                 &#inner_conversion
             },
         ))
+    } else if let Some((ty, conversion)) = map_type::map_arg_type(
+        conv_map,
+        rust_type,
+        arg_name,
+        (src_id, method_span),
+        MapToForeignFlag::FullSearch,
+        code_deps,
+    )? {
+        Ok((ty, conversion))
     } else {
         Err(DiagnosticError::new(
             src_id,
@@ -568,6 +753,7 @@ fn generate_conversion_for_return(
     method_span: Span,
     src_id: SourceId,
     conv_map: &mut TypeMap,
+    code_deps: &mut Vec<TokenStream>,
     rust_call: TokenStream,
 ) -> Result<(Type, TokenStream)> {
     if rust_type.ty == parse_type! { () } {
@@ -577,8 +763,15 @@ fn generate_conversion_for_return(
                 {#rust_call; py.None()}
             },
         ))
-    } else if is_cpython_supported_type(rust_type) {
-        Ok((rust_type.ty.clone(), rust_call))
+    } else if let Some((ty, conversion)) = map_type::map_return_type(
+        conv_map,
+        rust_type,
+        &rust_call,
+        (src_id, method_span),
+        MapToForeignFlag::FastSearch,
+        code_deps,
+    )? {
+        Ok((ty, conversion))
     } else if let Some((ty, conversion)) = if_exported_class_generate_return_conversion(
         &rust_type,
         conv_map,
@@ -591,10 +784,22 @@ fn generate_conversion_for_return(
         .implements
         .contains_path(&parse(ENUM_TRAIT_NAME, src_id)?)
     {
+        let enum_py_mod: Ident = parse(&py_wrapper_mod_name(&rust_type.normalized_name), src_id)?;
         Ok((
             parse_type!(u32),
             quote! {
-                #rust_call as u32
+                super::#enum_py_mod::to_u32(#rust_call)
+            },
+        ))
+    } else if rust_type
+        .implements
+        .contains_path(&parse(BITFLAGS_TRAIT_NAME, src_id)?)
+    {
+        let flags_py_mod: Ident = parse(&py_wrapper_mod_name(&rust_type.normalized_name), src_id)?;
+        Ok((
+            parse_type!(cpython::PyObject),
+            quote! {
+                super::#flags_py_mod::to_py(py, #rust_call)?
             },
         ))
     } else if let Some(inner) = ast::if_option_return_some_type(&rust_type) {
@@ -603,6 +808,7 @@ fn generate_conversion_for_return(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             quote! {inner},
         )?;
         Ok((
@@ -620,6 +826,7 @@ fn generate_conversion_for_return(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             quote! {inner},
         )?;
         Ok((
@@ -634,6 +841,7 @@ fn generate_conversion_for_return(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             quote! {inner},
         )?;
         Ok((
@@ -648,6 +856,7 @@ fn generate_conversion_for_return(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             quote! {ok_inner},
         )?;
         Ok((
@@ -668,6 +877,7 @@ fn generate_conversion_for_return(
             method_span,
             src_id,
             conv_map,
+            code_deps,
             quote! {(#rust_call).clone()},
         )
     } else if let Type::Tuple(ref tuple) = rust_type.ty {
@@ -685,6 +895,7 @@ fn generate_conversion_for_return(
                     method_span,
                     src_id,
                     conv_map,
+                    code_deps,
                     quote! {tuple.#i_ident},
                 )
             })
@@ -702,6 +913,15 @@ fn generate_conversion_for_return(
                 }
             },
         ))
+    } else if let Some((ty, conversion)) = map_type::map_return_type(
+        conv_map,
+        rust_type,
+        &rust_call,
+        (src_id, method_span),
+        MapToForeignFlag::FullSearch,
+        code_deps,
+    )? {
+        Ok((ty, conversion))
     } else {
         Err(DiagnosticError::new(
             src_id,
@@ -711,14 +931,6 @@ fn generate_conversion_for_return(
     }
 }
 
-fn is_cpython_supported_type(rust_type: &RustType) -> bool {
-    let primitive_types = [
-        "bool", "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32",
-        "f64", "String", "& str",
-    ];
-    primitive_types.contains(&rust_type.normalized_name.as_str())
-}
-
 fn extract_return_type(syn_return_type: &syn::ReturnType) -> Type {
     match syn_return_type {
         syn::ReturnType::Default => {
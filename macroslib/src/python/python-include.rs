@@ -2,31 +2,80 @@
 #[macro_use] extern crate cpython;
 use cpython::{ObjectProtocol as CPythonObjectProtocol, PythonObjectWithTypeObject as CPythonObjectWithTypeObject};
 
-// It is currently unused.
-mod swig_foreign_types_map {
-    #![swig_foreigner_type = "()"]
-    #![swig_rust_type = "()"]
-    #![swig_foreigner_type = "bool"]
-    #![swig_rust_type = "bool"]
-    #![swig_foreigner_type = "i8"]
-    #![swig_rust_type = "i8"]
-    #![swig_foreigner_type = "i16"]
-    #![swig_rust_type = "i16"]
-    #![swig_foreigner_type = "i32"]
-    #![swig_rust_type = "i32"]
-    #![swig_foreigner_type = "u8"]
-    #![swig_rust_type = "u8"]
-    #![swig_foreigner_type = "u16"]
-    #![swig_rust_type = "u16"]
-    #![swig_foreigner_type = "u32"]
-    #![swig_rust_type = "u32"]
-    #![swig_foreigner_type = "f32"]
-    #![swig_rust_type = "f32"]
-    #![swig_foreigner_type = "f64"]
-    #![swig_rust_type = "f64"]
-    #![swig_foreigner_type = "String"]
-    #![swig_rust_type = "String"]
-}
+foreign_typemap!(
+    (r_type) bool;
+    (f_type) "bool";
+);
+
+foreign_typemap!(
+    (r_type) i8;
+    (f_type) "i8";
+);
+
+foreign_typemap!(
+    (r_type) i16;
+    (f_type) "i16";
+);
+
+foreign_typemap!(
+    (r_type) i32;
+    (f_type) "i32";
+);
+
+foreign_typemap!(
+    (r_type) i64;
+    (f_type) "i64";
+);
+
+foreign_typemap!(
+    (r_type) isize;
+    (f_type) "isize";
+);
+
+foreign_typemap!(
+    (r_type) u8;
+    (f_type) "u8";
+);
+
+foreign_typemap!(
+    (r_type) u16;
+    (f_type) "u16";
+);
+
+foreign_typemap!(
+    (r_type) u32;
+    (f_type) "u32";
+);
+
+foreign_typemap!(
+    (r_type) u64;
+    (f_type) "u64";
+);
+
+foreign_typemap!(
+    (r_type) usize;
+    (f_type) "usize";
+);
+
+foreign_typemap!(
+    (r_type) f32;
+    (f_type) "f32";
+);
+
+foreign_typemap!(
+    (r_type) f64;
+    (f_type) "f64";
+);
+
+foreign_typemap!(
+    (r_type) String;
+    (f_type) "String";
+);
+
+foreign_typemap!(
+    (r_type) &str;
+    (f_type) "&str";
+);
 
 fn swig_collect_error_message(error: &dyn std::error::Error) -> String {
     if let Some(source) = error.source() {
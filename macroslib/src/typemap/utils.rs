@@ -1,4 +1,4 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::quote;
 use rustc_hash::FxHashSet;
 use std::{
@@ -18,7 +18,8 @@ use crate::{
         ForeignTypeInfo, RustTypeIdx, TypeMap,
     },
     types::{
-        ForeignClassInfo, ForeignInterfaceMethod, ForeignMethod, MethodVariant, SelfTypeVariant,
+        ForeignClassInfo, ForeignEnumInfo, ForeignInterfaceMethod, ForeignMethod, MethodVariant,
+        SelfTypeVariant,
     },
 };
 
@@ -381,6 +382,58 @@ where
     Ok(())
 }
 
+/// Generate implementation of `SwigForeignBitflags` for type created by `bitflags!`,
+/// value is passed as its real bits, and `from_i32` returns `None` in case of unknown bits
+pub(crate) fn rust_code_for_bitflags(fenum: &ForeignEnumInfo) -> TokenStream {
+    let flags_name = &fenum.name;
+    let width_check = rust_code_for_bitflags_width_check(fenum);
+    quote! {
+        #width_check
+        impl SwigForeignBitflags for #flags_name {
+            fn as_i32(&self) -> i32 {
+                self.bits() as i32
+            }
+            fn from_i32(x: i32) -> Option<Self> {
+                #flags_name::from_bits(x as _).filter(|flags| flags.bits() as i32 == x)
+            }
+        }
+    }
+}
+
+/// Bits of flags are passed as 32 bit integer, type of bits is unknown
+/// during code generation, so check its size during compilation of generated code
+pub(crate) fn rust_code_for_bitflags_width_check(fenum: &ForeignEnumInfo) -> TokenStream {
+    let flags_name = &fenum.name;
+    let msg = format!(
+        "foreign_bitflags!({}): flags wider than 32 bits are not supported",
+        flags_name
+    );
+    quote! {
+        const _: () = assert!(::std::mem::size_of::<#flags_name>() <= 4, #msg);
+    }
+}
+
+/// Values of Rust constants are unknown during code generation,
+/// so generate function that returns bits of `item`-th flag of `foreign_bitflags!`
+/// for foreign code, or `0` if there is no such flag
+pub(crate) fn rust_code_for_bitflags_item_bits(
+    fenum: &ForeignEnumInfo,
+    func_name: &str,
+) -> TokenStream {
+    let func_name = Ident::new(func_name, Span::call_site());
+    let indexes = (0..fenum.items.len()).map(|i| Literal::u32_unsuffixed(i as u32));
+    let rust_names = fenum.items.iter().map(|x| &x.rust_name);
+    quote! {
+        #[no_mangle]
+        pub extern "C" fn #func_name(item: u32) -> u32 {
+            match item {
+                #(#indexes => #rust_names.bits() as u32,)*
+                _ => 0,
+            }
+        }
+    }
+}
+
 impl FileOperationsRegistrator for FxHashSet<PathBuf> {
     fn register(&mut self, p: &Path) {
         self.insert(p.into());
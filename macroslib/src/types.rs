@@ -8,7 +8,7 @@ use crate::{
     error::{DiagnosticError, Result, SourceIdSpan},
     source_registry::SourceId,
     typemap::ast::DisplayToTokens,
-    SMART_PTR_COPY_TRAIT,
+    JAVA_RECORD, SMART_PTR_COPY_TRAIT,
 };
 
 #[derive(Debug, Clone)]
@@ -84,6 +84,10 @@ impl ForeignClassInfo {
     pub fn clone_derived(&self) -> bool {
         self.derive_list.iter().any(|x| x == "Clone")
     }
+    /// class should be represented as Java record
+    pub fn java_record_derived(&self) -> bool {
+        self.derive_list.iter().any(|x| x == JAVA_RECORD)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -252,6 +256,8 @@ pub(crate) struct ForeignEnumInfo {
     pub(crate) items: Vec<ForeignEnumItem>,
     pub(crate) doc_comments: Vec<String>,
     pub(crate) derive_list: Vec<String>,
+    /// Created by `foreign_bitflags!`, items are flags that can be combined
+    pub(crate) is_bitflags: bool,
 }
 
 impl ForeignEnumInfo {
@@ -265,6 +271,8 @@ pub(crate) struct ForeignEnumItem {
     pub(crate) name: Ident,
     pub(crate) rust_name: syn::Path,
     pub(crate) doc_comments: Vec<String>,
+    /// Value in foreign language, explicit or previous plus one like in Rust
+    pub(crate) value: u32,
 }
 
 pub(crate) struct ForeignInterface {
@@ -301,8 +309,23 @@ impl ForeignInterfaceMethod {
     }
 }
 
+/// Rust functions to call when foreign language loads/unloads library
+pub(crate) struct ForeignLifecycle {
+    pub(crate) src_id: SourceId,
+    pub(crate) span: Span,
+    pub(crate) on_load: Option<syn::Path>,
+    pub(crate) on_unload: Option<syn::Path>,
+}
+
+impl ForeignLifecycle {
+    pub(crate) fn src_id_span(&self) -> SourceIdSpan {
+        (self.src_id, self.span)
+    }
+}
+
 pub(crate) enum ItemToExpand {
     Class(Box<ForeignClassInfo>),
     Interface(ForeignInterface),
     Enum(ForeignEnumInfo),
+    Lifecycle(ForeignLifecycle),
 }
@@ -0,0 +1,3 @@
+"void onSave(int a0) throws Exception;";
+"double onLoad(int a0) throws Exception;";
+"boolean onStateChanged(boolean a0);";
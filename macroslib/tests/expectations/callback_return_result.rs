@@ -0,0 +1,6 @@
+foreign_callback!(callback SomeObserver {
+    self_type SomeTrait;
+    onSave = SomeTrait::on_save(&self, _: i32) -> Result<(), JavaException>;
+    onLoad = SomeTrait::on_load(&self, _: i32) -> Result<f64, MyError>;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: bool) -> bool;
+});
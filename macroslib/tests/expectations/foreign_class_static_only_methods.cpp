@@ -5,14 +5,14 @@ class UtilsWrapper;
 using Utils = UtilsWrapper<true>;
 using UtilsRef = UtilsWrapper<false>;
 
-//This is documentation comment
+/// This is documentation comment
 template<bool OWN_DATA>
 class UtilsWrapper {
 public:
     using value_type = UtilsWrapper<true>;
     friend class UtilsWrapper<true>;
     friend class UtilsWrapper<false>;
-    //Very important function
+    /// Very important function
     static int32_t f(int32_t a0) noexcept;
 
 };
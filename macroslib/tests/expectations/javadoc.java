@@ -5,9 +5,9 @@ public final class Foo {
     /**
      * some text about the new function
      * 
-     * ```
+     * <pre>
      * some markdown example in the text
-     * ```
+     * </pre>
      * 
      * @param id - some number
      * @param desc - more information
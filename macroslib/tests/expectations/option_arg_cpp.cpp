@@ -39,7 +39,7 @@ r#"template<bool OWN_DATA>
     inline void FooWrapper<OWN_DATA>::f7(const Boo * x) noexcept
     {
 
-        struct CRustClassOptBoo a0 = CRustClassOptBoo { (x != nullptr) ? static_cast<BooOpaque *>(* x) : nullptr };
+        struct CRustClassOptBoo a0 = CRustClassOptBoo { (x != nullptr) ? static_cast<Boo::CForeignType *>(* x) : nullptr };
 
         Foo_f7(std::move(a0));
     }"#;
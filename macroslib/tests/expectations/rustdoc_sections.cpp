@@ -0,0 +1,15 @@
+r#"/// Storage of `Item`s
+template<bool OWN_DATA>
+class BooWrapper {"#;
+
+r#"/// Find item by id, see also Boo::len
+    ///
+    /// @code
+    /// let item = boo.find(1, "a")?;
+    /// @endcode
+    ///
+    /// @param id identifier of [Item]
+    /// @param name name of item
+    /// @return Copy of found item
+    /// @par Errors
+    /// If there is no such item"#;
@@ -0,0 +1,18 @@
+r#"/**
+ * Storage of {@code Item}s
+ */
+public final class Boo {"#;
+
+r#"/**
+     * Find item by id, see also {@link Boo#len}
+     * 
+     * <pre>
+     * let item = boo.find(1, "a")?;
+     * </pre>
+     * 
+     * @param id identifier of [Item]
+     * @param name name of item
+     * @return Copy of found item
+     * @throws Exception If there is no such item
+     */
+    public final int find(int id, @NonNull String name) throws Exception"#;
@@ -0,0 +1,27 @@
+foreign_class!(
+/// Storage of [`Item`]s
+class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    /// Find item by id, see also [`Boo::len`]
+    ///
+    /// ```
+    /// # let boo = Boo::default();
+    /// let item = boo.find(1, "a")?;
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - identifier of [Item]
+    /// * `name` - name of item
+    ///
+    /// # Returns
+    ///
+    /// Copy of found item
+    ///
+    /// # Errors
+    ///
+    /// If there is no such item
+    fn Boo::find(&self, id: i32, name: &str) -> Result<i32, String>;
+    fn Boo::len(&self) -> usize;
+});
@@ -4,10 +4,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use flapigen::{rustfmt_cnt, CppConfig, Generator, JavaConfig, LanguageConfig, RustEdition};
+use flapigen::{
+    rustfmt_cnt, CConfig, CppConfig, CppSlice, CppStrView, CppVariant, DotNetConfig, Generator,
+    JavaCallbackExceptionPolicy, JavaConfig, JavaPanamaConfig, JavaThreadAttachPolicy,
+    JavaUnsignedIntegers, LanguageConfig, PythonConfig, RustEdition,
+};
 use log::warn;
 use syn::Token;
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
 
 include!(concat!(env!("OUT_DIR"), "/test_expectations.rs"));
 
@@ -230,6 +234,72 @@ foreign_class!(class TestEnumClass {
     }
 }
 
+#[test]
+fn test_foreign_enum_explicit_values() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_enum_explicit_values";
+    let src = r#"
+foreign_enum!(enum MyEnum {
+  ITEM1 = MyEnum::Item1,
+  ITEM2 = MyEnum::Item2 = 10,
+  ITEM3 = MyEnum::Item3,
+});
+
+foreign_class!(class TestEnumClass {
+    self_type Moo;
+    constructor Moo::default() -> Moo;
+    fn Moo::next_enum(v: MyEnum) -> MyEnum;
+});
+"#;
+    let java_code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("{}", java_code.foreign_code);
+    for line in &[
+        "    ITEM1(0),",
+        "    ITEM2(10),",
+        "    ITEM3(11);",
+        "case 11: return ITEM3;",
+    ] {
+        assert!(java_code.foreign_code.contains(line), "no {}", line);
+    }
+
+    let cpp_code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("{}", cpp_code.foreign_code);
+    assert!(cpp_code.foreign_code.contains("ITEM2 = 10,\nITEM3 = 11\n"));
+    assert!(cpp_code.rust_code.contains("MyEnum :: Item3 => 11u32"));
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::PythonConfig(PythonConfig::new(
+        "example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[]).unwrap();
+    println!("python rust_code: {}", code.rust_code);
+    for line in &[
+        "static ITEM2 = 10u32 ;",
+        "static ITEM3 = 11u32 ;",
+        "11u32 => Ok (super :: MyEnum :: Item3) ,",
+        "super :: MyEnum :: Item3 => 11u32 ,",
+        "super :: py_my_enum :: to_u32 (",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+    assert!(!code.rust_code.contains("as u32"));
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::DotNetConfig(DotNetConfig::new(
+        "example".into(),
+        tmp_dir.path().into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[".cs"]).unwrap();
+    println!("cs_code: {}", code.foreign_code);
+    assert!(code
+        .foreign_code
+        .contains("Item1 = 0,Item2 = 10,Item3 = 11"));
+    assert!(code.rust_code.contains("MyEnum :: Item3 => 11u32"));
+}
+
 #[test]
 fn test_return_result_type_with_object() {
     let _ = env_logger::try_init();
@@ -459,6 +529,1582 @@ B = 1"#
     tmp_dir.close().unwrap();
 }
 
+#[test]
+fn test_java_callback_exception_policy() {
+    let _ = env_logger::try_init();
+    let name = "java_callback_exception_policy";
+    let src = r#"
+foreign_callback!(callback SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32) -> bool;
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    fn Foo::subscribe(&mut self, _: Box<dyn SomeTrait>);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_callback_exception_policy(JavaCallbackExceptionPolicy::Panic)
+    })
+    .unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains("if let Some(ex) = JavaException::take_pending(env) {"));
+    assert!(!rust_code.contains("\": java throw exception\")"));
+    assert!(!rust_code.contains("JniRethrowGuard::new(env)"));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_callback_exception_policy(JavaCallbackExceptionPolicy::Rethrow)
+    })
+    .unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains("jni_stash_pending_exception(env);"));
+    // thread without native method on the stack has nobody to rethrow exception
+    assert!(rust_code.contains("if SWIG_JNI_RETHROW_GUARDS.with(|x| x.get()) == 0 {"));
+    assert!(rust_code.contains(
+        r#"pub extern "C" fn Java_org_example_Foo_do_1subscribe(
+    env: *mut JNIEnv,
+    _: jclass,
+    this: jlong,
+    a0: jobject,
+) -> () {
+    let _swig_rethrow_guard = JniRethrowGuard::new(env);"#
+    ));
+}
+
+#[test]
+fn test_java_thread_attach_policy() {
+    let _ = env_logger::try_init();
+    let name = "java_thread_attach_policy";
+    let src = r#"
+foreign_callback!(callback SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains(
+        "const SWIG_JNI_THREAD_ATTACH_POLICY: JniThreadAttachPolicy = JniThreadAttachPolicy::DetachAfterCall;"
+    ));
+    assert!(rust_code.contains("const SWIG_JNI_ATTACHED_THREAD_NAME: Option<&str> = None;"));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_thread_attach_policy(JavaThreadAttachPolicy::Daemon)
+            .attached_thread_name("rust-worker".into())
+            .attached_thread_group("RustThreads".into())
+    })
+    .unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains(
+        "const SWIG_JNI_THREAD_ATTACH_POLICY: JniThreadAttachPolicy = JniThreadAttachPolicy::Daemon;"
+    ));
+    assert!(rust_code
+        .contains(r#"const SWIG_JNI_ATTACHED_THREAD_NAME: Option<&str> = Some("rust-worker");"#));
+    assert!(rust_code
+        .contains(r#"const SWIG_JNI_ATTACHED_THREAD_GROUP: Option<&str> = Some("RustThreads");"#));
+}
+
+#[test]
+fn test_java_register_natives() {
+    let _ = env_logger::try_init();
+    let name = "java_register_natives";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self, _: &str) -> String;
+    fn Foo::g(_: Option<f64>) -> i64;
+    fn Foo::h(_: &[i32]);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_register_natives(true)
+    })
+    .unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    println!("java_code: {}", code.foreign_code);
+    assert!(!rust_code.contains("#[no_mangle]\npub extern \"C\" fn Java_"));
+    assert!(rust_code.contains("extern \"C\" fn Java_org_example_Foo_init("));
+    let flat_rust_code = rust_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    for (name, sig) in &[
+        ("init", "(I)J"),
+        ("do_f", "(JLjava/lang/String;)Ljava/lang/String;"),
+        ("g", "(Ljava/lang/Double;)J"),
+        ("h", "([I)V"),
+        ("do_delete", "(J)V"),
+    ] {
+        assert!(
+            flat_rust_code.contains(&format!(
+                "name: swig_c_str!(\"{}\") as *mut ::std::os::raw::c_char, signature: swig_c_str!(\"{}\")",
+                name, sig
+            )),
+            "no registration for {} {}",
+            name,
+            sig
+        );
+    }
+    assert!(rust_code.contains("swig_jni_register_natives(env);"));
+    // keep rules are generated without `generate_proguard_rules`
+    assert!(code
+        .foreign_code
+        .contains("-keep class org.example.Foo {\n    native <methods>;\n    long mNativeObj;\n}"));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains("#[no_mangle]\npub extern \"C\" fn Java_org_example_Foo_init("));
+    assert!(!rust_code.contains("swig_jni_register_natives"));
+    assert!(!code.foreign_code.contains("-keep class"));
+}
+
+#[test]
+fn test_java_boxed_byte_short_signatures() {
+    let _ = env_logger::try_init();
+    let name = "java_boxed_byte_short_signatures";
+    let src = r#"
+foreign_class!(class Foo {
+    fn f(_: Option<i8>) -> i32;
+    fn f(_: Option<i16>) -> i32;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains("fn Java_org_example_Foo_f__Ljava_lang_Byte_2("));
+    assert!(rust_code.contains("fn Java_org_example_Foo_f__Ljava_lang_Short_2("));
+
+    let code =
+        parse_java_code_with_config(name, Source::Str(src), |cfg| cfg.use_register_natives(true))
+            .unwrap();
+    let flat_rust_code = code
+        .rust_code
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!("rust_code: {}", flat_rust_code);
+    assert!(flat_rust_code.contains("\"(Ljava/lang/Byte;)I\""));
+    assert!(flat_rust_code.contains("\"(Ljava/lang/Short;)I\""));
+}
+
+#[test]
+fn test_java_proguard_rules() {
+    let _ = env_logger::try_init();
+    let name = "java_proguard_rules";
+    let src = r#"
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+
+foreign_callback!(callback SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32);
+});
+
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self, _: MyEnum) -> MyEnum;
+    fn Foo::set_observer(&mut self, _: Box<dyn SomeTrait>);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    assert!(!code.foreign_code.contains("-keep class"));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.generate_proguard_rules(true)
+    })
+    .unwrap();
+    println!("java_code: {}", code.foreign_code);
+    for rule in &[
+        "-keep class org.example.Foo {\n    native <methods>;\n    long mNativeObj;\n}",
+        "-keep class org.example.MyEnum {\n    org.example.MyEnum ITEM1;\n    org.example.MyEnum ITEM2;\n}",
+        "-keep interface org.example.SomeObserver {\n    <methods>;\n}",
+        "-keepclassmembers class * implements org.example.SomeObserver {\n    <methods>;\n}",
+    ] {
+        assert!(code.foreign_code.contains(rule), "no rule: {}", rule);
+    }
+    assert!(!code.foreign_code.contains("-keep class java."));
+}
+
+#[test]
+fn test_java_util_list() {
+    let _ = env_logger::try_init();
+    let name = "java_util_list";
+    let src = r#"
+foreign_class!(
+#[derive(Clone)]
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+});
+
+foreign_class!(class Boo {
+    fn get_foos() -> Vec<Foo>;
+    fn set_foos(_: Vec<Foo>);
+    fn foos_slice() -> &'static [Foo];
+    fn count_foos(_: &[Foo]) -> usize;
+    fn get_strings() -> Vec<String>;
+    fn set_strings(_: Vec<String>);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_java_util_list(true)
+    })
+    .unwrap();
+    println!("java_code: {}", code.foreign_code);
+    let java_code = code.foreign_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    for decl in &[
+        "public static native @NonNull java.util.List<Foo> get_foos();",
+        "public static native void set_foos(@NonNull java.util.List<Foo> a0);",
+        "public static native @NonNull java.util.List<Foo> foos_slice();",
+        "public static native long count_foos(@NonNull java.util.List<Foo> a0);",
+        "public static native @NonNull java.util.List<String> get_strings();",
+        "public static native void set_strings(@NonNull java.util.List<String> a0);",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains("fn vec_of_objects_to_java_list<"));
+    assert!(rust_code.contains("JStringObjectsArray = vec_string_to_java_list(ret, env);"));
+    assert!(rust_code.contains(r#"swig_c_str!("java/util/ArrayList")"#));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let java_code = code.foreign_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    for decl in &[
+        "public static native @NonNull Foo [] get_foos();",
+        "public static native void set_foos(@NonNull Foo [] a0);",
+        "public static native @NonNull Foo [] foos_slice();",
+        "public static native long count_foos(@NonNull Foo [] a0);",
+        "public static native @NonNull java.lang.String [] get_strings();",
+        "public static native void set_strings(@NonNull java.lang.String [] a0);",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(!rust_code.contains("java_list"));
+    assert!(!rust_code.contains(r#"swig_c_str!("java/util/ArrayList")"#));
+    assert!(rust_code.contains("NewObjectArray"));
+}
+
+#[test]
+fn test_java_time() {
+    let _ = env_logger::try_init();
+    let name = "java_time";
+    let src = r#"
+foreign_class!(class Foo {
+    fn timeout() -> Duration;
+    fn set_timeout(_: Duration);
+    fn now() -> SystemTime;
+    fn set_time(_: SystemTime);
+    fn today() -> NaiveDate;
+    fn set_day(_: NaiveDate);
+    fn local_now() -> NaiveDateTime;
+    fn set_local_time(_: NaiveDateTime);
+    fn utc_now() -> DateTime<Utc>;
+    fn set_utc_time(_: DateTime<Utc>);
+});
+"#;
+    let code =
+        parse_java_code_with_config(name, Source::Str(src), |cfg| cfg.use_java_time(true)).unwrap();
+    println!("java_code: {}", code.foreign_code);
+    let java_code = code.foreign_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    for decl in &[
+        "public static native @NonNull java.time.Duration timeout();",
+        "public static void set_timeout(@NonNull java.time.Duration a0) {",
+        "if (a0.isNegative()) {",
+        "public static native @NonNull java.time.Instant now();",
+        "public static void set_time(@NonNull java.time.Instant a0) {",
+        "public static native @NonNull java.time.LocalDate today();",
+        "public static void set_day(@NonNull java.time.LocalDate a0) {",
+        "public static native @NonNull java.time.LocalDateTime local_now();",
+        "public static void set_local_time(@NonNull java.time.LocalDateTime a0) {",
+        "public static native @NonNull java.time.Instant utc_now();",
+        "public static void set_utc_time(@NonNull java.time.Instant a0) {",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains(r#"swig_c_str!("java/time/Instant")"#));
+    assert!(rust_code.contains("match java_time_instant_to_system_time(env, a0) {"));
+    assert!(rust_code.contains("None => return JniInvalidValue::jni_invalid_value(),"));
+    assert!(rust_code.contains(r#"swig_c_str!("java/time/DateTimeException")"#));
+    assert!(!rust_code.contains(".expect(\"java.time"));
+
+    let code = parse_java_code_with_config(
+        name,
+        Source::Str("foreign_class!(class Foo { fn now() -> SystemTime; });"),
+        |cfg| cfg,
+    )
+    .unwrap();
+    let java_code = code.foreign_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    assert!(java_code.contains("public static @NonNull java.util.Date now() {"));
+}
+
+#[test]
+fn test_java_unsigned_integers() {
+    let _ = env_logger::try_init();
+    let name = "java_unsigned_integers";
+    let src = r#"
+foreign_class!(class Foo {
+    fn get_u8() -> u8;
+    fn set_u8(_: u8);
+    fn get_u32() -> u32;
+    fn set_u32(_: u32);
+    fn get_u64() -> u64;
+    fn set_u64(_: u64);
+});
+"#;
+    let java_code_for = |mode: JavaUnsignedIntegers| {
+        let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+            cfg.use_unsigned_integers(mode)
+        })
+        .unwrap();
+        println!("{:?} java_code: {}", mode, code.foreign_code);
+        code.foreign_code
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let java_code = java_code_for(JavaUnsignedIntegers::Widen);
+    for decl in &[
+        "public static native short get_u8();",
+        "public static native void set_u8(short a0);",
+        "public static native long get_u32();",
+        "public static native long get_u64();",
+        "public static native void set_u64(long a0);",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+
+    let java_code = java_code_for(JavaUnsignedIntegers::WidenChecked);
+    for decl in &[
+        "public static native short get_u8();",
+        "public static void set_u8(short a0) { if (a0 < 0 || a0 > 0xFF) { throw new ArithmeticException(",
+        "public static void set_u32(long a0) { if (a0 < 0 || a0 > 0xFFFFFFFFL) {",
+        "public static long get_u64() { long ret = do_get_u64(); if (ret < 0) { throw new ArithmeticException(",
+        "public static void set_u64(long a0) { if (a0 < 0) {",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+
+    let java_code = java_code_for(JavaUnsignedIntegers::WidenBigInteger);
+    for decl in &[
+        "public static void set_u32(long a0) { if (a0 < 0 || a0 > 0xFFFFFFFFL) {",
+        "public static @NonNull java.math.BigInteger get_u64() {",
+        "new java.math.BigInteger(Long.toUnsignedString(ret));",
+        "public static void set_u64(@NonNull java.math.BigInteger a0) { if (a0.signum() < 0 || a0.bitLength() > 64) {",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+
+    let java_code = java_code_for(JavaUnsignedIntegers::SameWidth);
+    for decl in &[
+        "public static native /*u8*/byte get_u8();",
+        "public static native void set_u8(/*u8*/byte a0);",
+        "public static native /*u32*/int get_u32();",
+        "public static native void set_u32(/*u32*/int a0);",
+        "public static native /*u64*/long get_u64();",
+        "public static native void set_u64(/*u64*/long a0);",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+
+    let code = parse_java_code_with_config(
+        name,
+        Source::Str(
+            r#"
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onChange = Observer::on_change(&self, x: u8) -> u32;
+});
+foreign_class!(class Foo {
+    fn subscribe(_: Box<dyn Observer>);
+});
+"#,
+        ),
+        |cfg| cfg.use_unsigned_integers(JavaUnsignedIntegers::SameWidth),
+    )
+    .unwrap();
+    assert!(code
+        .foreign_code
+        .contains("/*u32*/int onChange(/*u8*/byte x);"));
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains(r#"swig_c_str!("(B)I")"#));
+    assert!(rust_code.contains("(**env).CallIntMethod.unwrap()("));
+    assert!(rust_code.contains("a0 as ::std::ffi::c_int"));
+
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_unsigned_integers(JavaUnsignedIntegers::WidenChecked)
+    })
+    .unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains("match <u32 as ::std::convert::TryFrom<jlong>>::try_from(a0) {"));
+    assert!(rust_code.contains("jni_throw_arithmetic_exception("));
+    assert!(!rust_code.contains("a0 as u32"));
+
+    for mode in &[
+        JavaUnsignedIntegers::WidenChecked,
+        JavaUnsignedIntegers::WidenBigInteger,
+    ] {
+        let result = panic::catch_unwind(|| {
+            parse_java_code_with_config(
+                "java_unsigned_callback_return",
+                Source::Str(
+                    r#"
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onChange = Observer::on_change(&self, x: u8) -> u32;
+});
+"#,
+                ),
+                |cfg| cfg.use_unsigned_integers(*mode),
+            )
+            .unwrap();
+        });
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn test_java_record() {
+    let _ = env_logger::try_init();
+    let name = "java_record";
+    let src = r#"
+foreign_class!(
+#[derive(Copy, JavaRecord)]
+class Point {
+    self_type Point;
+    constructor Point::new(x: f64, name: &str, flag: bool) -> Point;
+    fn Point::clone(&self) -> Point;
+    fn Point::x(&self) -> f64;
+    fn Point::name(&self) -> &str;
+    fn Point::flag(&self) -> bool;
+    /// Distance to origin
+    fn Point::length(&self) -> f64;
+    fn Point::origin() -> Point;
+});
+foreign_class!(class Foo {
+    fn Foo::dist(a: &Point, b: Point) -> f64;
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.generate_proguard_rules(true)
+    })
+    .unwrap();
+    let java_code = code
+        .foreign_code
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!("java_code: {}", java_code);
+    for decl in &[
+        "public record Point(double x, @NonNull String name, boolean flag) {",
+        "/** * Distance to origin */ public double length() { return Native.length(this); }",
+        "public static @NonNull Point origin() { return Native.origin(); }",
+        "private static final class Native { private static native double length(@NonNull Point self_);",
+        "private static native @NonNull Point origin(); }",
+        "public static native double dist(@NonNull Point a, @NonNull Point b);",
+        "-keep class org.example.Point { <init>(double, java.lang.String, boolean); double x;",
+        "-keep class org.example.Point$Native { native <methods>; }",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+    assert!(!java_code.contains("mNativeObj"));
+    assert!(!java_code.contains("clone"));
+
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains("fn Java_org_example_Point_00024Native_length("));
+    assert!(rust_code.contains(
+        r#""<init>",
+        "(DLjava/lang/String;Z)V""#
+    ));
+    assert!(rust_code.contains(
+        "(**env).NewObject.unwrap()(env, class, ctor, x, name, flag as ::std::ffi::c_uint)"
+    ));
+    assert!(rust_code.contains("(**env).GetBooleanField.unwrap()(env, obj, field)"));
+    assert!(rust_code.contains("Point::new(x, name, flag)"));
+    assert!(rust_code.contains(
+        r#"unsafe { (**env).DeleteLocalRef.unwrap()(env, name) };
+    if unsafe { (**env).ExceptionCheck.unwrap()(env) } != 0 {"#
+    ));
+    assert!(rust_code.contains(
+        r#"        Point::new(x, name, flag)
+    };
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, name) };"#
+    ));
+    assert!(rust_code.contains(
+        r#"    if obj.is_null() {
+        jni_throw_null_pointer_exception(env, "Point: null record");
+        return None;
+    }"#
+    ));
+    assert!(rust_code.contains(
+        r#"let b: Point = match swig_Point_from_java_record(env, b) {
+        Some(x) => x,
+        None => return JniInvalidValue::jni_invalid_value(),
+    };"#
+    ));
+
+    let result = panic::catch_unwind(|| {
+        parse_java_code_with_config(
+            name,
+            Source::Str(
+                r#"
+foreign_class!(
+#[derive(Clone, JavaRecord)]
+class Point {
+    self_type Point;
+    constructor Point::new(x: f64) -> Point;
+    fn Point::x(&self) -> f64;
+    fn Point::set_x(&mut self, _: f64);
+});
+"#,
+            ),
+            |cfg| cfg,
+        )
+        .expect(name)
+    });
+    assert!(result.is_err());
+    parse_cpp_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+}
+
+#[test]
+fn test_foreign_lifecycle() {
+    let _ = env_logger::try_init();
+    let name = "foreign_lifecycle";
+    let src = r#"
+foreign_lifecycle!(on_load = crate::init_logger; on_unload = flush_caches;);
+foreign_class!(class Foo {
+    fn f() -> i32;
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains("fn swig_jni_on_load() {\n    crate::init_logger();\n}"));
+    assert!(rust_code.contains("fn swig_jni_on_unload() {\n    flush_caches();\n}"));
+    assert!(rust_code.contains("    swig_jni_on_load();\n"));
+    assert!(rust_code.contains("    swig_jni_on_unload();\n"));
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    println!("cpp_code: {}", code.foreign_code);
+    assert!(rust_code
+        .contains("#[no_mangle]\npub extern \"C\" fn org_examples_init() {\n    crate::init_logger();\n}"));
+    assert!(rust_code
+        .contains("#[no_mangle]\npub extern \"C\" fn org_examples_shutdown() {\n    flush_caches();\n}"));
+    assert!(code.foreign_code.contains("void org_examples_init(void);"));
+    assert!(code
+        .foreign_code
+        .contains("inline void shutdown() noexcept { org_examples_shutdown(); }"));
+
+    let code = parse_java_code_with_config(
+        name,
+        Source::Str("foreign_class!(class Foo { fn f() -> i32; });"),
+        |cfg| cfg,
+    )
+    .unwrap();
+    assert!(!code.rust_code.contains("swig_jni_on_load"));
+}
+
+#[test]
+fn test_java_borrowed_reference() {
+    let _ = env_logger::try_init();
+    let name = "java_borrowed_reference";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self) -> i32;
+});
+foreign_class!(class TestReferences {
+    self_type TestReferences;
+    constructor TestReferences::new() -> TestReferences;
+    fn TestReferences::get_foo_ref(&self) -> &Foo;
+    fn TestReferences::get_foo_mut(&mut self) -> &mut Foo;
+    fn TestReferences::take_foo(_: Foo);
+});
+"#;
+    let code = parse_java_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    println!("java_code: {}", code.foreign_code);
+    let java_code = code.foreign_code.split_whitespace().collect::<Vec<_>>().join(" ");
+    for decl in &[
+        "public final class TestReferences implements NativeObjectOwner {",
+        "public final @NonNull Foo get_foo_ref() { long ret = do_get_foo_ref(mNativeObj); Foo convRet = new Foo(InternalPointerMarker.BORROWED_PTR, ret, this);",
+        "Foo convRet = new Foo(InternalPointerMarker.BORROWED_PTR, ret, this);",
+        "public boolean isNativeObjectAlive() { return mNativeObj != 0; }",
+        "int ret = do_f(checkedNativeObj());",
+        "if (mOwner == null) { do_delete(mNativeObj); }",
+        "/*package*/ Foo(InternalPointerMarker marker, long ptr, NativeObjectOwner owner) {",
+        r#"if (a0.mOwner != null) { throw new IllegalStateException("Foo: borrowed object can not be passed by value"); } long a00 = a0.checkedNativeObj(); a0.mNativeObj = 0;"#,
+        "/*package*/ interface NativeObjectOwner {",
+    ] {
+        assert!(java_code.contains(decl), "no declaration: {}", decl);
+    }
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains("let ret: jlong = ret as *const Foo as jlong;"));
+
+    let code = parse_java_code_with_config(
+        name,
+        Source::Str(
+            r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self) -> i32;
+});
+"#,
+        ),
+        |cfg| cfg,
+    )
+    .unwrap();
+    assert!(!code.foreign_code.contains("checkedNativeObj"));
+    assert!(!code.foreign_code.contains("NativeObjectOwner"));
+
+    let result = panic::catch_unwind(|| {
+        parse_java_code_with_config(
+            "java_borrowed_reference_from_static",
+            Source::Str(&format!(
+                "{}{}",
+                src, "foreign_class!(class Boo { fn get_foo() -> &'static Foo; });"
+            )),
+            |cfg| cfg,
+        )
+        .unwrap();
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_java_module() {
+    let _ = env_logger::try_init();
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let java_dir = tmp_dir.path().join("java");
+    let output_dir = java_dir.join("org").join("example");
+    fs::create_dir_all(&output_dir).unwrap();
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(
+        JavaConfig::new(output_dir.clone(), "org.example".into())
+            .java_module("org.example.lib".into())
+            .java_module_requires("static org.jetbrains.annotations".into())
+            .native_library_loader("example".into()),
+    ))
+    .with_pointer_target_width(64);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &rust_src_path,
+        "foreign_class!(class Foo { fn f(_: i32) -> i32; });",
+    )
+    .unwrap();
+    swig_gen.expand(
+        "java_module",
+        &rust_src_path,
+        tmp_dir.path().join("test.rs"),
+    );
+
+    let module_info = fs::read_to_string(java_dir.join("module-info.java")).unwrap();
+    println!("module_info: {}", module_info);
+    assert!(module_info.contains(
+        "module org.example.lib {\n    requires static org.jetbrains.annotations;\n    exports org.example;\n}"
+    ));
+    let loader = fs::read_to_string(output_dir.join("NativeLibraryLoader.java")).unwrap();
+    assert!(loader.contains("package org.example;"));
+    assert!(loader.contains(r#"private static final String LIBRARY_NAME = "example";"#));
+    assert!(loader.contains("public static synchronized void load() {"));
+
+    let result = panic::catch_unwind(|| {
+        parse_java_code_with_config("java_module_wrong_dir", Source::Str(""), |cfg| {
+            cfg.java_module("org.example.lib".into())
+        })
+        .unwrap();
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_dotnet_clone_self_from_smart_pointer() {
+    let _ = env_logger::try_init();
+    let name = "dotnet_clone_self_from_smart_pointer";
+    let src = r#"
+foreign_class!(
+#[derive(Clone)]
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Arc<Foo>;
+});
+foreign_class!(class Boo {
+    fn take(_: Foo) -> i32;
+    fn take_arc(_: Arc<Foo>) -> i32;
+});
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::DotNetConfig(DotNetConfig::new(
+        "example".into(),
+        tmp_dir.path().into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[".cs"]).unwrap();
+    println!("rust_code: {}", code.rust_code);
+    for line in &[
+        "fn Boo_take (a0 : * mut Arc < Foo >) -> i32 { assert ! (! a0 . is_null ()) ; \
+         let a0 = unsafe { (* a0) . as_ref () } ; let a0 = a0 . clone () ;",
+        "fn Boo_take_arc (a0 : * mut Arc < Foo >) -> i32 { assert ! (! a0 . is_null ()) ; \
+         let a0 = unsafe { (* a0) . clone () } ;",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+}
+
+#[test]
+fn test_dotnet_doc_comments() {
+    let _ = env_logger::try_init();
+    let name = "dotnet_doc_comments";
+    let src = r#"
+foreign_class!(
+/// Some counter
+/// with two lines of docs
+class Counter {
+    self_type Counter;
+    constructor Counter::new() -> Counter;
+    /// Add value to counter,
+    /// returns new value
+    fn Counter::add(&mut self, x: i32) -> i32;
+});
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::DotNetConfig(DotNetConfig::new(
+        "example".into(),
+        tmp_dir.path().into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[".cs"]).unwrap();
+    println!("cs_code: {}", code.foreign_code);
+    assert!(code.foreign_code.contains(
+        r#"
+    /// <summary>
+    /// Some counter
+    /// with two lines of docs
+    /// </summary>
+    public class Counter: IDisposable {"#
+    ));
+    assert!(code.foreign_code.contains(
+        r#"
+        /// <summary>
+        /// Add value to counter,
+        /// returns new value
+        /// </summary>
+        public  int Add(int x_0) {"#
+    ));
+}
+
+#[test]
+fn test_java_panama() {
+    let _ = env_logger::try_init();
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let output_dir = tmp_dir.path().join("java");
+    fs::create_dir_all(&output_dir).unwrap();
+    let swig_gen = Generator::new(LanguageConfig::JavaPanamaConfig(JavaPanamaConfig::new(
+        output_dir.clone(),
+        "org.example".into(),
+        "example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &rust_src_path,
+        r#"
+foreign_enum!(enum Mood { Happy = Mood::Happy, Sad = Mood::Sad, });
+foreign_class!(class Counter {
+    self_type Counter;
+    constructor Counter::new(start: i32) -> Counter;
+    fn Counter::add(&mut self, x: u32);
+    fn Counter::name(&self, prefix: &str) -> String;
+    fn Counter::mood(&self) -> Mood;
+});
+"#,
+    )
+    .unwrap();
+    let rust_out_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand("java_panama", &rust_src_path, &rust_out_path);
+
+    let rust_code = fs::read_to_string(&rust_out_path).unwrap();
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains(r#"pub extern "C" fn Counter_new"#));
+    assert!(rust_code.contains(r#"pub unsafe extern "C" fn Counter_delete"#));
+    assert!(rust_code.contains(r#"extern "C" fn panama_take_last_error"#));
+    assert!(rust_code.contains("return PanamaInvalidValue :: panama_invalid_value ()"));
+    assert!(!rust_code.contains("panic !"));
+    assert!(!rust_code.contains(". expect ("));
+
+    let counter = fs::read_to_string(output_dir.join("Counter.java")).unwrap();
+    println!("Counter.java: {}", counter);
+    assert!(counter.contains("package org.example;"));
+    assert!(counter.contains("public final class Counter implements AutoCloseable"));
+    assert!(counter.contains("reinterpret(arena, Counter::delete)"));
+    assert!(counter.contains(
+        r#"RustLib.downcall("Counter_new",
+        FunctionDescriptor.of(ValueLayout.ADDRESS, ValueLayout.JAVA_INT));"#
+    ));
+    assert!(counter.contains("public void add(/* u32 */ long x)"));
+    assert!(counter.contains("try (Arena __arena = Arena.ofConfined())"));
+    assert!(counter.contains("Reference.reachabilityFence(this);"));
+    assert!(counter.contains(
+        r#"MemorySegment __ret = (MemorySegment) Counter_new.invokeExact(start);
+            RustLib.checkError();
+            return __ret;"#
+    ));
+    assert!(counter.contains(
+        r#"Counter_add.invokeExact(this.nativePtr, x);
+            RustLib.checkError();"#
+    ));
+
+    let mood = fs::read_to_string(output_dir.join("Mood.java")).unwrap();
+    assert!(mood.contains("public enum Mood"));
+    assert!(!mood.contains("import java.lang.foreign"));
+    let rust_lib = fs::read_to_string(output_dir.join("RustLib.java")).unwrap();
+    assert!(rust_lib.contains(r#"System.loadLibrary("example");"#));
+    assert!(rust_lib.contains("/*package*/ static void checkError() {"));
+}
+
+#[test]
+fn test_c_api() {
+    let _ = env_logger::try_init();
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let output_dir = tmp_dir.path().join("include");
+    fs::create_dir_all(&output_dir).unwrap();
+    let swig_gen = Generator::new(LanguageConfig::CConfig(
+        CConfig::new(output_dir.clone(), "example".into()).symbol_prefix("ex_".into()),
+    ))
+    .with_pointer_target_width(64);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &rust_src_path,
+        r#"
+foreign_enum!(enum Mood { Happy = Mood::Happy, Sad = Mood::Sad, });
+foreign_class!(
+/// Simple counter
+class Counter {
+    self_type Counter;
+    constructor Counter::new(start: i32) -> Counter;
+    fn Counter::add(&mut self, x: u32);
+    fn Counter::name(&self, prefix: &str) -> String;
+    fn Counter::mood(&self) -> Mood;
+    fn Counter::merge(&self, other: Counter) -> Counter;
+});
+"#,
+    )
+    .unwrap();
+    let rust_out_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand("c_api", &rust_src_path, &rust_out_path);
+
+    let rust_code = fs::read_to_string(&rust_out_path).unwrap();
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains(r#"pub unsafe extern "C" fn ex_Counter_new"#));
+    assert!(rust_code.contains(r#"pub unsafe extern "C" fn ex_Counter_free"#));
+    assert!(rust_code.contains(r#"pub unsafe extern "C" fn ex_string_free"#));
+    assert!(rust_code.contains(r#"pub extern "C" fn ex_take_last_error"#));
+    assert!(!rust_code.contains("panic!"));
+    assert!(!rust_code.contains(".expect("));
+    assert!(!rust_code.contains("assert!"));
+
+    let common = fs::read_to_string(output_dir.join("ex_common.h")).unwrap();
+    println!("ex_common.h: {}", common);
+    assert!(common.contains("#ifndef EX_COMMON_H"));
+    assert!(common.contains("#include <stdint.h>"));
+    assert!(common.contains("/// Simple counter\ntypedef struct ex_Counter ex_Counter;"));
+    assert!(common.contains(
+        "typedef int32_t ex_Mood;\nenum {\n    ex_Mood_Happy = 0,\n    ex_Mood_Sad = 1\n};"
+    ));
+    assert!(common.contains("void ex_string_free(char *s);"));
+    assert!(common.contains(
+        "/// Ownership: caller owns returned string, it should be freed by `ex_string_free`\n\
+         char *ex_take_last_error(void);"
+    ));
+
+    let counter = fs::read_to_string(output_dir.join("ex_Counter.h")).unwrap();
+    println!("ex_Counter.h: {}", counter);
+    assert!(counter.contains("#include \"ex_common.h\""));
+    assert!(counter.contains(
+        "/// Ownership: caller owns returned value, it should be freed by `ex_Counter_free`\n\
+         ex_Counter *ex_Counter_new(int32_t start);"
+    ));
+    assert!(counter.contains("void ex_Counter_add(ex_Counter *self /* borrowed */, uint32_t x);"));
+    assert!(counter.contains(
+        "/// Ownership: caller owns returned value, it should be freed by `ex_string_free`\n\
+         char *ex_Counter_name(const ex_Counter *self /* borrowed */, const char *prefix /* borrowed */);"
+    ));
+    assert!(counter.contains("ex_Mood ex_Counter_mood(const ex_Counter *self /* borrowed */);"));
+    assert!(counter.contains("ex_Counter *other /* owned, consumed by the call */"));
+    assert!(counter.contains("void ex_Counter_free(ex_Counter *self);"));
+
+    let umbrella = fs::read_to_string(output_dir.join("example.h")).unwrap();
+    assert!(umbrella.contains("#include \"ex_common.h\"\n#include \"ex_Counter.h\"\n"));
+}
+
+#[test]
+fn test_cpp_c_symbol_prefix() {
+    let _ = env_logger::try_init();
+    let name = "cpp_c_symbol_prefix";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::name(&self) -> String;
+    fn Foo::clone(&self) -> Foo;
+    fn Foo::list() -> Vec<Foo>;
+    fn Foo::pair(&self) -> (i32, String);
+});
+foreign_callback!(callback Observer {
+    self_type Observer;
+    onStateChanged = Observer::on_state_changed(&self, x: i32);
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.c_symbol_prefix("mylib_".into())
+    })
+    .unwrap();
+    println!("rust_code: {}", code.rust_code);
+    println!("cpp_code: {}", code.foreign_code);
+    for sym in &[
+        "mylib_Foo_new",
+        "mylib_Foo_delete",
+        "mylib_Foo_name",
+        "mylib_crust_string_free",
+        "mylib_C_Observer",
+        "mylib_RustForeignVecFoo_free",
+    ] {
+        assert!(code.rust_code.contains(sym), "no {} in Rust code", sym);
+        assert!(code.foreign_code.contains(sym), "no {} in C++ code", sym);
+    }
+    // Rust names of C types are not changed, only exported symbols
+    assert!(code
+        .rust_code
+        .contains(r#"# [export_name = "mylib_crust_string_free"] pub extern "C" fn crust_string_free (x : CRustString)"#));
+    assert!(code
+        .foreign_code
+        .contains("typedef struct mylib_FooOpaque mylib_FooOpaque;"));
+    assert!(code.foreign_code.contains("struct mylib_CRustString {"));
+    assert!(code
+        .foreign_code
+        .contains("void mylib_crust_string_free(struct mylib_CRustString x);"));
+    assert!(code
+        .foreign_code
+        .contains("struct mylib_CRustString mylib_Foo_name(const mylib_FooOpaque * const self);"));
+    // C++ code uses names without prefix inside of namespace
+    assert!(code.foreign_code.contains(
+        "namespace org_examples {\nusing CRustString = struct mylib_CRustString;\n} // namespace org_examples"
+    ));
+    assert!(code.foreign_code.contains(
+        "inline void crust_string_free(struct mylib_CRustString x) { return ::mylib_crust_string_free(x); }"
+    ));
+    assert!(code.foreign_code.contains("#include \"rust_tuplei32CRustString.h\""));
+    for sym in &[
+        " Foo_new(",
+        " Foo_delete(",
+        " FooOpaque",
+        " C_Observer",
+        "mylib_mylib_",
+        "i32mylib_",
+    ] {
+        assert!(!code.rust_code.contains(sym), "{} in Rust code", sym);
+        assert!(!code.foreign_code.contains(sym), "{} in C++ code", sym);
+    }
+    assert!(!code.rust_code.contains("# [no_mangle] pub extern \"C\" fn crust_"));
+    for sym in &["struct CRustString", "\nvoid crust_string_free("] {
+        assert!(!code.foreign_code.contains(sym), "{} in C++ code", sym);
+    }
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.c_symbol_prefix_from_namespace()
+    })
+    .unwrap();
+    assert!(code.rust_code.contains("org_examples_Foo_new"));
+    assert!(code.foreign_code.contains("struct org_examples_CRustString"));
+}
+
+#[test]
+fn test_cpp_result_as_exception() {
+    let _ = env_logger::try_init();
+    let name = "cpp_result_as_exception";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self) -> Result<i32, String>;
+    fn Foo::g(&mut self) -> Result<(), String>;
+    fn Foo::create(_: i32) -> Result<Foo, String>;
+    fn Foo::h(&self) -> i32;
+    fn Foo::level(&self) -> Level;
+});
+foreign_typemap!(
+    ($p:r_type) Level => i32 {
+        $out = $p.0;
+    };
+    ($p:f_type) => "/*Level*/int32_t" "$p /* can not throw */";
+);
+"#;
+    let code =
+        parse_cpp_code_with_config(name, Source::Str(src), |cfg| cfg.use_exceptions(true)).unwrap();
+    println!("rust_code: {}", code.rust_code);
+    println!("cpp_code: {}", code.foreign_code);
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    assert!(rust_code.contains("let msg = CRustString::from_string(err.to_string());"));
+    assert!(code
+        .foreign_code
+        .contains("template <typename E> class RustError : public std::runtime_error {"));
+    assert!(code.foreign_code.contains("#include \"rust_error.hpp\""));
+    assert!(code
+        .foreign_code
+        .contains("/*throws RustError<RustString>*/ int32_t f() const;"));
+    assert!(code
+        .foreign_code
+        .contains("/*throws RustError<RustString>*/ void g();"));
+    assert!(code
+        .foreign_code
+        .contains("static /*throws RustError<RustString>*/ Foo create(int32_t a0);"));
+    assert!(code.foreign_code.contains("int32_t h() const noexcept;"));
+    assert!(code.foreign_code.contains("/*Level*/int32_t level() const noexcept;"));
+    assert!(!code.foreign_code.contains("std::variant"));
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    assert!(!code.foreign_code.contains("RustError"));
+    assert!(code
+        .foreign_code
+        .contains("std::variant<int32_t, RustString> f() const noexcept;"));
+}
+
+#[test]
+fn test_cpp_mut_slice_and_result_arg() {
+    let _ = env_logger::try_init();
+    let name = "cpp_mut_slice_and_result_arg";
+    let src = r#"
+foreign_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::data_mut(&mut self) -> &mut [i32];
+    fn Foo::set(&mut self, r: Result<i32, Boo>);
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| cfg).unwrap();
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    assert!(rust_code.contains(
+        "let mut ret: CRustSliceMuti32 = CRustSliceMuti32 {
+        data: ret.as_mut_ptr(),"
+    ));
+    // error should be converted as `Boo`, not as `i32`
+    assert!(rust_code.contains(
+        "        } else {
+            assert!(!r.data.err.is_null());
+            let x: *mut Boo = r.data.err as *mut Boo;
+            let x: Box<Boo> = unsafe { Box::from_raw(x) };
+            let x: Boo = *x;
+            Err(x)
+        }"
+    ));
+}
+
+#[test]
+fn test_cpp20_cpp23_types() {
+    let _ = env_logger::try_init();
+    let name = "cpp20_cpp23_types";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self) -> Result<i32, String>;
+    fn Foo::g(&self) -> Result<(), String>;
+    fn Foo::sum(&self, x: &[i32]) -> i32;
+    fn Foo::data_mut(&mut self) -> &mut [i32];
+    fn Foo::name(&self) -> &str;
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.cpp_variant(CppVariant::Std23Expected)
+            .cpp_slice(CppSlice::Std20)
+            .cpp_str_view(CppStrView::Std20U8)
+    })
+    .unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    for decl in &[
+        "std::expected<int32_t, RustString> f() const noexcept;",
+        "std::expected<void, RustString> g() const noexcept;",
+        "int32_t sum(std::span<const int32_t> x) const noexcept;",
+        "std::span<int32_t> data_mut() noexcept;",
+        "std::u8string_view name() const noexcept;",
+        "#include <expected>",
+        "#include <span>",
+        "std::u8string_view to_u8string_view() const",
+    ] {
+        assert!(code.foreign_code.contains(decl), "no {}", decl);
+    }
+    assert!(code.foreign_code.contains(
+        "std::expected<int32_t, RustString> { std::unexpect, RustString{ret.data.err} }"
+    ));
+    assert!(!code.foreign_code.contains("RustSlice<"));
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.cpp_variant(CppVariant::TlExpected)
+    })
+    .unwrap();
+    assert!(code
+        .foreign_code
+        .contains("tl::expected<int32_t, RustString> f() const noexcept;"));
+    assert!(code.foreign_code.contains("#include <tl/expected.hpp>"));
+    assert!(code
+        .foreign_code
+        .contains("RustSlice<const int32_t> x) const noexcept;"));
+}
+
+#[test]
+fn test_cpp_maps() {
+    let _ = env_logger::try_init();
+    let name = "cpp_maps";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    fn Foo::names(&self) -> HashMap<String, u32>;
+    fn Foo::sorted(&self) -> BTreeMap<i32, f64>;
+    fn Foo::sum(&self, m: HashMap<u32, u32>) -> u32;
+    fn Foo::counters(&self) -> &HashMap<u32, u64>;
+    fn Foo::ordered(&self) -> &BTreeMap<i32, u8>;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    for decl in &[
+        "std::unordered_map<RustString, uint32_t> names() const noexcept;",
+        "std::map<int32_t, double> sorted() const noexcept;",
+        "uint32_t sum(std::unordered_map<uint32_t, uint32_t> m) const noexcept;",
+        "RustHashMapu32u64 counters() const noexcept;",
+        "RustBTreeMapi32u8 ordered() const noexcept;",
+        "CRustMapCRustStringu32_free(ret);",
+        "#include <unordered_map>",
+        "#include <map>",
+        "#include \"rust_map_impl.hpp\"",
+        "template <> struct hash<org_examples::RustString>",
+    ] {
+        assert!(code.foreign_code.contains(decl), "no {}", decl);
+    }
+    let rust_code = rustfmt_without_errors(code.rust_code);
+    println!("rust_code: {}", rust_code);
+    for rust_decl in &[
+        "pub extern \"C\" fn CRustMapCRustStringu32_free(m: CRustMapCRustStringu32)",
+        "pub extern \"C\" fn CRustHashMapViewu32u64_find(",
+        "pub extern \"C\" fn CRustBTreeMapViewi32u8_iter_next(",
+    ] {
+        assert!(rust_code.contains(rust_decl), "no {}", rust_decl);
+    }
+}
+
+#[test]
+fn test_cpp_cmake_package() {
+    let _ = env_logger::try_init();
+    let name = "cpp_cmake_package";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self) -> Option<i32>;
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.cmake_package("my_lib".into())
+    })
+    .unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    for pat in &[
+        r#"if (CMAKE_VERSION VERSION_LESS 3.8)
+  message(FATAL_ERROR "my_lib requires at least CMake 3.8")
+endif ()"#,
+        r#"if (NOT TARGET my_lib::my_lib)
+  add_library(my_lib::my_lib INTERFACE IMPORTED)
+  set_target_properties(my_lib::my_lib PROPERTIES
+    INTERFACE_INCLUDE_DIRECTORIES ""#,
+        r#"INTERFACE_COMPILE_FEATURES "cxx_std_17")"#,
+        r#"  if (my_lib_RUST_LIBRARY)
+    set_property(TARGET my_lib::my_lib APPEND PROPERTY INTERFACE_LINK_LIBRARIES "${my_lib_RUST_LIBRARY}")
+  endif ()
+endif ()"#,
+    ] {
+        assert!(code.foreign_code.contains(pat), "no {}", pat);
+    }
+    assert!(!code.foreign_code.contains("Boost"));
+    assert!(!code.foreign_code.contains("cmake_minimum_required"));
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.use_boost().cmake_package("my_lib".into())
+    })
+    .unwrap();
+    for line in &[
+        "find_dependency(Boost 1.66.0)",
+        "INTERFACE_COMPILE_FEATURES \"cxx_std_11\"",
+        "APPEND PROPERTY INTERFACE_LINK_LIBRARIES Boost::boost)",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.cpp_variant(CppVariant::Std23Expected)
+            .cmake_package("my_lib".into())
+    })
+    .unwrap();
+    assert!(code
+        .foreign_code
+        .contains("if (CMAKE_VERSION VERSION_LESS 3.20)"));
+    assert!(code
+        .foreign_code
+        .contains("INTERFACE_COMPILE_FEATURES \"cxx_std_23\""));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    assert!(!code.foreign_code.contains("CMAKE_VERSION"));
+}
+
+#[test]
+fn test_cpp_single_header() {
+    let _ = env_logger::try_init();
+    let name = "cpp_single_header";
+    let src = r#"
+foreign_class!(class A {
+    self_type A;
+    constructor A::default() -> A;
+    fn A::a(b: &B) -> i32;
+    fn A::make_b(&self) -> B;
+});
+foreign_class!(class B {
+    self_type B;
+    constructor B::default() -> B;
+    fn B::b(a: &A) -> i32;
+    fn B::make_a(&self) -> A;
+    fn B::name(&self) -> String;
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.single_header("my_lib.hpp".into())
+    })
+    .unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    let cpp_code = &code.foreign_code;
+    assert_eq!(1, cpp_code.matches("#pragma once").count());
+    assert!(!cpp_code.contains("#include \""));
+    assert_eq!(1, cpp_code.matches("class RustString final").count());
+
+    let pos = |line: &str| cpp_code.find(line).unwrap_or_else(|| panic!("no {}", line));
+    assert!(pos("typedef struct BOpaque BOpaque;") < pos("BOpaque * A_make_b("));
+    assert!(pos("struct CRustString {") < pos("CRustString B_name("));
+    assert!(pos("CRustString B_name(") < pos("class AWrapper {"));
+    assert!(pos("class AWrapper {") < pos("inline A BWrapper<OWN_DATA>::make_a()"));
+    assert!(pos("class BWrapper {") < pos("inline B AWrapper<OWN_DATA>::make_b()"));
+}
+
+#[test]
+fn test_cpp_separate_impl_sources() {
+    let _ = env_logger::try_init();
+    let name = "cpp_separate_impl_sources";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::f(&self, x: i32) -> i32;
+    fn Foo::set(&mut self, x: i32);
+    fn Foo::g(x: i32) -> i32;
+});
+"#;
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.separate_impl_sources(true)
+            .cmake_package("my_lib".into())
+    })
+    .unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    for line in &[
+        "extern template int32_t FooWrapper<true>::f(int32_t x) const noexcept;",
+        "extern template int32_t FooWrapper<false>::f(int32_t x) const noexcept;",
+        "extern template void FooWrapper<true>::set(int32_t x) noexcept;",
+        "extern template int32_t FooWrapper<false>::g(int32_t x) noexcept;",
+        "#include \"Foo.hpp\"",
+        "    int32_t FooWrapper<OWN_DATA>::f(int32_t x) const noexcept",
+        "    template int32_t FooWrapper<false>::f(int32_t x) const noexcept;",
+        "    template void FooWrapper<true>::set(int32_t x) noexcept;",
+        "APPEND PROPERTY INTERFACE_SOURCES",
+        "/Foo.cpp\")",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+    assert!(!code.foreign_code.contains("FooWrapper<false>::set"));
+    assert!(!code
+        .foreign_code
+        .contains("inline int32_t FooWrapper<OWN_DATA>::f"));
+
+    let code = parse_cpp_code_with_config(name, Source::Str(src), |cfg| {
+        cfg.separate_impl_sources(true)
+            .single_header("my_lib.hpp".into())
+    })
+    .unwrap();
+    assert!(code.foreign_code.contains("#include \"my_lib.hpp\""));
+    assert!(code
+        .foreign_code
+        .contains("extern template int32_t FooWrapper<true>::f(int32_t x) const noexcept;"));
+}
+
+#[test]
+fn test_cpp_separate_impl_sources_compile() {
+    let _ = env_logger::try_init();
+    let name = "cpp_separate_impl_sources_compile";
+    let src = r#"
+foreign_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    fn Foo::set(&mut self, x: i32);
+    fn Foo::parse(&self, x: &str) -> Result<i32, String>;
+    fn Foo::check(&self) -> Result<String, String>;
+});
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let out_dir = tmp_dir.path();
+    fs::write(out_dir.join("user.cpp"), "int user_code() { return 0; }\n").unwrap();
+    fs::write(
+        out_dir.join("Old.cpp"),
+        "// Automatically generated by flapigen\nint old_code() { return 0; }\n",
+    )
+    .unwrap();
+    let src_path = out_dir.join("src.rs");
+    fs::write(&src_path, src).unwrap();
+    let swig_gen = Generator::new(LanguageConfig::CppConfig(
+        CppConfig::new(out_dir.into(), "org_examples".into())
+            .cpp_variant(CppVariant::Std17)
+            .cpp_str_view(CppStrView::Std17)
+            .separate_impl_sources(true),
+    ))
+    .with_pointer_target_width(64)
+    .remove_not_generated_files_from_output_directory(true);
+    swig_gen.expand(name, src_path, out_dir.join("test.rs"));
+
+    assert!(out_dir.join("user.cpp").exists());
+    assert!(!out_dir.join("Old.cpp").exists());
+    let header = fs::read_to_string(out_dir.join("Foo.hpp")).unwrap();
+    let source = fs::read_to_string(out_dir.join("Foo.cpp")).unwrap();
+    println!("header: {}\nsource: {}", header, source);
+    // `std::variant<RustString, RustString>` can not be instantiated, so method stays in header
+    assert!(header.contains(
+        "inline std::variant<RustString, RustString> FooWrapper<OWN_DATA>::check() const noexcept"
+    ));
+    assert!(!source.contains("check"));
+    assert!(source.contains(
+        "template std::variant<int32_t, RustString> FooWrapper<true>::parse(std::string_view x) const noexcept;"
+    ));
+
+    let cxx = std::env::var("CXX").unwrap_or_else(|_| "c++".into());
+    if std::process::Command::new(&cxx)
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        println!("{} not found, skip compilation of generated code", cxx);
+        return;
+    }
+    fs::write(
+        out_dir.join("main.cpp"),
+        r#"#include "Foo.hpp"
+int main() {
+    org_examples::Foo foo(1);
+    foo.set(2);
+    return foo.parse("2").index();
+}
+"#,
+    )
+    .unwrap();
+    for tu in &["main.cpp", "Foo.cpp"] {
+        let status = std::process::Command::new(&cxx)
+            .args(["-std=c++17", "-Wall", "-Werror", "-c", tu])
+            .current_dir(out_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "compilation of {} failed", tu);
+    }
+}
+
+#[test]
+fn test_foreign_bitflags() {
+    let _ = env_logger::try_init();
+    let name = "foreign_bitflags";
+    let src = r#"
+foreign_bitflags!(
+/// File permissions
+struct Perms {
+    Read = Perms::READ,
+    Write = Perms::WRITE,
+    Exec = Perms::EXEC,
+    ReadWrite = Perms::READ_WRITE,
+});
+foreign_class!(class Fs {
+    self_type Fs;
+    constructor Fs::default() -> Fs;
+    fn Fs::set_perms(&mut self, p: Perms);
+    fn Fs::perms(&self) -> Perms;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("cpp_code: {}", code.foreign_code);
+    for line in &[
+        "uint32_t Perms_item_bits(uint32_t item);",
+        "class Perms final {",
+        "static Perms Read() noexcept { return Perms(Perms_item_bits(0), 0); }",
+        "static Perms ReadWrite() noexcept { return Perms(Perms_item_bits(3), 0); }",
+        "static Perms all() noexcept { return Perms(Read().bits_ | Write().bits_ | Exec().bits_ | ReadWrite().bits_, 0); }",
+        "static bool from_bits(uint32_t bits, Perms &flags) noexcept",
+        "return Perms::from_bits_truncate(ret);",
+        "void set_perms(Perms p) noexcept;",
+        "Perms perms() const noexcept;",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+    println!("rust_code: {}", code.rust_code);
+    for line in &[
+        "pub extern \"C\" fn Perms_item_bits (item : u32) -> u32",
+        "3 => Perms :: READ_WRITE . bits () as u32 ,",
+        "Perms :: from_bits (x as _) . filter",
+        "const _ : () = assert ! (:: std :: mem :: size_of :: < Perms > () <= 4 , \
+         \"foreign_bitflags!(Perms): flags wider than 32 bits are not supported\") ;",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("java_code: {}", code.foreign_code);
+    for line in &[
+        "public enum Perms {",
+        "    ReadWrite;",
+        "private static native int do_itemBits(int item);",
+        "BITS[i] = do_itemBits(i);",
+        "public static java.util.EnumSet<Perms> fromBits(int bits)",
+        "throw new IllegalArgumentException(\"Unknown bits for Perms: \" + bits);",
+        "int a0 = Perms.toBits(p);",
+        "java.util.EnumSet<Perms> convRet = Perms.fromBits(ret);",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+    println!("rust_code: {}", code.rust_code);
+    for line in &[
+        "fn Java_org_example_Perms_do_1itemBits (_ : * mut JNIEnv , _ : jclass , item : jint) -> jint",
+        "jni_throw_exception (env , & format ! (\"Unknown bits for flags: {}\" , p)) ;",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+    assert!(code.rust_code.contains("fn as_i32 (& self) -> i32 { self . bits () as i32 }"));
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::CConfig(CConfig::new(
+        tmp_dir.path().into(),
+        "example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[".h"]).unwrap();
+    println!("c_code: {}", code.foreign_code);
+    for line in &[
+        "typedef int32_t example_Perms;",
+        "example_Perms example_Perms_ReadWrite(void);",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+    assert!(code.rust_code.contains("c_api_set_last_error"));
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::PythonConfig(PythonConfig::new(
+        "example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[]).unwrap();
+    println!("python rust_code: {}", code.rust_code);
+    for line in &[
+        ". get (py , \"IntFlag\") ?",
+        "(\"ReadWrite\" , super :: Perms :: READ_WRITE . bits () as u32)",
+        "m . add (py , \"Perms\" , py_perms :: create_class (py) ?) ? ;",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+    assert!(code.rust_code.contains(":: std :: mem :: size_of :: < Perms > () <= 4"));
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::DotNetConfig(DotNetConfig::new(
+        "example".into(),
+        tmp_dir.path().into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[".cs"]).unwrap();
+    println!("cs_code: {}", code.foreign_code);
+    for line in &[
+        "public struct Perms : System.IEquatable<Perms> {",
+        "private static extern uint Perms_item_bits(uint item);",
+        "public static readonly Perms ReadWrite = new Perms(Perms_item_bits(3));",
+    ] {
+        assert!(code.foreign_code.contains(line), "no {}", line);
+    }
+    assert!(code.rust_code.contains(":: std :: mem :: size_of :: < Perms > () <= 4"));
+}
+
+#[test]
+fn test_python_foreign_typemap() {
+    let _ = env_logger::try_init();
+    let name = "python_foreign_typemap";
+    let src = r#"
+foreign_typemap!(
+    ($p:r_type) Uuid => String {
+        $out = $p.to_string();
+    };
+    ($p:r_type) Uuid <= String {
+        $out = Uuid::parse_str(&$p).unwrap();
+    };
+    ($p:f_type, unique_prefix = "/*Uuid*/") => "/*Uuid*/String";
+    ($p:f_type, unique_prefix = "/*Uuid*/") <= "/*Uuid*/String";
+);
+foreign_typemap!(
+    ($p:r_type) Duration => u64 {
+        $out = $p.as_millis() as u64;
+    };
+    ($p:r_type) Duration <= u64 {
+        $out = Duration::from_millis($p);
+    };
+    ($p:f_type) => "f64" "$out = $p as f64 / 1000.0;";
+    ($p:f_type) <= "f64" "$out = ($p * 1000.0) as u64;";
+);
+foreign_class!(class Session {
+    fn session_id(timeout: Duration) -> Uuid;
+    fn session_timeout(id: &Uuid) -> Option<Duration>;
+});
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::PythonConfig(PythonConfig::new(
+        "example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let code = expand_code(name, Source::Str(src), tmp_dir, swig_gen, &[]).unwrap();
+    println!("rust_code: {}", code.rust_code);
+    for line in &[
+        "def session_id (timeout : f64) -> cpython :: PyResult < String >",
+        "let mut timeout_tmp : u64 = (timeout * 1000.0) as u64 ;",
+        "let mut timeout_rust : Duration = Duration :: from_millis (timeout_tmp) ;",
+        "let mut ret_tmp : String = ret_rust . to_string () ;",
+        "def session_timeout (id : String) -> cpython :: PyResult < Option < f64 > >",
+        "let mut id_rust : Uuid = Uuid :: parse_str (& id) . unwrap () ;",
+        "let mut ret_py : f64 = ret_tmp as f64 / 1000.0 ;",
+    ] {
+        assert!(code.rust_code.contains(line), "no {}", line);
+    }
+}
+
 fn find_subsequence<T>(haystack: &[T], needle: &[T]) -> Option<usize>
 where
     for<'a> &'a [T]: PartialEq,
@@ -587,27 +2233,53 @@ enum Source<'a> {
 }
 
 fn parse_code(test_name: &str, rust_src: Source, lang: ForeignLang) -> Result<CodePair, Error> {
+    match lang {
+        ForeignLang::Java => parse_java_code_with_config(test_name, rust_src, |cfg| cfg),
+        ForeignLang::Cpp => parse_cpp_code_with_config(test_name, rust_src, |cfg| cfg),
+    }
+}
+
+fn parse_java_code_with_config(
+    test_name: &str,
+    rust_src: Source,
+    tune_config: impl FnOnce(JavaConfig) -> JavaConfig,
+) -> Result<CodePair, Error> {
     let tmp_dir = tempdir().expect("Can not create tmp directory");
-    let (swig_gen, ext_list): (Generator, &[&'static str]) = match lang {
-        ForeignLang::Java => {
-            let swig_gen = Generator::new(LanguageConfig::JavaConfig(
-                JavaConfig::new(tmp_dir.path().into(), "org.example".into())
-                    .use_null_annotation_from_package("android.support.annotation".into()),
-            ))
-            .with_pointer_target_width(64);
-
-            (swig_gen, &[".java"])
-        }
-        ForeignLang::Cpp => {
-            let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
-                tmp_dir.path().into(),
-                "org_examples".into(),
-            )))
-            .with_pointer_target_width(64);
-            (swig_gen, &[".h", ".hpp"])
-        }
-    };
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(tune_config(
+        JavaConfig::new(tmp_dir.path().into(), "org.example".into())
+            .use_null_annotation_from_package("android.support.annotation".into()),
+    )))
+    .with_pointer_target_width(64);
+    expand_code(test_name, rust_src, tmp_dir, swig_gen, &[".java", ".pro"])
+}
+
+fn parse_cpp_code_with_config(
+    test_name: &str,
+    rust_src: Source,
+    tune_config: impl FnOnce(CppConfig) -> CppConfig,
+) -> Result<CodePair, Error> {
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::CppConfig(tune_config(CppConfig::new(
+        tmp_dir.path().into(),
+        "org_examples".into(),
+    ))))
+    .with_pointer_target_width(64);
+    expand_code(
+        test_name,
+        rust_src,
+        tmp_dir,
+        swig_gen,
+        &[".h", ".hpp", ".cpp", ".cmake"],
+    )
+}
 
+fn expand_code(
+    test_name: &str,
+    rust_src: Source,
+    tmp_dir: TempDir,
+    swig_gen: Generator,
+    ext_list: &[&str],
+) -> Result<CodePair, Error> {
     let rust_code_path = tmp_dir.path().join("test.rs");
     match rust_src {
         Source::Path(rust_src_path) => swig_gen.expand(test_name, rust_src_path, &rust_code_path),
@@ -4,6 +4,8 @@ mod jni {
     use jni_sys::*;
 
     include!(concat!(env!("OUT_DIR"), "/jni-include.rs"));
+    include!(concat!(env!("OUT_DIR"), "/jni-java-time-include.rs"));
+    include!(concat!(env!("OUT_DIR"), "/jni-java-array-include.rs"));
 }
 
 mod cpp {